@@ -0,0 +1,140 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::{
+    mime_types::{BCS, JSON},
+    Error,
+};
+
+use warp::{
+    filters::BoxedFilter,
+    http::{header::ACCEPT, StatusCode},
+    reject, Filter,
+};
+
+/// Response encoding negotiated from the request `Accept` header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AcceptType {
+    Json,
+    Bcs,
+}
+
+impl AcceptType {
+    /// BCS is only returned when explicitly requested with a quality value at least as high as the
+    /// one of JSON, JSON is the default for anything else. A request refusing JSON with `q=0`
+    /// without accepting BCS is rejected.
+    fn from_header(accept: Option<String>) -> Result<Self, Error> {
+        let accept = match accept {
+            Some(accept) => accept,
+            None => return Ok(AcceptType::Json),
+        };
+
+        // The quality value of the most specific media range matching each encoding
+        let mut json: Option<(u8, f32)> = None;
+        let mut bcs: Option<f32> = None;
+        for media_range in accept.split(',') {
+            let mut parts = media_range.split(';');
+            let media_type = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .map(|q| q.trim().parse::<f32>().unwrap_or(1.0))
+                .next()
+                .unwrap_or(1.0);
+
+            let json_specificity = match media_type.as_str() {
+                JSON => Some(2),
+                "application/*" => Some(1),
+                "*/*" => Some(0),
+                _ => None,
+            };
+            if let Some(specificity) = json_specificity {
+                if json.map_or(true, |(current, _)| specificity > current) {
+                    json = Some((specificity, quality));
+                }
+            }
+            if media_type == BCS {
+                bcs = Some(quality);
+            }
+        }
+
+        let json = json.map(|(_, quality)| quality);
+        match (bcs, json) {
+            (Some(bcs), json) if bcs > 0.0 && bcs >= json.unwrap_or(0.0) => Ok(AcceptType::Bcs),
+            (_, Some(json)) if json <= 0.0 => Err(Error::new(
+                StatusCode::NOT_ACCEPTABLE,
+                format!(
+                    "none of the requested media types are supported: {}",
+                    accept
+                ),
+            )),
+            _ => Ok(AcceptType::Json),
+        }
+    }
+}
+
+pub(crate) fn accept_type() -> BoxedFilter<(AcceptType,)> {
+    warp::header::optional::<String>(ACCEPT.as_str())
+        .and_then(|accept| async move {
+            AcceptType::from_header(accept).map_err(reject::custom::<Error>)
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AcceptType;
+
+    #[test]
+    fn test_from_header() {
+        assert_eq!(AcceptType::from_header(None).unwrap(), AcceptType::Json);
+        assert_eq!(
+            AcceptType::from_header(Some("application/json".to_owned())).unwrap(),
+            AcceptType::Json
+        );
+        assert_eq!(
+            AcceptType::from_header(Some("*/*".to_owned())).unwrap(),
+            AcceptType::Json
+        );
+        assert_eq!(
+            AcceptType::from_header(Some("application/x-bcs".to_owned())).unwrap(),
+            AcceptType::Bcs
+        );
+        assert_eq!(
+            AcceptType::from_header(Some("application/json, application/x-bcs".to_owned()))
+                .unwrap(),
+            AcceptType::Bcs
+        );
+    }
+
+    #[test]
+    fn test_from_header_quality_values() {
+        assert_eq!(
+            AcceptType::from_header(Some("application/json, application/x-bcs;q=0.9".to_owned()))
+                .unwrap(),
+            AcceptType::Json
+        );
+        assert_eq!(
+            AcceptType::from_header(Some("application/json;q=0.5, application/x-bcs".to_owned()))
+                .unwrap(),
+            AcceptType::Bcs
+        );
+        assert_eq!(
+            AcceptType::from_header(Some("application/x-bcs;q=0".to_owned())).unwrap(),
+            AcceptType::Json
+        );
+        assert_eq!(
+            AcceptType::from_header(Some("*/*;q=0.1, application/x-bcs;q=0.2".to_owned())).unwrap(),
+            AcceptType::Bcs
+        );
+        assert_eq!(
+            AcceptType::from_header(Some("application/json;q=0, application/x-bcs".to_owned()))
+                .unwrap(),
+            AcceptType::Bcs
+        );
+        assert_eq!(
+            AcceptType::from_header(Some("*/*;q=0, application/json;q=0.3".to_owned())).unwrap(),
+            AcceptType::Json
+        );
+        assert!(AcceptType::from_header(Some("application/json;q=0".to_owned())).is_err());
+    }
+}
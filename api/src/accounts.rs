@@ -2,16 +2,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    accept_type::{accept_type, AcceptType},
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
     param::{AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam},
+    rate_limit::rate_limit,
     version::Version,
 };
 
 use aptos_api_types::{
-    AccountData, Address, AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response,
-    TransactionId,
+    AccountData, Address, AsConverter, Error, LedgerInfo, MoveModuleAbi, MoveModuleBytecode,
+    Response, TransactionId,
 };
 use aptos_types::{
     account_config::AccountResource,
@@ -27,13 +29,15 @@ use move_deps::move_core_types::{
     move_resource::MoveStructType,
     value::MoveValue,
 };
-use std::convert::TryInto;
+use std::{collections::BTreeMap, convert::TryInto};
 use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 
 // GET /accounts/<address>
 pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam)
         .and(warp::get())
+        .and(accept_type())
+        .and(rate_limit("get_account", &context))
         .and(context.filter())
         .and_then(handle_get_account)
         .with(metrics("get_account"))
@@ -44,9 +48,13 @@ pub fn get_account(context: Context) -> BoxedFilter<(impl Reply,)> {
 pub fn get_account_resources(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "resources")
         .and(warp::get())
+        .and(accept_type())
+        .and(rate_limit("get_account_resources", &context))
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .map(|address, accept_type, ctx, version: Version| {
+            (version.version, address, accept_type, ctx)
+        })
         .untuple_one()
         .and_then(handle_get_account_resources)
         .with(metrics("get_account_resources"))
@@ -57,39 +65,69 @@ pub fn get_account_resources(context: Context) -> BoxedFilter<(impl Reply,)> {
 pub fn get_account_modules(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "modules")
         .and(warp::get())
+        .and(accept_type())
+        .and(rate_limit("get_account_modules", &context))
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .map(|address, accept_type, ctx, version: Version| {
+            (version.version, address, accept_type, ctx)
+        })
         .untuple_one()
         .and_then(handle_get_account_modules)
         .with(metrics("get_account_modules"))
         .boxed()
 }
 
+// GET /accounts/<address>/modules/abi
+pub fn get_account_modules_abi(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "modules" / "abi")
+        .and(warp::get())
+        .and(rate_limit("get_account_modules_abi", &context))
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|address, ctx, version: Version| (version.version, address, ctx))
+        .untuple_one()
+        .and_then(handle_get_account_modules_abi)
+        .with(metrics("get_account_modules_abi"))
+        .boxed()
+}
+
 async fn handle_get_account(
     address: AddressParam,
+    accept_type: AcceptType,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account")?;
-    Ok(Account::new(None, address, context)?.account()?)
+    Ok(Account::new(None, address, context)?.account(accept_type)?)
 }
 
 async fn handle_get_account_resources(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
+    accept_type: AcceptType,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_resources")?;
-    Ok(Account::new(ledger_version, address, context)?.resources()?)
+    Ok(Account::new(ledger_version, address, context)?.resources(accept_type)?)
 }
 
 async fn handle_get_account_modules(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
+    accept_type: AcceptType,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_modules")?;
-    Ok(Account::new(ledger_version, address, context)?.modules()?)
+    Ok(Account::new(ledger_version, address, context)?.modules(accept_type)?)
+}
+
+async fn handle_get_account_modules_abi(
+    ledger_version: Option<LedgerVersionParam>,
+    address: AddressParam,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_modules_abi")?;
+    Ok(Account::new(ledger_version, address, context)?.modules_abi()?)
 }
 
 pub(crate) struct Account {
@@ -126,7 +164,7 @@ impl Account {
         })
     }
 
-    pub fn account(self) -> Result<impl Reply, Error> {
+    pub fn account(self, accept_type: AcceptType) -> Result<impl Reply, Error> {
         let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
             self.address.into(),
             AccountResource::struct_tag(),
@@ -134,38 +172,67 @@ impl Account {
 
         let state_value = self
             .context
-            .get_state_value(&state_key, self.ledger_version)?;
-
-        let account_resource: AccountResource = state_value
-            .map(|bytes| bcs::from_bytes(&bytes))
-            .transpose()
-            .map_err(anyhow::Error::from)?
+            .get_state_value(&state_key, self.ledger_version)?
             .ok_or_else(|| self.resource_not_found(&AccountResource::struct_tag()))?;
 
+        if accept_type == AcceptType::Bcs {
+            return Ok(Response::from_bcs_bytes(
+                self.latest_ledger_info,
+                state_value,
+            ));
+        }
+
+        let account_resource: AccountResource =
+            bcs::from_bytes(&state_value).map_err(anyhow::Error::from)?;
+
         let account: AccountData = account_resource.into();
 
         Response::new(self.latest_ledger_info, &account)
     }
 
-    pub fn resources(self) -> Result<impl Reply, Error> {
+    pub fn resources(self, accept_type: AcceptType) -> Result<impl Reply, Error> {
+        let account_state = self.account_state()?;
+        if accept_type == AcceptType::Bcs {
+            let resources: BTreeMap<StructTag, Vec<u8>> = account_state
+                .get_resources()
+                .map(|(tag, bytes)| (tag, bytes.to_vec()))
+                .collect();
+            return Response::new_bcs(self.latest_ledger_info, &resources);
+        }
+
         let resources = self
             .context
             .move_resolver()?
             .as_converter()
-            .try_into_resources(self.account_state()?.get_resources())?;
+            .try_into_resources(account_state.get_resources())?;
         Response::new(self.latest_ledger_info, &resources)
     }
 
-    pub fn modules(self) -> Result<impl Reply, Error> {
-        let modules = self
-            .account_state()?
-            .into_modules()
+    pub fn modules(self, accept_type: AcceptType) -> Result<impl Reply, Error> {
+        let modules = self.account_state()?.into_modules();
+        if accept_type == AcceptType::Bcs {
+            return Response::new_bcs(self.latest_ledger_info, &modules.collect::<Vec<_>>());
+        }
+
+        let modules = modules
             .map(MoveModuleBytecode::new)
             .map(|m| m.try_parse_abi())
             .collect::<Result<Vec<MoveModuleBytecode>>>()?;
         Response::new(self.latest_ledger_info, &modules)
     }
 
+    pub fn modules_abi(self) -> Result<impl Reply, Error> {
+        let abis = self
+            .account_state()?
+            .into_modules()
+            .map(|m| MoveModuleBytecode::new(m).try_parse_abi())
+            .collect::<Result<Vec<MoveModuleBytecode>>>()?
+            .into_iter()
+            .filter_map(|m| m.abi.map(MoveModuleAbi::from))
+            .collect::<Vec<_>>();
+        Response::new(self.latest_ledger_info, &abis)
+    }
+
     pub fn find_event_key(
         &self,
         struct_tag_param: MoveStructTagParam,
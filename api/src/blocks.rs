@@ -0,0 +1,175 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    failpoint::fail_point,
+    metrics::metrics,
+    param::{BlockHeightParam, Param, TransactionVersionParam},
+    rate_limit::rate_limit,
+    transactions::convert_transactions,
+};
+
+use aptos_api_types::{Block, Error, LedgerInfo, Response, TransactionId};
+use aptos_types::transaction::Transaction as AptosTransaction;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+/// Max number of transactions read from the DB at once when rendering a block.
+const MAX_TRANSACTIONS_PER_READ: u16 = 1000;
+
+#[derive(Clone, Debug, Deserialize)]
+struct BlockQuery {
+    with_transactions: Option<Param<bool>>,
+}
+
+impl BlockQuery {
+    fn with_transactions(self) -> Result<bool, Error> {
+        self.with_transactions
+            .map(|v| v.parse("with_transactions"))
+            .unwrap_or(Ok(false))
+    }
+}
+
+// GET /blocks/by_height/{height}?with_transactions={bool}
+pub fn get_block_by_height(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("blocks" / "by_height" / BlockHeightParam)
+        .and(warp::get())
+        .and(warp::query::<BlockQuery>())
+        .and(rate_limit("get_block_by_height", &context))
+        .and(context.filter())
+        .and_then(handle_get_block_by_height)
+        .with(metrics("get_block_by_height"))
+        .boxed()
+}
+
+// GET /blocks/by_version/{version}?with_transactions={bool}
+pub fn get_block_by_version(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("blocks" / "by_version" / TransactionVersionParam)
+        .and(warp::get())
+        .and(warp::query::<BlockQuery>())
+        .and(rate_limit("get_block_by_version", &context))
+        .and(context.filter())
+        .and_then(handle_get_block_by_version)
+        .with(metrics("get_block_by_version"))
+        .boxed()
+}
+
+async fn handle_get_block_by_height(
+    height: BlockHeightParam,
+    query: BlockQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_block_by_height")?;
+    Ok(Blocks::new(context)?
+        .get_by_height(height.parse("block height")?, query.with_transactions()?)?)
+}
+
+async fn handle_get_block_by_version(
+    version: TransactionVersionParam,
+    query: BlockQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_block_by_version")?;
+    Ok(Blocks::new(context)?
+        .get_by_version(version.parse("version")?, query.with_transactions()?)?)
+}
+
+struct Blocks {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl Blocks {
+    fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info()?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    pub fn get_by_height(self, height: u64, with_transactions: bool) -> Result<impl Reply, Error> {
+        let (first_version, last_version) = self
+            .context
+            .get_block_version_range(height, self.ledger_info.version())?
+            .ok_or_else(|| {
+                Error::not_found(
+                    "block",
+                    format!("height({})", height),
+                    self.ledger_info.version(),
+                )
+            })?;
+        self.render_block(height, first_version, last_version, with_transactions)
+    }
+
+    pub fn get_by_version(
+        self,
+        version: u64,
+        with_transactions: bool,
+    ) -> Result<impl Reply, Error> {
+        if version > self.ledger_info.version() {
+            return Err(Error::not_found(
+                "block",
+                TransactionId::Version(version),
+                self.ledger_info.version(),
+            ));
+        }
+        let height = self.context.get_block_height(version)?;
+        self.get_by_height(height, with_transactions)
+    }
+
+    fn render_block(
+        self,
+        height: u64,
+        first_version: u64,
+        last_version: u64,
+        with_transactions: bool,
+    ) -> Result<impl Reply, Error> {
+        // The first transaction identifies the block: it's either the genesis transaction or the
+        // block metadata transaction, so it's always read.
+        let end_version = if with_transactions {
+            last_version
+        } else {
+            first_version
+        };
+        let mut data = vec![];
+        let mut next_version = first_version;
+        while next_version <= end_version {
+            let limit = (end_version - next_version + 1).min(MAX_TRANSACTIONS_PER_READ as u64);
+            let batch = self.context.get_transactions(
+                next_version,
+                limit as u16,
+                self.ledger_info.version(),
+            )?;
+            next_version += batch.len() as u64;
+            data.extend(batch);
+        }
+        let first_txn = data
+            .first()
+            .ok_or_else(|| Error::internal(anyhow!("empty block at height {}", height)))?;
+        let block_hash = match &first_txn.transaction {
+            AptosTransaction::BlockMetadata(metadata) => metadata.id(),
+            _ => first_txn.info.transaction_hash(),
+        };
+        let block_timestamp = self.context.get_block_timestamp(first_version)?;
+
+        let transactions = if with_transactions {
+            Some(convert_transactions(&self.context, data)?)
+        } else {
+            None
+        };
+
+        let block = Block {
+            block_height: height.into(),
+            block_hash: block_hash.into(),
+            block_timestamp: block_timestamp.into(),
+            first_version: first_version.into(),
+            last_version: last_version.into(),
+            transactions,
+        };
+        Response::new(self.ledger_info, &block)
+    }
+}
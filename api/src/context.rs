@@ -1,18 +1,25 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::rate_limit::RateLimiter;
 use aptos_api_types::{Error, LedgerInfo, TransactionOnChainData};
 use aptos_config::config::ApiConfig;
 use aptos_crypto::HashValue;
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use aptos_mempool::{
+    MempoolClientRequest, MempoolClientSender, MempoolStats, MempoolTransactionStatus,
+    SubmissionStatus,
+};
 use aptos_types::{
+    access_path::AccessPath,
     account_address::AccountAddress,
+    account_config::{aptos_root_address, AccountResource},
     account_state::AccountState,
+    block_metadata::{new_block_event_key, BlockResource},
     chain_id::ChainId,
-    contract_event::ContractEvent,
+    contract_event::EventWithVersion,
     event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
-    transaction::{SignedTransaction, TransactionWithProof},
+    transaction::{SignedTransaction, Transaction, TransactionWithProof},
 };
 use storage_interface::{DbReader, Order};
 
@@ -24,11 +31,16 @@ use aptos_types::{
 };
 use aptos_vm::data_cache::{IntoMoveResolver, RemoteStorageOwned};
 use futures::{channel::oneshot, SinkExt};
+use move_deps::move_core_types::{language_storage::ResourceKey, move_resource::MoveStructType};
 use std::{convert::Infallible, sync::Arc};
 use storage_interface::state_view::{
     DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView,
 };
-use warp::{filters::BoxedFilter, Filter, Reply};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use warp::{filters::BoxedFilter, http::StatusCode, Filter, Reply};
+
+/// Number of events read at once when counting the events of an event key.
+const EVENT_COUNT_BATCH_SIZE: u64 = 100;
 
 // Context holds application scope context
 #[derive(Clone)]
@@ -37,6 +49,8 @@ pub struct Context {
     db: Arc<dyn DbReader>,
     mp_sender: MempoolClientSender,
     api_config: ApiConfig,
+    rate_limiter: Arc<RateLimiter>,
+    stream_permits: Arc<Semaphore>,
 }
 
 impl Context {
@@ -46,11 +60,15 @@ impl Context {
         mp_sender: MempoolClientSender,
         api_config: ApiConfig,
     ) -> Self {
+        let rate_limiter = Arc::new(RateLimiter::new(api_config.rate_limit.as_ref()));
+        let stream_permits = Arc::new(Semaphore::new(api_config.max_concurrent_streams));
         Self {
             chain_id,
             db,
             mp_sender,
             api_config,
+            rate_limiter,
+            stream_permits,
         }
     }
 
@@ -60,6 +78,10 @@ impl Context {
             .map(|state_view| state_view.into_move_resolver())
     }
 
+    pub fn latest_state_view(&self) -> Result<DbStateView> {
+        self.db.latest_state_checkpoint_view()
+    }
+
     pub fn state_view_at_version(&self, version: Version) -> Result<DbStateView> {
         self.db.state_view_at_version(Some(version))
     }
@@ -72,6 +94,23 @@ impl Context {
         self.api_config.content_length_limit()
     }
 
+    pub(crate) fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    /// Reserves one of the `max_concurrent_streams` slots, released when the permit is dropped.
+    pub(crate) fn try_acquire_stream_permit(&self) -> Result<OwnedSemaphorePermit, Error> {
+        self.stream_permits
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| {
+                Error::new(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "too many concurrent streams".to_owned(),
+                )
+            })
+    }
+
     pub fn filter(self) -> impl Filter<Extract = (Context,), Error = Infallible> + Clone {
         warp::any().map(move || self.clone())
     }
@@ -119,6 +158,53 @@ impl Context {
         self.db.get_block_timestamp(version)
     }
 
+    /// Returns the first and last version of the block at `height`, or `None` if the block is
+    /// not committed at `ledger_version`.
+    ///
+    /// The genesis block has height 0, and the block at height `h > 0` starts at the version
+    /// emitting the `NewBlockEvent` with sequence number `h - 1`.
+    pub fn get_block_version_range(
+        &self,
+        height: u64,
+        ledger_version: u64,
+    ) -> Result<Option<(u64, u64)>> {
+        let block_start_versions: Vec<u64> = self
+            .db
+            .get_events(
+                &new_block_event_key(),
+                height.saturating_sub(1),
+                Order::Ascending,
+                2,
+            )?
+            .into_iter()
+            .map(|event| event.transaction_version)
+            .filter(|version| *version <= ledger_version)
+            .collect();
+
+        let (first_version, next_block_start) = if height == 0 {
+            (0, block_start_versions.first())
+        } else {
+            match block_start_versions.first() {
+                Some(version) => (*version, block_start_versions.get(1)),
+                None => return Ok(None),
+            }
+        };
+        let last_version = next_block_start.map_or(ledger_version, |version| version - 1);
+        Ok(Some((first_version, last_version)))
+    }
+
+    /// Returns the height of the block containing `version`.
+    pub fn get_block_height(&self, version: u64) -> Result<u64> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            aptos_root_address(),
+            BlockResource::struct_tag(),
+        )));
+        let bytes = self
+            .get_state_value(&state_key, version)?
+            .ok_or_else(|| format_err!("block resource not found at version {}", version))?;
+        Ok(bcs::from_bytes::<BlockResource>(&bytes)?.height())
+    }
+
     pub fn get_transactions(
         &self,
         start_version: u64,
@@ -162,6 +248,23 @@ impl Context {
             .collect()
     }
 
+    /// Returns the gas unit prices of the user transactions in the last `limit` versions up to
+    /// `ledger_version`.
+    pub fn get_recent_gas_unit_prices(&self, limit: u16, ledger_version: u64) -> Result<Vec<u64>> {
+        let start_version = (ledger_version + 1).saturating_sub(limit as u64);
+        let txns = self
+            .db
+            .get_transactions(start_version, limit as u64, ledger_version, false)?;
+        Ok(txns
+            .transactions
+            .into_iter()
+            .filter_map(|txn| match txn {
+                Transaction::UserTransaction(txn) => Some(txn.gas_unit_price()),
+                _ => None,
+            })
+            .collect())
+    }
+
     pub fn get_account_transactions(
         &self,
         address: AccountAddress,
@@ -182,6 +285,23 @@ impl Context {
             .collect::<Result<Vec<_>>>()
     }
 
+    /// Returns the number of transactions committed by `address` at `version`, i.e. its sequence
+    /// number.
+    pub fn get_account_sequence_number(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<u64> {
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            address,
+            AccountResource::struct_tag(),
+        )));
+        match self.get_state_value(&state_key, version)? {
+            Some(bytes) => Ok(bcs::from_bytes::<AccountResource>(&bytes)?.sequence_number()),
+            None => Ok(0),
+        }
+    }
+
     pub fn get_transaction_by_hash(
         &self,
         hash: HashValue,
@@ -208,6 +328,35 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    pub async fn get_pending_account_transactions(
+        &self,
+        account: AccountAddress,
+    ) -> Result<Vec<(SignedTransaction, MempoolTransactionStatus)>> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetAccountTransactions(
+                account, req_sender,
+            ))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
+    pub async fn get_mempool_stats(&self) -> Result<MempoolStats> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetMempoolStats(req_sender))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
@@ -243,17 +392,40 @@ impl Context {
         start: u64,
         limit: u16,
         ledger_version: u64,
-    ) -> Result<Vec<ContractEvent>> {
+    ) -> Result<Vec<EventWithVersion>> {
         let events = self
             .db
             .get_events(event_key, start, Order::Ascending, limit as u64)?;
         Ok(events
             .into_iter()
             .filter(|event| event.transaction_version <= ledger_version)
-            .map(|event| event.event)
             .collect::<Vec<_>>())
     }
 
+    /// Returns the number of events emitted under `event_key` at `ledger_version`.
+    pub fn get_event_count(&self, event_key: &EventKey, ledger_version: u64) -> Result<u64> {
+        // The DB reads events at its latest version, skip the ones committed after
+        // `ledger_version`.
+        let mut start = u64::MAX;
+        loop {
+            let events =
+                self.db
+                    .get_events(event_key, start, Order::Descending, EVENT_COUNT_BATCH_SIZE)?;
+            match events
+                .iter()
+                .find(|event| event.transaction_version <= ledger_version)
+            {
+                Some(event) => return Ok(event.event.sequence_number() + 1),
+                None => match events.last() {
+                    Some(event) if event.event.sequence_number() > 0 => {
+                        start = event.event.sequence_number() - 1;
+                    }
+                    _ => return Ok(0),
+                },
+            }
+        }
+    }
+
     pub fn health_check_route(&self) -> BoxedFilter<(impl Reply,)> {
         super::health_check::health_check_route(self.db.clone())
     }
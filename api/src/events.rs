@@ -2,12 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    accept_type::{accept_type, AcceptType},
     accounts::Account,
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
     page::Page,
     param::{AddressParam, EventKeyParam, MoveIdentifierParam, MoveStructTagParam},
+    rate_limit::rate_limit,
+    stream::{ndjson_stream, StreamQuery, STREAM_BATCH_SIZE},
 };
 
 use aptos_api_types::{AsConverter, Error, LedgerInfo, Response};
@@ -21,6 +24,8 @@ pub fn get_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("events" / EventKeyParam)
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(accept_type())
+        .and(rate_limit("get_events_by_event_key", &context))
         .and(context.filter())
         .and_then(handle_get_events_by_event_key)
         .with(metrics("get_events_by_event_key"))
@@ -32,19 +37,48 @@ pub fn get_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)
     warp::path!("accounts" / AddressParam / "events" / MoveStructTagParam / MoveIdentifierParam)
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(accept_type())
+        .and(rate_limit("get_events_by_event_handle", &context))
         .and(context.filter())
         .and_then(handle_get_events_by_event_handle)
         .with(metrics("get_events_by_event_handle"))
         .boxed()
 }
 
+// GET /events/<event_key>/stream?start={u64}
+pub fn stream_events_by_event_key(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("events" / EventKeyParam / "stream")
+        .and(warp::get())
+        .and(warp::query::<StreamQuery>())
+        .and(rate_limit("stream_events_by_event_key", &context))
+        .and(context.filter())
+        .and_then(handle_stream_events_by_event_key)
+        .with(metrics("stream_events_by_event_key"))
+        .boxed()
+}
+
+// GET /accounts/<address>/events/<event_handle_struct>/<field_name>/stream?start={u64}
+pub fn stream_events_by_event_handle(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!(
+        "accounts" / AddressParam / "events" / MoveStructTagParam / MoveIdentifierParam / "stream"
+    )
+    .and(warp::get())
+    .and(warp::query::<StreamQuery>())
+    .and(rate_limit("stream_events_by_event_handle", &context))
+    .and(context.filter())
+    .and_then(handle_stream_events_by_event_handle)
+    .with(metrics("stream_events_by_event_handle"))
+    .boxed()
+}
+
 async fn handle_get_events_by_event_key(
     event_key: EventKeyParam,
     page: Page,
+    accept_type: AcceptType,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_key")?;
-    Ok(Events::new(event_key.parse("event key")?.into(), context)?.list(page)?)
+    Ok(Events::new(event_key.parse("event key")?.into(), context)?.list(page, accept_type)?)
 }
 
 async fn handle_get_events_by_event_handle(
@@ -52,12 +86,35 @@ async fn handle_get_events_by_event_handle(
     struct_tag: MoveStructTagParam,
     field_name: MoveIdentifierParam,
     page: Page,
+    accept_type: AcceptType,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_events_by_event_handle")?;
     let key =
         Account::new(None, address, context.clone())?.find_event_key(struct_tag, field_name)?;
-    Ok(Events::new(key, context)?.list(page)?)
+    Ok(Events::new(key, context)?.list(page, accept_type)?)
+}
+
+async fn handle_stream_events_by_event_key(
+    event_key: EventKeyParam,
+    query: StreamQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_events_by_event_key")?;
+    Ok(Events::new(event_key.parse("event key")?.into(), context)?.stream(query.start()?)?)
+}
+
+async fn handle_stream_events_by_event_handle(
+    address: AddressParam,
+    struct_tag: MoveStructTagParam,
+    field_name: MoveIdentifierParam,
+    query: StreamQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_events_by_event_handle")?;
+    let key =
+        Account::new(None, address, context.clone())?.find_event_key(struct_tag, field_name)?;
+    Ok(Events::new(key, context)?.stream(query.start()?)?)
 }
 
 struct Events {
@@ -76,16 +133,45 @@ impl Events {
         })
     }
 
-    pub fn list(self, page: Page) -> Result<impl Reply, Error> {
-        let contract_events = self.context.get_events(
-            &self.key,
-            page.start(0, u64::MAX)?,
-            page.limit()?,
-            self.ledger_info.version(),
-        )?;
+    pub fn list(self, page: Page, accept_type: AcceptType) -> Result<impl Reply, Error> {
+        let ledger_version = self.ledger_info.version();
+        let total = self.context.get_event_count(&self.key, ledger_version)?;
+        let range = page.sequence_range(total)?;
+        let events = range.read(|first, limit| {
+            self.context
+                .get_events(&self.key, first, limit, ledger_version)
+        })?;
+        let page_info = range.page_info(events.len());
+        let events = range.order(events);
+        // The BCS encoding keeps the versions of the transactions emitting the events
+        if accept_type == AcceptType::Bcs {
+            return Ok(Response::new_bcs(self.ledger_info, &events)?.with_page_info(page_info));
+        }
 
+        let contract_events: Vec<_> = events.into_iter().map(|event| event.event).collect();
         let resolver = self.context.move_resolver()?;
         let events = resolver.as_converter().try_into_events(&contract_events)?;
-        Response::new(self.ledger_info, &events)
+        Ok(Response::new(self.ledger_info, &events)?.with_page_info(page_info))
+    }
+
+    /// Streams the events from the `start` sequence number, defaulting to the first event.
+    pub fn stream(self, start: Option<u64>) -> Result<impl Reply, Error> {
+        let key = self.key;
+        let context = self.context.clone();
+        ndjson_stream(
+            &self.context,
+            self.ledger_info,
+            start.unwrap_or(0),
+            move |sequence_number| {
+                let ledger_version = context.get_latest_ledger_info()?.version();
+                let contract_events: Vec<_> = context
+                    .get_events(&key, sequence_number, STREAM_BATCH_SIZE, ledger_version)?
+                    .into_iter()
+                    .map(|event| event.event)
+                    .collect();
+                let resolver = context.move_resolver()?;
+                Ok(resolver.as_converter().try_into_events(&contract_events)?)
+            },
+        )
     }
 }
@@ -2,15 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    accounts,
+    accounts, blocks,
     context::Context,
     events,
     failpoint::fail_point,
-    log,
+    log, mempool,
     metrics::{metrics, status_metrics},
+    rate_limit::{rate_limit, RateLimited, X_APTOS_API_KEY},
     state, transactions,
 };
-use aptos_api_types::{Error, Response};
+use aptos_api_types::{Error, Response, X_APTOS_CURSOR, X_APTOS_TOTAL_COUNT};
 
 use std::convert::Infallible;
 use warp::{
@@ -31,23 +32,42 @@ pub fn routes(context: Context) -> impl Filter<Extract = impl Reply, Error = Inf
         .or(accounts::get_account(context.clone()))
         .or(accounts::get_account_resources(context.clone()))
         .or(accounts::get_account_modules(context.clone()))
+        .or(accounts::get_account_modules_abi(context.clone()))
+        .or(transactions::stream_transactions(context.clone()))
         .or(transactions::get_transaction(context.clone()))
         .or(transactions::get_transactions(context.clone()))
         .or(transactions::get_account_transactions(context.clone()))
         .or(transactions::submit_bcs_transactions(context.clone()))
         .or(transactions::submit_json_transactions(context.clone()))
+        .or(transactions::submit_bcs_transactions_batch(context.clone()))
+        .or(transactions::submit_json_transactions_batch(
+            context.clone(),
+        ))
+        .or(transactions::simulate_bcs_transactions(context.clone()))
+        .or(transactions::simulate_json_transactions(context.clone()))
+        .or(transactions::estimate_gas_bcs(context.clone()))
+        .or(transactions::estimate_gas_json(context.clone()))
         .or(transactions::create_signing_message(context.clone()))
+        .or(blocks::get_block_by_height(context.clone()))
+        .or(blocks::get_block_by_version(context.clone()))
+        .or(mempool::get_mempool_transactions(context.clone()))
+        .or(mempool::get_mempool_stats(context.clone()))
+        .or(events::stream_events_by_event_key(context.clone()))
+        .or(events::stream_events_by_event_handle(context.clone()))
         .or(events::get_events_by_event_key(context.clone()))
         .or(events::get_events_by_event_handle(context.clone()))
         .or(state::get_account_resource(context.clone()))
         .or(state::get_account_module(context.clone()))
+        .or(state::get_account_module_abi(context.clone()))
         .or(state::get_table_item(context.clone()))
+        .or(state::get_raw_table_item(context.clone()))
         .or(context.health_check_route().with(metrics("health_check")))
         .with(
             warp::cors()
                 .allow_any_origin()
                 .allow_methods(vec!["POST", "GET"])
-                .allow_headers(vec![header::CONTENT_TYPE]),
+                .allow_headers(vec![header::CONTENT_TYPE.as_str(), X_APTOS_API_KEY])
+                .expose_headers(vec![X_APTOS_CURSOR, X_APTOS_TOTAL_COUNT]),
         )
         .recover(handle_rejection)
         .with(log::logger())
@@ -74,6 +94,7 @@ pub fn openapi_spec() -> BoxedFilter<(impl Reply,)> {
 pub fn index(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path::end()
         .and(warp::get())
+        .and(rate_limit("get_ledger_info", &context))
         .and(context.filter())
         .and_then(handle_index)
         .with(metrics("get_ledger_info"))
@@ -89,6 +110,7 @@ pub async fn handle_index(context: Context) -> Result<impl Reply, Rejection> {
 async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
     let code;
     let body;
+    let mut retry_after = None;
 
     if err.is_not_found() {
         code = StatusCode::NOT_FOUND;
@@ -96,6 +118,10 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
     } else if let Some(error) = err.find::<Error>() {
         code = error.status_code();
         body = reply::json(error);
+    } else if let Some(cause) = err.find::<RateLimited>() {
+        code = StatusCode::TOO_MANY_REQUESTS;
+        body = reply::json(&Error::new(code, "Rate limit exceeded".to_owned()));
+        retry_after = Some(cause.retry_after.as_secs());
     } else if let Some(cause) = err.find::<CorsForbidden>() {
         code = StatusCode::FORBIDDEN;
         body = reply::json(&Error::new(code, cause.to_string()));
@@ -121,6 +147,10 @@ async fn handle_rejection(err: Rejection) -> Result<impl Reply, Infallible> {
     let mut rep = reply::with_status(body, code).into_response();
     rep.headers_mut()
         .insert("access-control-allow-origin", HeaderValue::from_static("*"));
+    if let Some(secs) = retry_after {
+        rep.headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+    }
     Ok(rep)
 }
 
@@ -1,17 +1,22 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod accept_type;
 mod accounts;
+mod blocks;
 pub mod context;
 mod events;
 mod health_check;
 mod index;
 pub(crate) mod log;
+mod mempool;
 mod metrics;
 mod page;
 pub mod param;
+mod rate_limit;
 pub mod runtime;
 mod state;
+mod stream;
 mod transactions;
 pub(crate) mod version;
 
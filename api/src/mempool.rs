@@ -0,0 +1,123 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context, failpoint::fail_point, metrics::metrics, param::AddressParam,
+    rate_limit::rate_limit,
+};
+
+use aptos_api_types::{
+    AsConverter, Error, LedgerInfo, MempoolStats, MempoolTransaction, MempoolTransactionStatus,
+    Response,
+};
+use aptos_mempool::{
+    MempoolStats as CoreMempoolStats, MempoolTransactionStatus as CoreMempoolTransactionStatus,
+};
+use aptos_types::account_address::AccountAddress;
+
+use anyhow::Result;
+use serde::Deserialize;
+use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
+
+#[derive(Clone, Debug, Deserialize)]
+struct MempoolTransactionsQuery {
+    account: AddressParam,
+}
+
+// GET /mempool/transactions?account={address}
+pub fn get_mempool_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("mempool" / "transactions")
+        .and(warp::get())
+        .and(warp::query::<MempoolTransactionsQuery>())
+        .and(rate_limit("get_mempool_transactions", &context))
+        .and(context.filter())
+        .and_then(handle_get_mempool_transactions)
+        .with(metrics("get_mempool_transactions"))
+        .boxed()
+}
+
+// GET /mempool/stats
+pub fn get_mempool_stats(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("mempool" / "stats")
+        .and(warp::get())
+        .and(rate_limit("get_mempool_stats", &context))
+        .and(context.filter())
+        .and_then(handle_get_mempool_stats)
+        .with(metrics("get_mempool_stats"))
+        .boxed()
+}
+
+async fn handle_get_mempool_transactions(
+    query: MempoolTransactionsQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_mempool_transactions")?;
+    let account = query.account.parse("account")?.into();
+    Ok(Mempool::new(context)?.list(account).await?)
+}
+
+async fn handle_get_mempool_stats(context: Context) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_mempool_stats")?;
+    Ok(Mempool::new(context)?.stats().await?)
+}
+
+struct Mempool {
+    ledger_info: LedgerInfo,
+    context: Context,
+}
+
+impl Mempool {
+    fn new(context: Context) -> Result<Self, Error> {
+        let ledger_info = context.get_latest_ledger_info()?;
+        Ok(Self {
+            ledger_info,
+            context,
+        })
+    }
+
+    pub async fn list(self, account: AccountAddress) -> Result<impl Reply, Error> {
+        let txns = self
+            .context
+            .get_pending_account_transactions(account)
+            .await?;
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+        let txns = txns
+            .into_iter()
+            .map(|(txn, status)| {
+                Ok(MempoolTransaction {
+                    status: match status {
+                        CoreMempoolTransactionStatus::Ready => MempoolTransactionStatus::Ready,
+                        CoreMempoolTransactionStatus::Parked => MempoolTransactionStatus::Parked,
+                    },
+                    transaction: converter.try_into_pending_transaction(txn)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Response::new(self.ledger_info, &txns)
+    }
+
+    pub async fn stats(self) -> Result<impl Reply, Error> {
+        let CoreMempoolStats {
+            capacity,
+            capacity_per_user,
+            system_ttl_index,
+            expiration_time_index,
+            priority_index,
+            parking_lot_index,
+            timeline_index,
+            transaction_hash_index,
+        } = self.context.get_mempool_stats().await?;
+        let stats = MempoolStats {
+            capacity: (capacity as u64).into(),
+            capacity_per_user: (capacity_per_user as u64).into(),
+            system_ttl_index: (system_ttl_index as u64).into(),
+            expiration_time_index: (expiration_time_index as u64).into(),
+            priority_index: (priority_index as u64).into(),
+            parking_lot_index: (parking_lot_index as u64).into(),
+            timeline_index: (timeline_index as u64).into(),
+            transaction_hash_index: (transaction_hash_index as u64).into(),
+        };
+        Response::new(self.ledger_info, &stats)
+    }
+}
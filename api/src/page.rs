@@ -3,11 +3,11 @@
 
 use crate::param::{Param, TransactionVersionParam};
 
-use aptos_api_types::{Error, TransactionId};
+use aptos_api_types::{Error, PageInfo, TransactionId};
 
 use anyhow::Result;
-use serde::Deserialize;
-use std::num::NonZeroU16;
+use serde::{Deserialize, Serialize};
+use std::{fmt, num::NonZeroU16, str::FromStr};
 
 const DEFAULT_PAGE_SIZE: u16 = 25;
 const MAX_PAGE_SIZE: u16 = 1000;
@@ -16,6 +16,8 @@ const MAX_PAGE_SIZE: u16 = 1000;
 pub(crate) struct Page {
     start: Option<TransactionVersionParam>,
     limit: Option<Param<NonZeroU16>>,
+    reverse: Option<Param<bool>>,
+    cursor: Option<Param<Cursor>>,
 }
 
 impl Page {
@@ -50,4 +52,228 @@ impl Page {
         }
         Ok(limit)
     }
+
+    /// Resolves the page of a list of `total` items indexed by sequence numbers, i.e. the
+    /// transactions sent by an account or the events of an event key.
+    ///
+    /// The page starts at the `cursor` of the previous page, or at `start`. In reverse order
+    /// pages go from newest to oldest, and `start` defaults to the last item.
+    pub fn sequence_range(&self, total: u64) -> Result<SequenceRange, Error> {
+        let limit = self.limit()?;
+        let (start, reverse) = match self.cursor.clone() {
+            Some(cursor) => {
+                if self.start.is_some() || self.reverse.is_some() {
+                    return Err(Error::bad_request(
+                        "cursor can't be combined with start or reverse",
+                    ));
+                }
+                let cursor = cursor.parse("cursor")?;
+                (Some(cursor.start), cursor.reverse)
+            }
+            None => {
+                let start = self.start.clone().map(|v| v.parse("start")).transpose()?;
+                let reverse = self
+                    .reverse
+                    .clone()
+                    .map(|v| v.parse("reverse"))
+                    .unwrap_or(Ok(false))?;
+                (start, reverse)
+            }
+        };
+
+        if !reverse {
+            return Ok(SequenceRange {
+                first: start.unwrap_or(0),
+                limit,
+                reverse,
+                total,
+            });
+        }
+        if total == 0 {
+            return Ok(SequenceRange {
+                first: 0,
+                limit: 0,
+                reverse,
+                total,
+            });
+        }
+        let last = start.unwrap_or(u64::MAX).min(total - 1);
+        let first = (last + 1).saturating_sub(limit as u64);
+        Ok(SequenceRange {
+            first,
+            limit: (last - first + 1) as u16,
+            reverse,
+            total,
+        })
+    }
+}
+
+/// Range of sequence numbers to read for a page, always in ascending order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct SequenceRange {
+    first: u64,
+    limit: u16,
+    reverse: bool,
+    total: u64,
+}
+
+impl SequenceRange {
+    /// Reads the items of the range in ascending order with `read(first, limit)`, an empty range
+    /// isn't read.
+    pub fn read<T, F>(&self, read: F) -> Result<Vec<T>, Error>
+    where
+        F: FnOnce(u64, u16) -> Result<Vec<T>>,
+    {
+        if self.limit == 0 {
+            return Ok(vec![]);
+        }
+        Ok(read(self.first, self.limit)?)
+    }
+
+    /// Puts the `items` read from the range in page order.
+    pub fn order<T>(&self, mut items: Vec<T>) -> Vec<T> {
+        if self.reverse {
+            items.reverse();
+        }
+        items
+    }
+
+    /// Returns the pagination headers, given the number of items read from the range.
+    pub fn page_info(&self, len: usize) -> PageInfo {
+        let next = if self.reverse {
+            (self.first > 0).then(|| Cursor {
+                start: self.first - 1,
+                reverse: true,
+            })
+        } else {
+            let start = self.first + len as u64;
+            (len > 0 && start < self.total).then(|| Cursor {
+                start,
+                reverse: false,
+            })
+        };
+        PageInfo {
+            cursor: next.map(|cursor| cursor.to_string()),
+            total_count: self.total,
+        }
+    }
+}
+
+/// Opaque continuation token of a page, returned in the `X-Aptos-Cursor` header.
+///
+/// It's the hex encoded BCS of the first sequence number of the next page and the page order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Cursor {
+    start: u64,
+    reverse: bool,
+}
+
+impl FromStr for Cursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(bcs::from_bytes(&hex::decode(s)?)?)
+    }
+}
+
+impl fmt::Display for Cursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = bcs::to_bytes(self).map_err(|_| fmt::Error)?;
+        write!(f, "{}", hex::encode(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cursor, Page, SequenceRange};
+
+    async fn page(query: &str) -> Page {
+        warp::test::request()
+            .path(&format!("/?{}", query))
+            .filter(&warp::query::<Page>())
+            .await
+            .unwrap()
+    }
+
+    fn range(first: u64, limit: u16, reverse: bool, total: u64) -> SequenceRange {
+        SequenceRange {
+            first,
+            limit,
+            reverse,
+            total,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sequence_range() {
+        assert_eq!(
+            page("limit=10").await.sequence_range(25).unwrap(),
+            range(0, 10, false, 25)
+        );
+        assert_eq!(
+            page("start=20&limit=10").await.sequence_range(25).unwrap(),
+            range(20, 10, false, 25)
+        );
+        assert_eq!(
+            page("reverse=true&limit=10")
+                .await
+                .sequence_range(25)
+                .unwrap(),
+            range(15, 10, true, 25)
+        );
+        assert_eq!(
+            page("reverse=true&start=5&limit=10")
+                .await
+                .sequence_range(25)
+                .unwrap(),
+            range(0, 6, true, 25)
+        );
+        assert_eq!(
+            page("reverse=true").await.sequence_range(0).unwrap(),
+            range(0, 0, true, 0)
+        );
+    }
+
+    #[test]
+    fn test_page_info() {
+        let info = range(0, 10, false, 25).page_info(10);
+        assert_eq!(info.total_count, 25);
+        let cursor: Cursor = info.cursor.unwrap().parse().unwrap();
+        assert_eq!(
+            cursor,
+            Cursor {
+                start: 10,
+                reverse: false
+            }
+        );
+        assert_eq!(range(20, 10, false, 25).page_info(5).cursor, None);
+
+        let cursor: Cursor = range(15, 10, true, 25)
+            .page_info(10)
+            .cursor
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            cursor,
+            Cursor {
+                start: 14,
+                reverse: true
+            }
+        );
+        assert_eq!(range(0, 6, true, 25).page_info(6).cursor, None);
+    }
+
+    #[tokio::test]
+    async fn test_cursor() {
+        let info = range(0, 10, false, 25).page_info(10);
+        let next = page(&format!("cursor={}", info.cursor.unwrap()))
+            .await
+            .sequence_range(25)
+            .unwrap();
+        assert_eq!(next, range(10, 25, false, 25));
+
+        assert!(page("cursor=invalid").await.sequence_range(25).is_err());
+        assert!(page("cursor=00&start=1").await.sequence_range(25).is_err());
+    }
 }
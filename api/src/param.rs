@@ -9,6 +9,7 @@ use serde::{Deserialize, Deserializer};
 use std::{convert::Infallible, str::FromStr};
 
 pub type AddressParam = Param<Address>;
+pub type BlockHeightParam = Param<u64>;
 pub type EventKeyParam = Param<EventKey>;
 pub type LedgerVersionParam = Param<u64>;
 pub type MoveStructTagParam = Param<MoveStructTag>;
@@ -0,0 +1,151 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::Context;
+
+use aptos_config::config::ApiRateLimitConfig;
+use aptos_infallible::Mutex;
+use aptos_metrics_core::{register_int_counter_vec, IntCounterVec};
+use aptos_rate_limiter::rate_limit::TokenBucketRateLimiter;
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    time::{Duration, Instant},
+};
+use warp::{filters::BoxedFilter, reject::Reject, Filter, Rejection};
+
+/// Request header carrying the API key of the client.
+pub const X_APTOS_API_KEY: &str = "X-Aptos-Api-Key";
+
+const IP_LIMITER: &str = "ip";
+const API_KEY_LIMITER: &str = "api_key";
+
+/// How often the buckets of idle clients are dropped.
+const GARBAGE_COLLECTION_INTERVAL: Duration = Duration::from_secs(10);
+
+static RATE_LIMITED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_api_rate_limited_requests",
+        "Number of API requests rejected by the rate limiter, grouped by operation_id and limiter",
+        &["operation_id", "limiter"]
+    )
+    .unwrap()
+});
+
+/// Rejection of a throttled request, rendered as a 429 with a `Retry-After` header.
+#[derive(Debug)]
+pub struct RateLimited {
+    pub retry_after: Duration,
+}
+
+impl Reject for RateLimited {}
+
+/// Token bucket rate limiters of the API, one bucket per client IP address and one per
+/// configured API key.
+pub struct RateLimiter {
+    ip: TokenBucketRateLimiter<IpAddr>,
+    api_key: TokenBucketRateLimiter<String>,
+    api_keys: HashSet<String>,
+    last_garbage_collection: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(config: Option<&ApiRateLimitConfig>) -> Self {
+        match config {
+            Some(config) if config.enabled => Self {
+                ip: TokenBucketRateLimiter::new(
+                    IP_LIMITER,
+                    "api".to_string(),
+                    config.initial_bucket_fill_percentage,
+                    config.ip_bucket_size,
+                    config.ip_bucket_rate,
+                    None,
+                ),
+                api_key: TokenBucketRateLimiter::new(
+                    API_KEY_LIMITER,
+                    "api".to_string(),
+                    config.initial_bucket_fill_percentage,
+                    config.api_key_bucket_size,
+                    config.api_key_bucket_rate,
+                    None,
+                ),
+                api_keys: config.api_keys.iter().cloned().collect(),
+                last_garbage_collection: Mutex::new(Instant::now()),
+            },
+            _ => Self {
+                ip: TokenBucketRateLimiter::open(IP_LIMITER),
+                api_key: TokenBucketRateLimiter::open(API_KEY_LIMITER),
+                api_keys: HashSet::new(),
+                last_garbage_collection: Mutex::new(Instant::now()),
+            },
+        }
+    }
+
+    /// Takes a token from the bucket of the client. Requests with an unknown API key are
+    /// throttled by IP address, and requests without a remote address are never throttled.
+    pub fn check(
+        &self,
+        operation_id: &'static str,
+        remote: Option<SocketAddr>,
+        api_key: Option<String>,
+    ) -> Result<(), RateLimited> {
+        self.maybe_garbage_collect();
+        let (limiter, bucket) = match api_key.filter(|key| self.api_keys.contains(key)) {
+            Some(key) => (API_KEY_LIMITER, self.api_key.bucket(key)),
+            None => match remote {
+                Some(addr) => (IP_LIMITER, self.ip.bucket(addr.ip())),
+                None => return Ok(()),
+            },
+        };
+        let result = bucket.lock().acquire_all_tokens(1);
+        result.map_err(|ready_at| {
+            RATE_LIMITED_REQUESTS
+                .with_label_values(&[operation_id, limiter])
+                .inc();
+            RateLimited {
+                retry_after: retry_after(ready_at),
+            }
+        })
+    }
+
+    /// A client can rotate its IP address, so the IP buckets of idle clients are dropped to
+    /// bound their number. A bucket that refilled completely is the same as a new one.
+    fn maybe_garbage_collect(&self) {
+        {
+            let mut last_garbage_collection = self.last_garbage_collection.lock();
+            if last_garbage_collection.elapsed() < GARBAGE_COLLECTION_INTERVAL {
+                return;
+            }
+            *last_garbage_collection = Instant::now();
+        }
+        self.ip.garbage_collect_full_buckets();
+    }
+}
+
+// Rounds up to whole seconds, as `Retry-After` doesn't take fractions.
+fn retry_after(ready_at: Option<Instant>) -> Duration {
+    let wait = ready_at
+        .map(|at| at.saturating_duration_since(Instant::now()))
+        .unwrap_or_default();
+    let secs = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+    Duration::from_secs(secs.max(1))
+}
+
+/// Rejects the request when the client ran out of its request quota.
+/// Should use the same `operation_id` as the `metrics` of the route.
+pub fn rate_limit(operation_id: &'static str, context: &Context) -> BoxedFilter<()> {
+    warp::addr::remote()
+        .and(warp::header::optional::<String>(X_APTOS_API_KEY))
+        .and(context.clone().filter())
+        .and_then(
+            move |remote: Option<SocketAddr>, api_key: Option<String>, context: Context| async move {
+                context
+                    .rate_limiter()
+                    .check(operation_id, remote, api_key)
+                    .map_err(Rejection::from)
+            },
+        )
+        .untuple_one()
+        .boxed()
+}
@@ -2,17 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    accept_type::{accept_type, AcceptType},
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
     param::{
         AddressParam, LedgerVersionParam, MoveIdentifierParam, MoveStructTagParam, TableHandleParam,
     },
+    rate_limit::rate_limit,
     version::Version,
 };
 use anyhow::anyhow;
 use aptos_api_types::{
-    AsConverter, Error, LedgerInfo, MoveModuleBytecode, Response, TableItemRequest, TransactionId,
+    AsConverter, Error, HexEncodedBytes, LedgerInfo, MoveModuleAbi, MoveModuleBytecode,
+    RawTableItemRequest, Response, TableItemRequest, TransactionId,
 };
 use aptos_state_view::StateView;
 use aptos_types::{access_path::AccessPath, state_store::state_key::StateKey};
@@ -30,10 +33,12 @@ use warp::{filters::BoxedFilter, Filter, Rejection, Reply};
 pub fn get_account_resource(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "resource" / MoveStructTagParam)
         .and(warp::get())
+        .and(accept_type())
+        .and(rate_limit("get_account_resource", &context))
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, struct_tag, ctx, version: Version| {
-            (version.version, address, struct_tag, ctx)
+        .map(|address, struct_tag, accept_type, ctx, version: Version| {
+            (version.version, address, struct_tag, accept_type, ctx)
         })
         .untuple_one()
         .and_then(handle_get_account_resource)
@@ -45,36 +50,80 @@ pub fn get_account_resource(context: Context) -> BoxedFilter<(impl Reply,)> {
 pub fn get_account_module(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("accounts" / AddressParam / "module" / MoveIdentifierParam)
         .and(warp::get())
+        .and(accept_type())
+        .and(rate_limit("get_account_module", &context))
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|address, name, ctx, version: Version| (version.version, address, name, ctx))
+        .map(|address, name, accept_type, ctx, version: Version| {
+            (version.version, address, name, accept_type, ctx)
+        })
         .untuple_one()
         .and_then(handle_get_account_module)
         .with(metrics("get_account_module"))
         .boxed()
 }
 
+// GET /accounts/<address>/modules/<module_name>/abi
+pub fn get_account_module_abi(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("accounts" / AddressParam / "modules" / MoveIdentifierParam / "abi")
+        .and(warp::get())
+        .and(rate_limit("get_account_module_abi", &context))
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|address, name, ctx, version: Version| (version.version, address, name, ctx))
+        .untuple_one()
+        .and_then(handle_get_account_module_abi)
+        .with(metrics("get_account_module_abi"))
+        .boxed()
+}
+
 // GET /tables/<table_handle>/item
 pub fn get_table_item(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("tables" / TableHandleParam / "item")
         .and(warp::post())
+        .and(rate_limit("get_table_item", &context))
         .and(warp::body::content_length_limit(
             context.content_length_limit(),
         ))
         .and(warp::body::json::<TableItemRequest>())
+        .and(accept_type())
         .and(context.filter())
         .and(warp::query::<Version>())
-        .map(|handle, body, ctx, version: Version| (version.version, handle, body, ctx))
+        .map(|handle, body, accept_type, ctx, version: Version| {
+            (version.version, handle, body, accept_type, ctx)
+        })
         .untuple_one()
         .and_then(handle_get_table_item)
         .with(metrics("get_table_item"))
         .boxed()
 }
 
+// POST /tables/<table_handle>/raw_item
+pub fn get_raw_table_item(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("tables" / TableHandleParam / "raw_item")
+        .and(warp::post())
+        .and(rate_limit("get_raw_table_item", &context))
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<RawTableItemRequest>())
+        .and(accept_type())
+        .and(context.filter())
+        .and(warp::query::<Version>())
+        .map(|handle, body, accept_type, ctx, version: Version| {
+            (version.version, handle, body, accept_type, ctx)
+        })
+        .untuple_one()
+        .and_then(handle_get_raw_table_item)
+        .with(metrics("get_raw_table_item"))
+        .boxed()
+}
+
 async fn handle_get_account_resource(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
     struct_tag: MoveStructTagParam,
+    accept_type: AcceptType,
     context: Context,
 ) -> anyhow::Result<impl Reply, Rejection> {
     fail_point("endpoint_query_resource")?;
@@ -85,6 +134,7 @@ async fn handle_get_account_resource(
             .clone()
             .try_into()
             .map_err(|_| Error::invalid_param("resource_type", struct_tag))?,
+        accept_type,
     )?)
 }
 
@@ -92,12 +142,27 @@ async fn handle_get_account_module(
     ledger_version: Option<LedgerVersionParam>,
     address: AddressParam,
     name: MoveIdentifierParam,
+    accept_type: AcceptType,
     context: Context,
 ) -> anyhow::Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_module")?;
     Ok(State::new(ledger_version, context)?.module(
         address.parse("account address")?.into(),
         name.parse("module name")?,
+        accept_type,
+    )?)
+}
+
+async fn handle_get_account_module_abi(
+    ledger_version: Option<LedgerVersionParam>,
+    address: AddressParam,
+    name: MoveIdentifierParam,
+    context: Context,
+) -> anyhow::Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_account_module_abi")?;
+    Ok(State::new(ledger_version, context)?.module_abi(
+        address.parse("account address")?.into(),
+        name.parse("module name")?,
     )?)
 }
 
@@ -105,10 +170,30 @@ async fn handle_get_table_item(
     ledger_version: Option<LedgerVersionParam>,
     handle: TableHandleParam,
     body: TableItemRequest,
+    accept_type: AcceptType,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_table_item")?;
-    Ok(State::new(ledger_version, context)?.table_item(handle.parse("table handle")?, body)?)
+    Ok(State::new(ledger_version, context)?.table_item(
+        handle.parse("table handle")?,
+        body,
+        accept_type,
+    )?)
+}
+
+async fn handle_get_raw_table_item(
+    ledger_version: Option<LedgerVersionParam>,
+    handle: TableHandleParam,
+    body: RawTableItemRequest,
+    accept_type: AcceptType,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_get_raw_table_item")?;
+    Ok(State::new(ledger_version, context)?.raw_table_item(
+        handle.parse("table handle")?,
+        body,
+        accept_type,
+    )?)
 }
 
 pub(crate) struct State {
@@ -148,6 +233,7 @@ impl State {
         self,
         address: AccountAddress,
         struct_tag: StructTag,
+        accept_type: AcceptType,
     ) -> Result<impl Reply, Error> {
         let resource_key = ResourceKey::new(address, struct_tag.clone());
         let access_path = AccessPath::resource_access_path(resource_key.clone());
@@ -156,6 +242,9 @@ impl State {
             .state_view
             .get_state_value(&state_key)?
             .ok_or_else(|| Error::not_found("Resource", resource_key, self.ledger_version))?;
+        if accept_type == AcceptType::Bcs {
+            return Ok(Response::from_bcs_bytes(self.latest_ledger_info, bytes));
+        }
 
         let resource = self
             .state_view
@@ -165,7 +254,12 @@ impl State {
         Response::new(self.latest_ledger_info, &resource)
     }
 
-    pub fn module(self, address: AccountAddress, name: Identifier) -> Result<impl Reply, Error> {
+    pub fn module(
+        self,
+        address: AccountAddress,
+        name: Identifier,
+        accept_type: AcceptType,
+    ) -> Result<impl Reply, Error> {
         let module_id = ModuleId::new(address, name);
         let access_path = AccessPath::code_access_path(module_id.clone());
         let state_key = StateKey::AccessPath(access_path);
@@ -173,6 +267,9 @@ impl State {
             .state_view
             .get_state_value(&state_key)?
             .ok_or_else(|| Error::not_found("Module", module_id, self.ledger_version))?;
+        if accept_type == AcceptType::Bcs {
+            return Ok(Response::from_bcs_bytes(self.latest_ledger_info, bytes));
+        }
 
         let module = MoveModuleBytecode::new(bytes)
             .try_parse_abi()
@@ -180,7 +277,34 @@ impl State {
         Response::new(self.latest_ledger_info, &module)
     }
 
-    pub fn table_item(self, handle: u128, body: TableItemRequest) -> Result<impl Reply, Error> {
+    pub fn module_abi(
+        self,
+        address: AccountAddress,
+        name: Identifier,
+    ) -> Result<impl Reply, Error> {
+        let module_id = ModuleId::new(address, name);
+        let access_path = AccessPath::code_access_path(module_id.clone());
+        let state_key = StateKey::AccessPath(access_path);
+        let bytes = self
+            .state_view
+            .get_state_value(&state_key)?
+            .ok_or_else(|| Error::not_found("Module", module_id, self.ledger_version))?;
+
+        let abi: MoveModuleAbi = MoveModuleBytecode::new(bytes)
+            .try_parse_abi()
+            .map_err(Error::internal)?
+            .abi
+            .ok_or_else(|| Error::internal(anyhow!("invalid module bytecode")))?
+            .into();
+        Response::new(self.latest_ledger_info, &abi)
+    }
+
+    pub fn table_item(
+        self,
+        handle: u128,
+        body: TableItemRequest,
+        accept_type: AcceptType,
+    ) -> Result<impl Reply, Error> {
         let TableItemRequest {
             key_type,
             value_type,
@@ -205,8 +329,30 @@ impl State {
             .state_view
             .get_state_value(&state_key)?
             .ok_or_else(|| Error::not_found("table handle or item", key, self.ledger_version))?;
+        if accept_type == AcceptType::Bcs {
+            return Ok(Response::from_bcs_bytes(self.latest_ledger_info, bytes));
+        }
 
         let move_value = converter.try_into_move_value(&value_type, &bytes)?;
         Response::new(self.latest_ledger_info, &move_value)
     }
+
+    pub fn raw_table_item(
+        self,
+        handle: u128,
+        body: RawTableItemRequest,
+        accept_type: AcceptType,
+    ) -> Result<impl Reply, Error> {
+        let key = body.key;
+        let state_key = StateKey::table_item(handle, key.inner().to_vec());
+        let bytes = self
+            .state_view
+            .get_state_value(&state_key)?
+            .ok_or_else(|| Error::not_found("table handle or item", key, self.ledger_version))?;
+        if accept_type == AcceptType::Bcs {
+            return Ok(Response::from_bcs_bytes(self.latest_ledger_info, bytes));
+        }
+
+        Response::new(self.latest_ledger_info, &HexEncodedBytes::from(bytes))
+    }
 }
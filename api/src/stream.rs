@@ -0,0 +1,105 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{context::Context, param::Param};
+
+use aptos_api_types::{mime_types::NDJSON, Error, LedgerInfo};
+
+use bytes::Bytes;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, time::Duration};
+use warp::{hyper::Body, reply::Response, Reply};
+
+/// Max number of items fetched from the DB per poll.
+pub(crate) const STREAM_BATCH_SIZE: u16 = 100;
+
+/// How long a stream waits before polling again once it caught up with the ledger. The interval
+/// doubles on every poll returning nothing, up to `MAX_STREAM_POLL_INTERVAL`.
+const MIN_STREAM_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_STREAM_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct StreamQuery {
+    start: Option<Param<u64>>,
+}
+
+impl StreamQuery {
+    pub fn start(self) -> Result<Option<u64>, Error> {
+        self.start.map(|v| v.parse("start")).transpose()
+    }
+}
+
+/// Creates a chunked `application/x-ndjson` response that never ends on its own.
+///
+/// `fetch` is called with the cursor of the next item, i.e. a transaction version or an event
+/// sequence number, and the cursor advances by the number of items returned. As it reads the DB,
+/// it runs on the blocking thread pool. When no items are returned, the stream backs off before
+/// polling again. An error is sent as the last line of the stream.
+///
+/// At most `max_concurrent_streams` streams are served at once, others are rejected.
+///
+/// The ledger info headers are the ones of the ledger when the stream started.
+pub(crate) fn ndjson_stream<T, F>(
+    context: &Context,
+    ledger_info: LedgerInfo,
+    start: u64,
+    fetch: F,
+) -> Result<Response, Error>
+where
+    T: Serialize,
+    F: FnMut(u64) -> Result<Vec<T>, Error> + Send + 'static,
+{
+    let permit = context.try_acquire_stream_permit()?;
+    let state = (start, fetch, MIN_STREAM_POLL_INTERVAL, permit);
+    let stream = futures::stream::unfold(Some(state), |state| async move {
+        let (cursor, mut fetch, mut poll_interval, permit) = state?;
+        loop {
+            let result = tokio::task::spawn_blocking(move || {
+                let chunk = fetch(cursor).and_then(|items| Ok((to_ndjson(&items)?, items.len())));
+                (fetch, chunk)
+            })
+            .await;
+            let (returned_fetch, chunk) = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    let chunk = to_ndjson(&[Error::internal(err.into())]).unwrap_or_default();
+                    return Some((Ok(chunk), None));
+                }
+            };
+            fetch = returned_fetch;
+            match chunk {
+                Ok((_, 0)) => {
+                    tokio::time::sleep(poll_interval).await;
+                    poll_interval = std::cmp::min(poll_interval * 2, MAX_STREAM_POLL_INTERVAL);
+                }
+                Ok((chunk, len)) => {
+                    let state = (cursor + len as u64, fetch, MIN_STREAM_POLL_INTERVAL, permit);
+                    return Some((Ok(chunk), Some(state)));
+                }
+                Err(err) => {
+                    let chunk = to_ndjson(&[err]).unwrap_or_default();
+                    return Some((Ok::<_, Infallible>(chunk), None));
+                }
+            }
+        }
+    });
+
+    let mut response = aptos_api_types::Response {
+        ledger_info,
+        body: vec![],
+        content_type: NDJSON,
+        page_info: None,
+    }
+    .into_response();
+    *response.body_mut() = Body::wrap_stream(stream);
+    Ok(response)
+}
+
+fn to_ndjson<T: Serialize>(items: &[T]) -> Result<Bytes, Error> {
+    let mut buf = vec![];
+    for item in items {
+        serde_json::to_writer(&mut buf, item)?;
+        buf.push(b'\n');
+    }
+    Ok(buf.into())
+}
@@ -5,7 +5,10 @@ use crate::{
     current_function_name,
     tests::{find_value, new_test_context},
 };
+use aptos_types::account_config::AccountResource;
+use move_deps::move_core_types::{language_storage::StructTag, move_resource::MoveStructType};
 use serde_json::json;
+use std::collections::BTreeMap;
 
 #[tokio::test]
 async fn test_get_account_resources_returns_empty_array_for_account_has_no_resources() {
@@ -190,6 +193,22 @@ async fn test_get_account_modules_by_ledger_version() {
     assert_eq!(modules, json!([]));
 }
 
+#[tokio::test]
+async fn test_get_account_modules_abi() {
+    let context = new_test_context(current_function_name!());
+    let modules = context.get(&account_modules("0x1")).await;
+    let abis = context.get("/accounts/0x1/modules/abi").await;
+    let abis = abis.as_array().unwrap();
+    assert_eq!(abis.len(), modules.as_array().unwrap().len());
+
+    let coin = abis.iter().find(|abi| abi["name"] == "Coin").unwrap();
+    assert!(coin["script_functions"]
+        .as_array()
+        .unwrap()
+        .contains(&json!("0x1::Coin::register")));
+    assert!(!coin["structs"].as_array().unwrap().is_empty());
+}
+
 #[tokio::test]
 async fn test_get_core_account_data() {
     let mut context = new_test_context(current_function_name!());
@@ -204,6 +223,33 @@ async fn test_get_core_account_data_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_account_bcs() {
+    let context = new_test_context(current_function_name!());
+    let address = context.root_account().address();
+
+    let bytes = context.get_bcs(&format!("/accounts/{}", address)).await;
+    let account: AccountResource = bcs::from_bytes(&bytes).unwrap();
+    let json = context.get(&format!("/accounts/{}", address)).await;
+    assert_eq!(
+        json["sequence_number"],
+        account.sequence_number().to_string()
+    );
+}
+
+#[tokio::test]
+async fn test_get_account_resources_bcs() {
+    let context = new_test_context(current_function_name!());
+    let address = context.root_account().address();
+
+    let bytes = context
+        .get_bcs(&account_resources(&address.to_string()))
+        .await;
+    let resources: BTreeMap<StructTag, Vec<u8>> = bcs::from_bytes(&bytes).unwrap();
+    let account_resource = resources.get(&AccountResource::struct_tag()).unwrap();
+    bcs::from_bytes::<AccountResource>(account_resource).unwrap();
+}
+
 fn account_resources(address: &str) -> String {
     format!("/accounts/{}/resources", address)
 }
@@ -0,0 +1,84 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{current_function_name, tests::new_test_context};
+
+#[tokio::test]
+async fn test_get_genesis_block_by_height() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context.get("/blocks/by_height/0").await;
+    assert_eq!(resp["block_height"], "0");
+    assert_eq!(resp["first_version"], "0");
+    assert_eq!(resp["last_version"], "0");
+    assert!(resp.get("transactions").is_none());
+}
+
+#[tokio::test]
+async fn test_get_block_by_height_with_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    for _ in 0..2 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&vec![txn]).await;
+    }
+
+    // Each block has a block metadata, a user and a state checkpoint transaction.
+    let resp = context
+        .get("/blocks/by_height/1?with_transactions=true")
+        .await;
+    assert_eq!(resp["block_height"], "1");
+    assert_eq!(resp["first_version"], "1");
+    assert_eq!(resp["last_version"], "3");
+    let txns = resp["transactions"].as_array().unwrap();
+    assert_eq!(txns.len(), 3);
+    assert_eq!(txns[0]["type"], "block_metadata_transaction");
+    assert_eq!(txns[0]["id"], resp["block_hash"]);
+    assert_eq!(txns[0]["timestamp"], resp["block_timestamp"]);
+    assert_eq!(txns[1]["type"], "user_transaction");
+
+    let resp = context.get("/blocks/by_height/2").await;
+    assert_eq!(resp["first_version"], "4");
+    assert_eq!(resp["last_version"], "6");
+}
+
+#[tokio::test]
+async fn test_get_block_by_version() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    for _ in 0..2 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        context.commit_block(&vec![txn]).await;
+    }
+
+    for version in 4..=6 {
+        let resp = context
+            .get(&format!("/blocks/by_version/{}", version))
+            .await;
+        assert_eq!(resp["block_height"], "2");
+        assert_eq!(resp["first_version"], "4");
+        assert_eq!(resp["last_version"], "6");
+    }
+    let resp = context.get("/blocks/by_version/0").await;
+    assert_eq!(resp["block_height"], "0");
+}
+
+#[tokio::test]
+async fn test_get_block_not_found() {
+    let context = new_test_context(current_function_name!());
+
+    context
+        .expect_status_code(404)
+        .get("/blocks/by_height/1")
+        .await;
+    context
+        .expect_status_code(404)
+        .get("/blocks/by_version/1000")
+        .await;
+    context
+        .expect_status_code(400)
+        .get("/blocks/by_height/0?with_transactions=yes")
+        .await;
+}
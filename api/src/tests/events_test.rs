@@ -2,7 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{current_function_name, tests::new_test_context};
+use aptos_api_types::{X_APTOS_CURSOR, X_APTOS_TOTAL_COUNT};
+use aptos_types::contract_event::EventWithVersion;
+use hyper::body::HttpBody;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::Value;
 
 static EVENT_KEY: &str =
     "0x0500000000000000000000000000000000000000000000000000000000000000000000000a550c18";
@@ -16,6 +20,44 @@ async fn test_get_events() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_events_bcs() {
+    let context = new_test_context(current_function_name!());
+
+    let bytes = context
+        .get_bcs(format!("/events/{}", EVENT_KEY).as_str())
+        .await;
+    let events: Vec<EventWithVersion> = bcs::from_bytes(&bytes).unwrap();
+    let resp = context.get(format!("/events/{}", EVENT_KEY).as_str()).await;
+    assert_eq!(events.len(), resp.as_array().unwrap().len());
+    for (event, json) in events.iter().zip(resp.as_array().unwrap()) {
+        assert_eq!(json["key"], format!("0x{}", event.event.key()));
+        assert_eq!(
+            json["sequence_number"],
+            event.event.sequence_number().to_string()
+        );
+    }
+    // The events of the genesis transaction
+    assert!(events.iter().all(|event| event.transaction_version == 0));
+}
+
+#[tokio::test]
+async fn test_stream_events() {
+    let context = new_test_context(current_function_name!());
+
+    let mut resp = context
+        .get_stream(format!("/events/{}/stream", EVENT_KEY).as_str())
+        .await;
+    let chunk = resp.body_mut().data().await.unwrap().unwrap();
+    let events: Vec<Value> = std::str::from_utf8(&chunk)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let resp = context.get(format!("/events/{}", EVENT_KEY).as_str()).await;
+    assert_eq!(&events, resp.as_array().unwrap());
+}
+
 #[tokio::test]
 async fn test_get_events_filter_by_start_sequence_number() {
     let mut context = new_test_context(current_function_name!());
@@ -43,6 +85,28 @@ async fn test_get_events_filter_by_limit_page_size() {
     assert_eq!(resp.as_array().unwrap().len(), 2);
 }
 
+#[tokio::test]
+async fn test_get_events_in_reverse_order() {
+    let context = new_test_context(current_function_name!());
+
+    let events = context.get(format!("/events/{}", EVENT_KEY).as_str()).await;
+    let mut events = events.as_array().unwrap().clone();
+    events.reverse();
+
+    let path = format!("/events/{}?reverse=true", EVENT_KEY);
+    let resp = context
+        .reply(warp::test::request().method("GET").path(&path))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(
+        resp.headers()[X_APTOS_TOTAL_COUNT],
+        events.len().to_string().as_str()
+    );
+    assert!(resp.headers().get(X_APTOS_CURSOR).is_none());
+    let page: Value = serde_json::from_slice(resp.body()).unwrap();
+    assert_eq!(page.as_array().unwrap(), &events);
+}
+
 #[tokio::test]
 async fn test_get_events_by_invalid_key() {
     let mut context = new_test_context(current_function_name!());
@@ -0,0 +1,90 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    current_function_name,
+    tests::{new_test_context, TestContext},
+};
+
+use aptos_types::{account_config::aptos_root_address, transaction::SignedTransaction};
+
+#[tokio::test]
+async fn test_get_mempool_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let (ready_txn, _) = submit_ready_and_parked_transactions(&mut context).await;
+
+    let resp = context
+        .get(&format!(
+            "/mempool/transactions?account={}",
+            aptos_root_address().to_hex_literal()
+        ))
+        .await;
+    let txns = resp.as_array().unwrap();
+    assert_eq!(txns.len(), 2);
+    assert_eq!(txns[0]["status"], "ready");
+    assert_eq!(txns[0]["transaction"]["type"], "pending_transaction");
+    assert_eq!(
+        txns[0]["transaction"]["hash"],
+        ready_txn.committed_hash().to_hex_literal()
+    );
+    assert_eq!(txns[0]["transaction"]["sequence_number"], "0");
+    assert_eq!(txns[1]["status"], "parked");
+    assert_eq!(txns[1]["transaction"]["sequence_number"], "2");
+}
+
+#[tokio::test]
+async fn test_get_mempool_transactions_of_unknown_account() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+
+    let resp = context
+        .get(&format!(
+            "/mempool/transactions?account={}",
+            account.address().to_hex_literal()
+        ))
+        .await;
+    assert_eq!(resp.as_array().unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn test_get_mempool_transactions_with_invalid_account() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/mempool/transactions?account=invalid")
+        .await;
+    assert_eq!(resp["code"], 400);
+}
+
+#[tokio::test]
+async fn test_get_mempool_stats() {
+    let mut context = new_test_context(current_function_name!());
+    submit_ready_and_parked_transactions(&mut context).await;
+
+    let resp = context.get("/mempool/stats").await;
+    assert_eq!(resp["system_ttl_index"], "2");
+    assert_eq!(resp["transaction_hash_index"], "2");
+    assert_eq!(resp["priority_index"], "1");
+    assert_eq!(resp["parking_lot_index"], "1");
+}
+
+/// Submits two transactions of the root account, the second one skips a sequence number so it
+/// waits in the parking lot.
+async fn submit_ready_and_parked_transactions(
+    context: &mut TestContext,
+) -> (SignedTransaction, SignedTransaction) {
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let ready_txn = context.create_user_account_by(&mut root_account, &account);
+    *root_account.sequence_number_mut() += 1;
+    let account = context.gen_account();
+    let parked_txn = context.create_user_account_by(&mut root_account, &account);
+    for txn in [&ready_txn, &parked_txn] {
+        context
+            .expect_status_code(202)
+            .post_bcs_txn("/transactions", bcs::to_bytes(txn).unwrap())
+            .await;
+    }
+    (ready_txn, parked_txn)
+}
@@ -2,11 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod accounts_test;
+mod blocks_test;
 mod converter_test;
 mod events_test;
 mod golden_output;
 mod index_test;
 mod invalid_post_request_test;
+mod mempool_test;
+mod rate_limit_test;
 mod state_test;
 mod string_resource_test;
 mod test_context;
@@ -14,7 +17,7 @@ mod transaction_vector_test;
 mod transactions_test;
 
 use serde_json::Value;
-pub use test_context::{new_test_context, TestContext};
+pub use test_context::{new_test_context, new_test_context_with_min_gas_unit_price, TestContext};
 
 pub fn find_value(val: &Value, filter: for<'r> fn(&'r &Value) -> bool) -> Value {
     let resources = val
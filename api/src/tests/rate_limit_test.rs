@@ -0,0 +1,105 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::Context,
+    current_function_name, index,
+    rate_limit::X_APTOS_API_KEY,
+    tests::{new_test_context, TestContext},
+};
+
+use aptos_config::config::{ApiConfig, ApiRateLimitConfig};
+use aptos_types::chain_id::ChainId;
+use std::net::SocketAddr;
+use warp::http::{header::RETRY_AFTER, StatusCode};
+
+const API_KEY: &str = "test-api-key";
+
+#[tokio::test]
+async fn test_rate_limit_by_ip() {
+    let context = rate_limited_context(current_function_name!());
+    let client: SocketAddr = "10.0.0.1:4321".parse().unwrap();
+
+    for _ in 0..2 {
+        let resp = get(&context, "/", client, None).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+    let resp = get(&context, "/", client, None).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(resp.headers()[RETRY_AFTER], "1");
+
+    // Other clients have their own quota.
+    let other_client: SocketAddr = "10.0.0.2:4321".parse().unwrap();
+    let resp = get(&context, "/", other_client, None).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_rate_limit_by_api_key() {
+    let context = rate_limited_context(current_function_name!());
+    let client: SocketAddr = "10.0.0.1:4321".parse().unwrap();
+
+    for _ in 0..2 {
+        let resp = get(&context, "/", client, None).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+    // A configured API key is throttled by its own quota instead of the IP one.
+    for _ in 0..4 {
+        let resp = get(&context, "/", client, Some(API_KEY)).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+    let resp = get(&context, "/", client, Some(API_KEY)).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Unknown API keys fall back to the IP quota.
+    let resp = get(&context, "/", client, Some("unknown")).await;
+    assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_rate_limit_disabled_by_default() {
+    let context = new_test_context(current_function_name!());
+    let client: SocketAddr = "10.0.0.1:4321".parse().unwrap();
+
+    for _ in 0..10 {
+        let resp = get(&context.context, "/", client, None).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
+
+fn rate_limited_context(test_name: &'static str) -> Context {
+    let context: TestContext = new_test_context(test_name);
+    let api_config = ApiConfig {
+        rate_limit: Some(ApiRateLimitConfig {
+            ip_bucket_rate: 1,
+            ip_bucket_size: 2,
+            api_key_bucket_rate: 1,
+            api_key_bucket_size: 4,
+            api_keys: vec![API_KEY.to_owned()],
+            ..ApiRateLimitConfig::default()
+        }),
+        ..ApiConfig::default()
+    };
+    Context::new(
+        ChainId::test(),
+        context.db.clone(),
+        context.mempool.ac_client.clone(),
+        api_config,
+    )
+}
+
+async fn get(
+    context: &Context,
+    path: &str,
+    remote: SocketAddr,
+    api_key: Option<&str>,
+) -> warp::http::Response<bytes::Bytes> {
+    let mut req = warp::test::request()
+        .method("GET")
+        .path(path)
+        .remote_addr(remote);
+    if let Some(key) = api_key {
+        req = req.header(X_APTOS_API_KEY, key);
+    }
+    req.reply(&index::routes(context.clone())).await
+}
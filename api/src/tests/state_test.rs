@@ -93,6 +93,35 @@ async fn test_get_account_module_not_found() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_account_module_abi() {
+    let context = new_test_context(current_function_name!());
+    let resp = context.get("/accounts/0x1/modules/Coin/abi").await;
+    assert_eq!(resp["address"], "0x1");
+    assert_eq!(resp["name"], "Coin");
+    let script_functions = resp["script_functions"].as_array().unwrap();
+    assert!(script_functions.contains(&json!("0x1::Coin::transfer")));
+    assert!(!script_functions.contains(&json!("0x1::Coin::balance")));
+
+    let transfer = resp["exposed_functions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["name"] == "transfer")
+        .unwrap();
+    assert_eq!(transfer["visibility"], "script");
+    assert_eq!(transfer["generic_type_params"].as_array().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_get_account_module_abi_not_found() {
+    let mut context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(404)
+        .get("/accounts/0x1/modules/NoNoNo/abi")
+        .await;
+}
+
 #[tokio::test]
 async fn test_get_table_item() {
     let mut context = new_test_context(current_function_name!());
@@ -176,6 +205,40 @@ async fn test_get_table_item() {
     assert_table_item(ctx, &nested_table, "u8", "u8", 2, 3).await;
 }
 
+#[tokio::test]
+async fn test_get_raw_table_item() {
+    let mut context = new_test_context(current_function_name!());
+    let ctx = &mut context;
+    let mut account = ctx.gen_account();
+    let acc = &mut account;
+    let txn = ctx.create_user_account(acc);
+    ctx.commit_block(&vec![txn.clone()]).await;
+    make_test_tables(ctx, acc).await;
+
+    let tt = ctx
+        .api_get_account_resource(
+            acc,
+            format!(
+                "{}::TableTestData::TestTables",
+                acc.address().to_hex_literal()
+            ),
+        )
+        .await["data"]
+        .to_owned();
+
+    let handle: u128 = tt["u64_table"]["handle"].as_str().unwrap().parse().unwrap();
+    let key = format!("0x{}", hex::encode(bcs::to_bytes(&1u64).unwrap()));
+    let resp = ctx
+        .post(&get_raw_table_item(handle), json!({ "key": key }))
+        .await;
+    assert_eq!(resp, json!(key));
+
+    let missing_key = format!("0x{}", hex::encode(bcs::to_bytes(&2u64).unwrap()));
+    ctx.expect_status_code(404)
+        .post(&get_raw_table_item(handle), json!({ "key": missing_key }))
+        .await;
+}
+
 fn get_account_resource(address: &str, struct_tag: &str) -> String {
     format!("/accounts/{}/resource/{}", address, struct_tag)
 }
@@ -188,6 +251,10 @@ fn get_table_item(handle: u128) -> String {
     format!("/tables/{}/item", handle)
 }
 
+fn get_raw_table_item(handle: u128) -> String {
+    format!("/tables/{}/raw_item", handle)
+}
+
 async fn make_test_tables(ctx: &mut TestContext, account: &mut LocalAccount) {
     let module = build_test_module(account.address()).await;
 
@@ -30,7 +30,7 @@ use aptosdb::AptosDB;
 use bytes::Bytes;
 use executor::db_bootstrapper;
 use executor_types::BlockExecutorTrait;
-use hyper::Response;
+use hyper::{Body, Response};
 use mempool_notifications::MempoolNotificationSender;
 use storage_interface::DbReaderWriter;
 
@@ -43,9 +43,19 @@ use serde_json::{json, Value};
 use std::{boxed::Box, collections::BTreeMap, iter::once, sync::Arc};
 use storage_interface::state_view::DbStateView;
 use vm_validator::vm_validator::VMValidator;
-use warp::http::header::CONTENT_TYPE;
+use warp::{
+    http::header::{ACCEPT, CONTENT_TYPE},
+    Reply,
+};
 
 pub fn new_test_context(test_name: &'static str) -> TestContext {
+    new_test_context_with_min_gas_unit_price(test_name, 0)
+}
+
+pub fn new_test_context_with_min_gas_unit_price(
+    test_name: &'static str,
+    min_price_per_gas_unit: u64,
+) -> TestContext {
     let tmp_dir = TempPath::new();
     tmp_dir.create_as_dir().unwrap();
 
@@ -55,7 +65,7 @@ pub fn new_test_context(test_name: &'static str) -> TestContext {
         cached_framework_packages::module_blobs().to_vec(),
     )
     .unwrap()
-    .with_min_price_per_gas_unit(0)
+    .with_min_price_per_gas_unit(min_price_per_gas_unit)
     .with_randomize_first_validator_ports(false);
 
     let (root_key, genesis, genesis_waypoint, validators) = builder.build(&mut rng).unwrap();
@@ -366,6 +376,44 @@ impl TestContext {
         .await
     }
 
+    pub async fn post_bcs_txns_batch(&self, txns: &[SignedTransaction]) -> Value {
+        self.execute(
+            warp::test::request()
+                .method("POST")
+                .path("/transactions/batch")
+                .header(CONTENT_TYPE, mime_types::BCS_SIGNED_TRANSACTION_BATCH)
+                .body(bcs::to_bytes(txns).unwrap()),
+        )
+        .await
+    }
+
+    pub async fn get_bcs(&self, path: &str) -> Bytes {
+        let resp = self
+            .reply(
+                warp::test::request()
+                    .method("GET")
+                    .path(path)
+                    .header(ACCEPT, mime_types::BCS),
+            )
+            .await;
+        assert_eq!(self.expect_status_code, resp.status());
+        assert_eq!(resp.headers()[CONTENT_TYPE], mime_types::BCS);
+        resp.into_body()
+    }
+
+    /// Sends a request to a streaming route, the body of the response never ends.
+    pub async fn get_stream(&self, path: &str) -> Response<Body> {
+        let resp = warp::test::request()
+            .method("GET")
+            .path(path)
+            .filter(&index::routes(self.context.clone()))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(self.expect_status_code, resp.status());
+        resp
+    }
+
     pub async fn reply(&self, req: warp::test::RequestBuilder) -> Response<Bytes> {
         req.reply(&index::routes(self.context.clone())).await
     }
@@ -2,11 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    context::Context,
     current_function_name,
-    tests::{assert_json, new_test_context, pretty, TestContext},
+    tests::{
+        assert_json, new_test_context, new_test_context_with_min_gas_unit_price, pretty,
+        TestContext,
+    },
 };
 
-use aptos_api_types::HexEncodedBytes;
+use aptos_api_types::{
+    mime_types, AsConverter, HexEncodedBytes, RawGasEstimationRequest, TransactionData,
+    TransactionOnChainData, X_APTOS_CURSOR, X_APTOS_TOTAL_COUNT,
+};
+use aptos_config::config::ApiConfig;
 use aptos_crypto::{
     multi_ed25519::{MultiEd25519PrivateKey, MultiEd25519PublicKey},
     PrivateKey, SigningKey, Uniform,
@@ -15,6 +23,7 @@ use aptos_sdk::types::LocalAccount;
 use aptos_types::{
     access_path::{AccessPath, Path},
     account_address::AccountAddress,
+    chain_id::ChainId,
     transaction::{
         authenticator::{AuthenticationKey, TransactionAuthenticator},
         ChangeSet, Script, ScriptFunction, SignedTransaction,
@@ -25,12 +34,15 @@ use aptos_types::{
 
 use aptos_crypto::ed25519::Ed25519PrivateKey;
 use aptos_types::state_store::state_key::StateKey;
+use cached_framework_packages::aptos_stdlib;
+use hyper::body::HttpBody;
 use move_deps::move_core_types::{
     identifier::Identifier,
     language_storage::{ModuleId, StructTag, TypeTag},
 };
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use serde_json::json;
+use warp::http::header::CONTENT_TYPE;
 
 #[tokio::test]
 async fn test_deserialize_genesis_transaction() {
@@ -48,6 +60,105 @@ async fn test_get_transactions_output_genesis_transaction() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_get_transactions_bcs() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let bytes = context.get_bcs("/transactions?start=0&limit=25").await;
+    let txns: Vec<TransactionOnChainData> = bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(txns, context.get_transactions(0, 25));
+}
+
+#[tokio::test]
+async fn test_get_transaction_by_version_bcs() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let bytes = context.get_bcs("/transactions/2").await;
+    match bcs::from_bytes::<TransactionData>(&bytes).unwrap() {
+        TransactionData::OnChain(data) => {
+            assert_eq!(data.transaction.as_signed_user_txn().unwrap(), &txn);
+        }
+        TransactionData::Pending(_) => panic!("expected an on-chain transaction"),
+    }
+}
+
+#[tokio::test]
+async fn test_stream_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+
+    let mut resp = context.get_stream("/transactions/stream?start=0").await;
+    assert_eq!(resp.headers()[CONTENT_TYPE], mime_types::NDJSON);
+    let txns = next_ndjson_chunk(resp.body_mut()).await;
+    let versions: Vec<_> = txns.iter().map(|txn| txn["version"].clone()).collect();
+    assert_eq!(versions, vec!["0", "1", "2", "3"]);
+
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+
+    let txns = next_ndjson_chunk(resp.body_mut()).await;
+    let versions: Vec<_> = txns.iter().map(|txn| txn["version"].clone()).collect();
+    assert_eq!(versions, vec!["4", "5", "6"]);
+    assert_eq!(txns[1]["sender"], root_account.address().to_hex_literal());
+}
+
+#[tokio::test]
+async fn test_stream_transactions_defaults_to_new_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let mut resp = context.get_stream("/transactions/stream").await;
+
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let txn = context.create_user_account_by(&mut root_account, &account);
+    context.commit_block(&vec![txn]).await;
+
+    let txns = next_ndjson_chunk(resp.body_mut()).await;
+    assert_eq!(txns[0]["version"], "1");
+}
+
+#[tokio::test]
+async fn test_stream_transactions_limits_concurrent_streams() {
+    let mut context = new_test_context(current_function_name!());
+    context.context = Context::new(
+        ChainId::test(),
+        context.db.clone(),
+        context.mempool.ac_client.clone(),
+        ApiConfig {
+            max_concurrent_streams: 1,
+            ..ApiConfig::default()
+        },
+    );
+
+    let resp = context.get_stream("/transactions/stream").await;
+    context
+        .expect_status_code(503)
+        .get_stream("/transactions/stream")
+        .await;
+
+    // closing the stream releases its slot
+    drop(resp);
+    context.get_stream("/transactions/stream").await;
+}
+
+#[tokio::test]
+async fn test_stream_transactions_with_invalid_start() {
+    let context = new_test_context(current_function_name!());
+    context
+        .expect_status_code(400)
+        .get("/transactions/stream?start=abc")
+        .await;
+}
+
 #[tokio::test]
 async fn test_get_transactions_returns_last_page_when_start_version_is_not_specified() {
     let mut context = new_test_context(current_function_name!());
@@ -240,6 +351,92 @@ async fn test_post_bcs_format_transaction() {
         .await;
 }
 
+#[tokio::test]
+async fn test_post_bcs_format_transactions_batch() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let mut txns = vec![];
+    for _ in 0..2 {
+        let account = context.gen_account();
+        txns.push(context.create_user_account_by(&mut root_account, &account));
+    }
+    // Reuses sequence number 0 with another payload.
+    let account = context.gen_account();
+    txns.push(context.create_user_account(&account));
+    txns.push(context.create_invalid_signature_transaction());
+
+    let resp = context.post_bcs_txns_batch(&txns).await;
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    for (txn, result) in txns.iter().zip(results) {
+        assert_eq!(result["hash"], txn.committed_hash().to_hex_literal());
+    }
+    assert_eq!(results[0]["status"], "accepted");
+    assert_eq!(results[1]["status"], "accepted");
+    assert_eq!(results[2]["status"], "invalid_update");
+    assert!(results[2]["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("Transaction already in mempool"));
+    assert_eq!(results[3]["status"], "vm_error");
+    assert_eq!(results[3]["vm_status"], "INVALID_SIGNATURE");
+    assert_eq!(context.mempool.get_txns(10).len(), 2);
+}
+
+#[tokio::test]
+async fn test_post_json_format_transactions_batch() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let resolver = context.context.move_resolver().unwrap();
+    let mut requests = vec![];
+    for _ in 0..2 {
+        let account = context.gen_account();
+        let txn = context.create_user_account_by(&mut root_account, &account);
+        requests.push(
+            resolver
+                .as_converter()
+                .try_into_pending_transaction(txn)
+                .unwrap(),
+        );
+    }
+
+    let resp = context.post("/transactions/batch", json!(requests)).await;
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    for (request, result) in requests.iter().zip(results) {
+        assert_eq!(result["hash"], json!(request)["hash"]);
+        assert_eq!(result["status"], "accepted");
+    }
+}
+
+#[tokio::test]
+async fn test_post_transactions_batch_exceeding_max_size() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txns = vec![context.create_user_account(&account); 101];
+
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txns_batch(&txns)
+        .await;
+    assert!(resp["message"]
+        .as_str()
+        .unwrap()
+        .contains("exceeds the maximum of 100"));
+    assert!(context.mempool.get_txns(10).is_empty());
+}
+
+#[tokio::test]
+async fn test_post_transactions_batch_with_invalid_request() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .expect_status_code(400)
+        .post("/transactions/batch", json!([{"sender": "invalid"}]))
+        .await;
+    assert_eq!(resp["code"], 400);
+}
+
 #[tokio::test]
 async fn test_post_invalid_bcs_format_transaction() {
     let mut context = new_test_context(current_function_name!());
@@ -283,6 +480,242 @@ async fn test_post_transaction_rejected_by_mempool() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test]
+async fn test_simulate_bcs_format_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let ledger_version = context.get_latest_ledger_info().version();
+
+    let resp = context
+        .post_bcs_txn("/transactions/simulate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["type"], "user_transaction");
+    assert_eq!(resp["success"], true);
+    assert_eq!(resp["vm_status"], "Executed successfully");
+    assert_eq!(resp["hash"], txn.committed_hash().to_hex_literal());
+    // the simulation runs on the latest state checkpoint, the end of the last committed block
+    assert_eq!(resp["version"], ledger_version.to_string());
+    assert!(resp["gas_used"].as_str().unwrap().parse::<u64>().unwrap() > 0);
+    assert!(!resp["changes"].as_array().unwrap().is_empty());
+
+    // simulation must not commit the transaction
+    assert_eq!(ledger_version, context.get_latest_ledger_info().version());
+    context
+        .expect_status_code(404)
+        .get(&format!("/accounts/{}", account.address()))
+        .await;
+}
+
+#[tokio::test]
+async fn test_simulate_json_format_transaction() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    let pending_txn = context
+        .expect_status_code(202)
+        .post_bcs_txn("/transactions", bcs::to_bytes(&txn).unwrap())
+        .await;
+
+    let resp = context
+        .expect_status_code(200)
+        .post("/transactions/simulate", pending_txn)
+        .await;
+    assert_eq!(resp["type"], "user_transaction");
+    assert_eq!(resp["success"], true);
+}
+
+#[tokio::test]
+async fn test_simulate_transaction_does_not_verify_signature() {
+    let mut context = new_test_context(current_function_name!());
+    let txn = context.create_invalid_signature_transaction();
+
+    let resp = context
+        .post_bcs_txn("/transactions/simulate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert_eq!(resp["success"], true);
+}
+
+#[tokio::test]
+async fn test_simulate_transaction_discarded_by_vm() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account);
+    context.commit_block(&vec![txn.clone()]).await;
+
+    // the sequence number has been used by the committed transaction
+    let resp = context
+        .expect_status_code(400)
+        .post_bcs_txn("/transactions/simulate", bcs::to_bytes(&txn).unwrap())
+        .await;
+    assert!(resp["message"]
+        .as_str()
+        .unwrap()
+        .contains("SEQUENCE_NUMBER_TOO_OLD"));
+}
+
+#[tokio::test]
+async fn test_estimate_gas_bcs_format_request() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let req = RawGasEstimationRequest {
+        sender: root_account.address(),
+        public_key: root_account.public_key().clone(),
+        payload: aptos_stdlib::encode_account_create_account(account.address()),
+    };
+
+    let resp = context
+        .execute(
+            warp::test::request()
+                .method("POST")
+                .path("/transactions/estimate_gas")
+                .header(CONTENT_TYPE, mime_types::BCS_GAS_ESTIMATION_REQUEST)
+                .body(bcs::to_bytes(&req).unwrap()),
+        )
+        .await;
+    let gas_used: u64 = resp["gas_used"].as_str().unwrap().parse().unwrap();
+    let max_gas_amount: u64 = resp["max_gas_amount"].as_str().unwrap().parse().unwrap();
+    assert!(gas_used > 0);
+    assert!(max_gas_amount >= gas_used);
+    assert!(resp["gas_unit_price"].as_str().is_some());
+
+    // the suggested max gas amount is enough for the transaction to be committed
+    let txn = root_account.sign_with_transaction_builder(
+        context
+            .transaction_factory()
+            .create_user_account(account.public_key())
+            .max_gas_amount(max_gas_amount)
+            .expiration_timestamp_secs(u64::MAX),
+    );
+    context.commit_block(&vec![txn]).await;
+    context
+        .get(&format!("/accounts/{}", account.address()))
+        .await;
+}
+
+#[tokio::test]
+async fn test_estimate_gas_with_min_gas_unit_price() {
+    const MIN_GAS_UNIT_PRICE: u64 = 100;
+    // far below the maximum number of gas units at the minimum gas unit price
+    const BALANCE: u64 = 5_000_000;
+
+    let mut context =
+        new_test_context_with_min_gas_unit_price(current_function_name!(), MIN_GAS_UNIT_PRICE);
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let factory = context.transaction_factory();
+    let create_txn = root_account.sign_with_transaction_builder(
+        factory
+            .create_user_account(account.public_key())
+            .gas_unit_price(MIN_GAS_UNIT_PRICE)
+            .expiration_timestamp_secs(u64::MAX),
+    );
+    let fund_txn = root_account.sign_with_transaction_builder(
+        factory
+            .transfer(account.address(), BALANCE)
+            .gas_unit_price(MIN_GAS_UNIT_PRICE)
+            .expiration_timestamp_secs(u64::MAX),
+    );
+    context.commit_block(&vec![create_txn, fund_txn]).await;
+
+    let req = RawGasEstimationRequest {
+        sender: account.address(),
+        public_key: account.public_key().clone(),
+        payload: aptos_stdlib::encode_test_coin_transfer(root_account.address(), 1),
+    };
+    let resp = context
+        .execute(
+            warp::test::request()
+                .method("POST")
+                .path("/transactions/estimate_gas")
+                .header(CONTENT_TYPE, mime_types::BCS_GAS_ESTIMATION_REQUEST)
+                .body(bcs::to_bytes(&req).unwrap()),
+        )
+        .await;
+    let gas_used: u64 = resp["gas_used"].as_str().unwrap().parse().unwrap();
+    let max_gas_amount: u64 = resp["max_gas_amount"].as_str().unwrap().parse().unwrap();
+    let gas_unit_price: u64 = resp["gas_unit_price"].as_str().unwrap().parse().unwrap();
+    assert!(gas_used > 0);
+    assert!(max_gas_amount >= gas_used);
+    assert!(max_gas_amount * MIN_GAS_UNIT_PRICE <= BALANCE);
+    assert!(gas_unit_price >= MIN_GAS_UNIT_PRICE);
+}
+
+#[tokio::test]
+async fn test_estimate_gas_with_low_balance() {
+    const MIN_GAS_UNIT_PRICE: u64 = 100;
+    // the recent transactions pay far more than the minimum gas unit price
+    const RECENT_GAS_UNIT_PRICE: u64 = 10_000;
+    // barely enough to pay for a transfer at the recent gas unit price
+    const BALANCE: u64 = 5_000_000;
+
+    let mut context =
+        new_test_context_with_min_gas_unit_price(current_function_name!(), MIN_GAS_UNIT_PRICE);
+    let mut root_account = context.root_account();
+    let account = context.gen_account();
+    let factory = context.transaction_factory();
+    let create_txn = root_account.sign_with_transaction_builder(
+        factory
+            .create_user_account(account.public_key())
+            .gas_unit_price(RECENT_GAS_UNIT_PRICE)
+            .expiration_timestamp_secs(u64::MAX),
+    );
+    let fund_txn = root_account.sign_with_transaction_builder(
+        factory
+            .transfer(account.address(), BALANCE)
+            .gas_unit_price(RECENT_GAS_UNIT_PRICE)
+            .expiration_timestamp_secs(u64::MAX),
+    );
+    context.commit_block(&vec![create_txn, fund_txn]).await;
+
+    let req = RawGasEstimationRequest {
+        sender: account.address(),
+        public_key: account.public_key().clone(),
+        payload: aptos_stdlib::encode_test_coin_transfer(root_account.address(), 1),
+    };
+    let resp = context
+        .execute(
+            warp::test::request()
+                .method("POST")
+                .path("/transactions/estimate_gas")
+                .header(CONTENT_TYPE, mime_types::BCS_GAS_ESTIMATION_REQUEST)
+                .body(bcs::to_bytes(&req).unwrap()),
+        )
+        .await;
+    let gas_used: u64 = resp["gas_used"].as_str().unwrap().parse().unwrap();
+    let max_gas_amount: u64 = resp["max_gas_amount"].as_str().unwrap().parse().unwrap();
+    let gas_unit_price: u64 = resp["gas_unit_price"].as_str().unwrap().parse().unwrap();
+
+    // the sender can afford the suggested max gas amount at the suggested gas unit price
+    assert!(max_gas_amount >= gas_used);
+    assert!(max_gas_amount * gas_unit_price <= BALANCE);
+    assert!(gas_unit_price >= MIN_GAS_UNIT_PRICE);
+}
+
+#[tokio::test]
+async fn test_estimate_gas_for_account_not_found() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+
+    context
+        .expect_status_code(404)
+        .post(
+            "/transactions/estimate_gas",
+            json!({
+                "sender": account.address().to_hex_literal(),
+                "public_key": HexEncodedBytes::from(account.public_key().to_bytes().to_vec()),
+                "payload": {
+                    "type": "script_function_payload",
+                    "function": "0x1::Account::create_account",
+                    "type_arguments": [],
+                    "arguments": [account.address().to_hex_literal()]
+                }
+            }),
+        )
+        .await;
+}
+
 #[ignore]
 #[tokio::test]
 async fn test_multi_agent_signed_transaction() {
@@ -770,6 +1203,68 @@ async fn test_get_account_transactions_filter_transactions_by_limit() {
     assert_eq!(txns.as_array().unwrap().len(), 2);
 }
 
+#[tokio::test]
+async fn test_get_account_transactions_in_reverse_order_with_cursor() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account();
+    let mut txns = vec![];
+    for _ in 0..3 {
+        let account = context.gen_account();
+        txns.push(context.create_user_account_by(&mut root_account, &account));
+    }
+    context.commit_block(&txns).await;
+
+    let path = format!(
+        "/accounts/{}/transactions?reverse=true&limit=2",
+        context.root_account().address()
+    );
+    let resp = context
+        .reply(warp::test::request().method("GET").path(&path))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()[X_APTOS_TOTAL_COUNT], "3");
+    let cursor = resp.headers()[X_APTOS_CURSOR].to_str().unwrap().to_owned();
+    let page: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    let sequence_numbers: Vec<_> = page
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|txn| txn["sequence_number"].as_str().unwrap())
+        .collect();
+    assert_eq!(sequence_numbers, vec!["2", "1"]);
+
+    let path = format!(
+        "/accounts/{}/transactions?cursor={}&limit=2",
+        context.root_account().address(),
+        cursor
+    );
+    let resp = context
+        .reply(warp::test::request().method("GET").path(&path))
+        .await;
+    assert_eq!(resp.status(), 200);
+    assert!(resp.headers().get(X_APTOS_CURSOR).is_none());
+    let page: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    let page = page.as_array().unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0]["sequence_number"], "0");
+}
+
+#[tokio::test]
+async fn test_get_account_transactions_with_cursor_and_start() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get(
+            format!(
+                "/accounts/{}/transactions?cursor=00&start=1",
+                context.root_account().address()
+            )
+            .as_str(),
+        )
+        .await;
+    assert_eq!(resp["code"], 400);
+}
+
 #[tokio::test]
 async fn test_get_txn_execute_failed_by_invalid_module_payload_bytecode() {
     let context = new_test_context(current_function_name!());
@@ -1173,3 +1668,12 @@ fn gen_string(len: u64) -> String {
         .map(char::from)
         .collect()
 }
+
+async fn next_ndjson_chunk(body: &mut hyper::Body) -> Vec<serde_json::Value> {
+    let chunk = body.data().await.unwrap().unwrap();
+    std::str::from_utf8(&chunk)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
@@ -2,35 +2,64 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    accept_type::{accept_type, AcceptType},
     context::Context,
     failpoint::fail_point,
     metrics::metrics,
     page::Page,
     param::{AddressParam, TransactionIdParam},
+    rate_limit::rate_limit,
+    stream::{ndjson_stream, StreamQuery, STREAM_BATCH_SIZE},
 };
 
 use aptos_api_types::{
-    mime_types::BCS_SIGNED_TRANSACTION, AsConverter, Error, LedgerInfo, Response, Transaction,
-    TransactionData, TransactionId, TransactionOnChainData, TransactionSigningMessage,
+    mime_types::{
+        BCS_GAS_ESTIMATION_REQUEST, BCS_SIGNED_TRANSACTION, BCS_SIGNED_TRANSACTION_BATCH,
+    },
+    AsConverter, Error, GasEstimation, GasEstimationRequest, LedgerInfo, RawGasEstimationRequest,
+    Response, SubmissionStatusCode, Transaction, TransactionData, TransactionId,
+    TransactionOnChainData, TransactionSigningMessage, TransactionSubmissionResult,
     UserCreateSigningMessageRequest, UserTransactionRequest,
 };
-use aptos_crypto::signing_message;
+use aptos_crypto::{
+    ed25519::{Ed25519Signature, ED25519_SIGNATURE_LENGTH},
+    signing_message,
+};
+use aptos_state_view::StateView;
 use aptos_types::{
+    access_path::AccessPath,
+    account_config::{AccountResource, CoinStoreResource},
     mempool_status::MempoolStatusCode,
-    transaction::{RawTransaction, RawTransactionWithData, SignedTransaction},
+    state_store::state_key::StateKey,
+    transaction::{
+        ExecutionStatus, RawTransaction, RawTransactionWithData, SignedTransaction,
+        TransactionInfo, TransactionStatus,
+    },
+};
+use aptos_vm::{logging::AdapterLogSchema, AptosVM};
+use move_deps::move_core_types::{
+    gas_schedule::GasAlgebra, language_storage::ResourceKey, move_resource::MoveStructType,
 };
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use std::convert::TryFrom;
 use warp::{
     filters::BoxedFilter,
     http::{header::CONTENT_TYPE, StatusCode},
     reply, Filter, Rejection, Reply,
 };
 
+const GAS_ESTIMATION_EXPIRATION_SECS: u64 = 60;
+const GAS_ESTIMATION_HEADROOM_PERCENT: u64 = 150;
+const GAS_PRICE_SAMPLE_SIZE: u16 = 100;
+const MAX_TRANSACTIONS_BATCH_SIZE: usize = 100;
+
 // GET /transactions/{txn-hash / version}
 pub fn get_transaction(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions" / TransactionIdParam)
         .and(warp::get())
+        .and(accept_type())
+        .and(rate_limit("get_transaction", &context))
         .and(context.filter())
         .and_then(handle_get_transaction)
         .with(metrics("get_transaction"))
@@ -42,6 +71,8 @@ pub fn get_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions")
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(accept_type())
+        .and(rate_limit("get_transactions", &context))
         .and(context.filter())
         .and_then(handle_get_transactions)
         .with(metrics("get_transactions"))
@@ -53,16 +84,31 @@ pub fn get_account_transactions(context: Context) -> BoxedFilter<(impl Reply,)>
     warp::path!("accounts" / AddressParam / "transactions")
         .and(warp::get())
         .and(warp::query::<Page>())
+        .and(accept_type())
+        .and(rate_limit("get_account_transactions", &context))
         .and(context.filter())
         .and_then(handle_get_account_transactions)
         .with(metrics("get_account_transactions"))
         .boxed()
 }
 
+// GET /transactions/stream?start={u64}
+pub fn stream_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "stream")
+        .and(warp::get())
+        .and(warp::query::<StreamQuery>())
+        .and(rate_limit("stream_transactions", &context))
+        .and(context.filter())
+        .and_then(handle_stream_transactions)
+        .with(metrics("stream_transactions"))
+        .boxed()
+}
+
 // POST /transactions with JSON
 pub fn submit_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions")
         .and(warp::post())
+        .and(rate_limit("submit_json_transactions", &context))
         .and(warp::body::content_length_limit(
             context.content_length_limit(),
         ))
@@ -89,6 +135,7 @@ pub fn submit_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
             CONTENT_TYPE.as_str(),
             BCS_SIGNED_TRANSACTION,
         ))
+        .and(rate_limit("submit_bcs_transactions", &context))
         .and(warp::body::bytes())
         .and(context.filter())
         .and_then(handle_submit_bcs_transactions)
@@ -96,10 +143,117 @@ pub fn submit_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+// POST /transactions/batch with JSON
+pub fn submit_json_transactions_batch(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "batch")
+        .and(warp::post())
+        .and(rate_limit("submit_json_transactions_batch", &context))
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<Vec<UserTransactionRequest>>())
+        .and(context.filter())
+        .and_then(handle_submit_json_transactions_batch)
+        .with(metrics("submit_json_transactions_batch"))
+        .boxed()
+}
+
+// POST /transactions/batch with BCS
+pub fn submit_bcs_transactions_batch(context: Context) -> BoxedFilter<(impl Reply,)> {
+    // Same as `submit_bcs_transactions`, the content-type header is checked explicitly so that
+    // `submit_json_transactions_batch` emits the rejection for unsupported media types.
+    warp::path!("transactions" / "batch")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::header::exact(
+            CONTENT_TYPE.as_str(),
+            BCS_SIGNED_TRANSACTION_BATCH,
+        ))
+        .and(rate_limit("submit_bcs_transactions_batch", &context))
+        .and(warp::body::bytes())
+        .and(context.filter())
+        .and_then(handle_submit_bcs_transactions_batch)
+        .with(metrics("submit_bcs_transactions_batch"))
+        .boxed()
+}
+
+// POST /transactions/simulate with JSON
+pub fn simulate_json_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "simulate")
+        .and(warp::post())
+        .and(rate_limit("simulate_json_transactions", &context))
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<UserTransactionRequest>())
+        .and(context.filter())
+        .and_then(handle_simulate_json_transactions)
+        .with(metrics("simulate_json_transactions"))
+        .boxed()
+}
+
+// POST /transactions/simulate with BCS
+pub fn simulate_bcs_transactions(context: Context) -> BoxedFilter<(impl Reply,)> {
+    // Same as `submit_bcs_transactions`, the content-type header is checked explicitly so that
+    // `simulate_json_transactions` emits the rejection for unsupported media types.
+    warp::path!("transactions" / "simulate")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::header::exact(
+            CONTENT_TYPE.as_str(),
+            BCS_SIGNED_TRANSACTION,
+        ))
+        .and(rate_limit("simulate_bcs_transactions", &context))
+        .and(warp::body::bytes())
+        .and(context.filter())
+        .and_then(handle_simulate_bcs_transactions)
+        .with(metrics("simulate_bcs_transactions"))
+        .boxed()
+}
+
+// POST /transactions/estimate_gas with JSON
+pub fn estimate_gas_json(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "estimate_gas")
+        .and(warp::post())
+        .and(rate_limit("estimate_gas_json", &context))
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::body::json::<GasEstimationRequest>())
+        .and(context.filter())
+        .and_then(handle_estimate_gas_json)
+        .with(metrics("estimate_gas_json"))
+        .boxed()
+}
+
+// POST /transactions/estimate_gas with BCS
+pub fn estimate_gas_bcs(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("transactions" / "estimate_gas")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(
+            context.content_length_limit(),
+        ))
+        .and(warp::header::exact(
+            CONTENT_TYPE.as_str(),
+            BCS_GAS_ESTIMATION_REQUEST,
+        ))
+        .and(rate_limit("estimate_gas_bcs", &context))
+        .and(warp::body::bytes())
+        .and(context.filter())
+        .and_then(handle_estimate_gas_bcs)
+        .with(metrics("estimate_gas_bcs"))
+        .boxed()
+}
+
 // POST /transactions/signing_message
 pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("transactions" / "signing_message")
         .and(warp::post())
+        .and(rate_limit("create_signing_message", &context))
         .and(warp::body::content_length_limit(
             context.content_length_limit(),
         ))
@@ -112,26 +266,40 @@ pub fn create_signing_message(context: Context) -> BoxedFilter<(impl Reply,)> {
 
 async fn handle_get_transaction(
     id: TransactionIdParam,
+    accept_type: AcceptType,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_transaction")?;
     Ok(Transactions::new(context)?
-        .get_transaction(id.parse("transaction hash or version")?)
+        .get_transaction(id.parse("transaction hash or version")?, accept_type)
         .await?)
 }
 
-async fn handle_get_transactions(page: Page, context: Context) -> Result<impl Reply, Rejection> {
+async fn handle_get_transactions(
+    page: Page,
+    accept_type: AcceptType,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_transactions")?;
-    Ok(Transactions::new(context)?.list(page)?)
+    Ok(Transactions::new(context)?.list(page, accept_type)?)
 }
 
 async fn handle_get_account_transactions(
     address: AddressParam,
     page: Page,
+    accept_type: AcceptType,
     context: Context,
 ) -> Result<impl Reply, Rejection> {
     fail_point("endpoint_get_account_transactions")?;
-    Ok(Transactions::new(context)?.list_by_account(address, page)?)
+    Ok(Transactions::new(context)?.list_by_account(address, page, accept_type)?)
+}
+
+async fn handle_stream_transactions(
+    query: StreamQuery,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_stream_transactions")?;
+    Ok(Transactions::new(context)?.stream(query.start()?)?)
 }
 
 async fn handle_submit_json_transactions(
@@ -154,6 +322,62 @@ async fn handle_submit_bcs_transactions(
     Ok(Transactions::new(context)?.create(txn).await?)
 }
 
+async fn handle_submit_json_transactions_batch(
+    body: Vec<UserTransactionRequest>,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_submit_json_transactions_batch")?;
+    Ok(Transactions::new(context)?
+        .create_batch_from_requests(body)
+        .await?)
+}
+
+async fn handle_submit_bcs_transactions_batch(
+    body: bytes::Bytes,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_submit_bcs_transactions_batch")?;
+    let txns = bcs::from_bytes(&body)
+        .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
+    Ok(Transactions::new(context)?.create_batch(txns).await?)
+}
+
+async fn handle_simulate_json_transactions(
+    body: UserTransactionRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_simulate_json_transactions")?;
+    Ok(Transactions::new(context)?.simulate_from_request(body)?)
+}
+
+async fn handle_simulate_bcs_transactions(
+    body: bytes::Bytes,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_simulate_bcs_transactions")?;
+    let txn = bcs::from_bytes(&body)
+        .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
+    Ok(Transactions::new(context)?.simulate(txn)?)
+}
+
+async fn handle_estimate_gas_json(
+    body: GasEstimationRequest,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_estimate_gas_json")?;
+    Ok(Transactions::new(context)?.estimate_gas_from_request(body)?)
+}
+
+async fn handle_estimate_gas_bcs(
+    body: bytes::Bytes,
+    context: Context,
+) -> Result<impl Reply, Rejection> {
+    fail_point("endpoint_estimate_gas_bcs")?;
+    let req = bcs::from_bytes(&body)
+        .map_err(|err| Error::invalid_request_body(format!("deserialize error: {}", err)))?;
+    Ok(Transactions::new(context)?.estimate_gas(req)?)
+}
+
 async fn handle_create_signing_message(
     body: UserCreateSigningMessageRequest,
     context: Context,
@@ -194,6 +418,235 @@ impl Transactions {
         self.create(txn).await
     }
 
+    pub async fn create_batch_from_requests(
+        self,
+        reqs: Vec<UserTransactionRequest>,
+    ) -> Result<impl Reply, Error> {
+        check_batch_size(reqs.len())?;
+        let resolver = self.context.move_resolver()?;
+        let converter = resolver.as_converter();
+        let txns = reqs
+            .into_iter()
+            .enumerate()
+            .map(|(i, req)| {
+                converter
+                    .try_into_signed_transaction(req, self.context.chain_id())
+                    .map_err(|e| {
+                        Error::invalid_request_body(format!(
+                            "failed to create SignedTransaction from request {}: {}",
+                            i, e
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.create_batch(txns).await
+    }
+
+    // Transactions are submitted in order, so a batch can hold consecutive sequence numbers of
+    // the same account. A rejected transaction doesn't fail the batch, its status is returned
+    // along with the others, as is a transaction that couldn't reach mempool.
+    pub async fn create_batch(self, txns: Vec<SignedTransaction>) -> Result<impl Reply, Error> {
+        check_batch_size(txns.len())?;
+        let mut results = Vec::with_capacity(txns.len());
+        for txn in txns {
+            let hash = txn.committed_hash().into();
+            let result = match self.context.submit_transaction(txn).await {
+                Ok((mempool_status, vm_status_opt)) => TransactionSubmissionResult {
+                    hash,
+                    status: mempool_status.code.into(),
+                    message: Some(mempool_status.message).filter(|message| !message.is_empty()),
+                    vm_status: vm_status_opt.map(|s| format!("{:?}", s)),
+                },
+                Err(err) => TransactionSubmissionResult {
+                    hash,
+                    status: SubmissionStatusCode::SubmissionFailed,
+                    message: Some(err.to_string()),
+                    vm_status: None,
+                },
+            };
+            results.push(result);
+        }
+        Response::new(self.ledger_info, &results)
+    }
+
+    pub fn simulate_from_request(self, req: UserTransactionRequest) -> Result<impl Reply, Error> {
+        let txn = self
+            .context
+            .move_resolver()?
+            .as_converter()
+            .try_into_signed_transaction(req, self.context.chain_id())
+            .map_err(|e| {
+                Error::invalid_request_body(format!(
+                    "failed to create SignedTransaction from UserTransactionRequest: {}",
+                    e
+                ))
+            })?;
+        self.simulate(txn)
+    }
+
+    // The transaction is executed on top of the latest state checkpoint and its output is
+    // discarded. The signature is not verified, so clients may dry-run a transaction before
+    // signing it. The reported version is the one of the checkpoint, which may lag behind the
+    // ledger version.
+    pub fn simulate(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
+        let state_view = self.context.latest_state_view()?;
+        let version = state_view
+            .version()
+            .ok_or_else(|| Error::internal(anyhow!("no state checkpoint to simulate on")))?;
+        let (vm_status, output) = AptosVM::simulate_signed_transaction(&txn, &state_view);
+        let exe_status = match output.status() {
+            TransactionStatus::Keep(exe_status) => exe_status.clone(),
+            _ => {
+                return Err(Error::bad_request(format!(
+                    "invalid transaction: {:?}",
+                    vm_status
+                )))
+            }
+        };
+
+        let timestamp = self.context.get_block_timestamp(version)?;
+        let info = TransactionInfo::new(
+            txn.clone().committed_hash(),
+            aptos_crypto::HashValue::zero(),
+            aptos_crypto::HashValue::zero(),
+            None,
+            output.gas_used(),
+            exe_status,
+        );
+        let data = TransactionOnChainData {
+            version,
+            transaction: aptos_types::transaction::Transaction::UserTransaction(txn),
+            info,
+            events: output.events().to_vec(),
+            accumulator_root_hash: aptos_crypto::HashValue::zero(),
+            changes: output.write_set().clone(),
+        };
+
+        let resolver = self.context.move_resolver()?;
+        let simulated_txn = resolver
+            .as_converter()
+            .try_into_onchain_transaction(timestamp, data)?;
+        Response::new(self.ledger_info, &simulated_txn)
+    }
+
+    pub fn estimate_gas_from_request(self, req: GasEstimationRequest) -> Result<impl Reply, Error> {
+        let req = self
+            .context
+            .move_resolver()?
+            .as_converter()
+            .try_into_raw_gas_estimation_request(req)
+            .map_err(|e| {
+                Error::invalid_request_body(format!("invalid GasEstimationRequest: {}", e))
+            })?;
+        self.estimate_gas(req)
+    }
+
+    // The payload is executed at the minimum gas unit price with the largest gas amount the
+    // sender can afford, bounded by the maximum allowed by the gas schedule, so that the
+    // prologue balance check doesn't fail the estimation.
+    pub fn estimate_gas(self, req: RawGasEstimationRequest) -> Result<impl Reply, Error> {
+        let RawGasEstimationRequest {
+            sender,
+            public_key,
+            payload,
+        } = req;
+        let state_view = self.context.latest_state_view()?;
+
+        let state_key = StateKey::AccessPath(AccessPath::resource_access_path(ResourceKey::new(
+            sender,
+            AccountResource::struct_tag(),
+        )));
+        let account_resource: AccountResource = state_view
+            .get_state_value(&state_key)?
+            .map(|bytes| bcs::from_bytes(&bytes))
+            .transpose()
+            .map_err(anyhow::Error::from)?
+            .ok_or_else(|| Error::not_found("account", sender, self.ledger_info.version()))?;
+
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let gas_constants = AptosVM::new(&state_view)
+            .internals()
+            .gas_schedule(&log_context)
+            .map_err(|status| {
+                Error::internal(anyhow!("failed to load gas schedule: {:?}", status))
+            })?
+            .gas_constants
+            .clone();
+        let min_gas_unit_price = gas_constants.min_price_per_gas_unit.get();
+
+        let coin_store_key = StateKey::AccessPath(AccessPath::resource_access_path(
+            ResourceKey::new(sender, CoinStoreResource::struct_tag()),
+        ));
+        let balance = state_view
+            .get_state_value(&coin_store_key)?
+            .map(|bytes| bcs::from_bytes::<CoinStoreResource>(&bytes))
+            .transpose()
+            .map_err(anyhow::Error::from)?
+            .map(|coin_store| coin_store.coin())
+            .unwrap_or_default();
+        let max_gas_amount = match balance.checked_div(min_gas_unit_price) {
+            Some(affordable_gas_amount) => std::cmp::min(
+                gas_constants.maximum_number_of_gas_units.get(),
+                affordable_gas_amount,
+            ),
+            None => gas_constants.maximum_number_of_gas_units.get(),
+        };
+
+        let raw_txn = RawTransaction::new(
+            sender,
+            account_resource.sequence_number(),
+            payload,
+            max_gas_amount,
+            min_gas_unit_price,
+            self.ledger_info.timestamp() / 1_000_000 + GAS_ESTIMATION_EXPIRATION_SECS,
+            self.context.chain_id(),
+        );
+        let signature = Ed25519Signature::try_from(&[0u8; ED25519_SIGNATURE_LENGTH][..])
+            .map_err(anyhow::Error::from)?;
+        let txn = SignedTransaction::new(raw_txn, public_key, signature);
+
+        let (vm_status, output) = AptosVM::simulate_signed_transaction(&txn, &state_view);
+        if output.status() != &TransactionStatus::Keep(ExecutionStatus::Success) {
+            return Err(Error::bad_request(format!(
+                "failed to execute transaction payload: {:?}",
+                vm_status
+            )));
+        }
+        let gas_used = output.gas_used();
+
+        let mut gas_unit_prices = self
+            .context
+            .get_recent_gas_unit_prices(GAS_PRICE_SAMPLE_SIZE, self.ledger_info.version())?;
+        gas_unit_prices.sort_unstable();
+        let median_gas_unit_price = gas_unit_prices
+            .get(gas_unit_prices.len() / 2)
+            .copied()
+            .unwrap_or_default();
+        // The sender must be able to pay for the gas used at the suggested price, otherwise the
+        // prologue rejects the transaction. The simulation ensures it can at the minimum price.
+        let gas_unit_price = std::cmp::max(median_gas_unit_price, min_gas_unit_price);
+        let gas_unit_price = if gas_used.saturating_mul(gas_unit_price) > balance {
+            min_gas_unit_price
+        } else {
+            gas_unit_price
+        };
+        let max_gas_amount = match balance.checked_div(gas_unit_price) {
+            Some(affordable_gas_amount) => std::cmp::min(max_gas_amount, affordable_gas_amount),
+            None => max_gas_amount,
+        };
+
+        let estimation = GasEstimation {
+            gas_used: gas_used.into(),
+            max_gas_amount: std::cmp::min(
+                gas_used.saturating_mul(GAS_ESTIMATION_HEADROOM_PERCENT) / 100,
+                max_gas_amount,
+            )
+            .into(),
+            gas_unit_price: gas_unit_price.into(),
+        };
+        Response::new(self.ledger_info, &estimation)
+    }
+
     pub async fn create(self, txn: SignedTransaction) -> Result<impl Reply, Error> {
         let (mempool_status, vm_status_opt) = self.context.submit_transaction(txn.clone()).await?;
         match mempool_status.code {
@@ -216,7 +669,7 @@ impl Transactions {
         }
     }
 
-    pub fn list(self, page: Page) -> Result<impl Reply, Error> {
+    pub fn list(self, page: Page, accept_type: AcceptType) -> Result<impl Reply, Error> {
         let ledger_version = self.ledger_info.version();
         let limit = page.limit()?;
         let last_page_start = if ledger_version > (limit as u64) {
@@ -230,47 +683,75 @@ impl Transactions {
             .context
             .get_transactions(start_version, limit, ledger_version)?;
 
-        self.render_transactions(data)
+        self.render_transactions(data, accept_type)
     }
 
-    pub fn list_by_account(self, address: AddressParam, page: Page) -> Result<impl Reply, Error> {
-        let data = self.context.get_account_transactions(
-            address.parse("account address")?.into(),
-            page.start(0, u64::MAX)?,
-            page.limit()?,
-            self.ledger_info.version(),
-        )?;
-        self.render_transactions(data)
+    pub fn list_by_account(
+        self,
+        address: AddressParam,
+        page: Page,
+        accept_type: AcceptType,
+    ) -> Result<impl Reply, Error> {
+        let address = address.parse("account address")?.into();
+        let ledger_version = self.ledger_info.version();
+        let total = self
+            .context
+            .get_account_sequence_number(address, ledger_version)?;
+        let range = page.sequence_range(total)?;
+        let data = range.read(|first, limit| {
+            self.context
+                .get_account_transactions(address, first, limit, ledger_version)
+        })?;
+        let page_info = range.page_info(data.len());
+
+        if accept_type == AcceptType::Bcs {
+            let data = range.order(data);
+            return Ok(Response::new_bcs(self.ledger_info, &data)?.with_page_info(page_info));
+        }
+        // Transactions are converted in ascending order, as timestamps come from the preceding
+        // block metadata transaction.
+        let txns = range.order(convert_transactions(&self.context, data)?);
+        Ok(Response::new(self.ledger_info, &txns)?.with_page_info(page_info))
     }
 
-    fn render_transactions(self, data: Vec<TransactionOnChainData>) -> Result<impl Reply, Error> {
-        if data.is_empty() {
-            let txns: Vec<Transaction> = vec![];
-            return Response::new(self.ledger_info, &txns);
+    fn render_transactions(
+        self,
+        data: Vec<TransactionOnChainData>,
+        accept_type: AcceptType,
+    ) -> Result<impl Reply, Error> {
+        if accept_type == AcceptType::Bcs {
+            return Response::new_bcs(self.ledger_info, &data);
         }
-        let first_version = data[0].version;
-        let mut timestamp = self.context.get_block_timestamp(first_version)?;
-        let resolver = self.context.move_resolver()?;
-        let converter = resolver.as_converter();
-        let txns: Vec<Transaction> = data
-            .into_iter()
-            .map(|t| {
-                let txn = converter.try_into_onchain_transaction(timestamp, t)?;
-                // update timestamp, when txn is metadata block transaction
-                // new timestamp is used for the following transactions
-                timestamp = txn.timestamp();
-                Ok(txn)
-            })
-            .collect::<Result<_>>()?;
+        let txns = convert_transactions(&self.context, data)?;
         Response::new(self.ledger_info, &txns)
     }
 
-    pub async fn get_transaction(self, id: TransactionId) -> Result<impl Reply, Error> {
+    pub fn stream(self, start: Option<u64>) -> Result<impl Reply, Error> {
+        let start = start.unwrap_or_else(|| self.ledger_info.version() + 1);
+        let context = self.context.clone();
+        ndjson_stream(&self.context, self.ledger_info, start, move |version| {
+            let ledger_version = context.get_latest_ledger_info()?.version();
+            if version > ledger_version {
+                return Ok(vec![]);
+            }
+            let data = context.get_transactions(version, STREAM_BATCH_SIZE, ledger_version)?;
+            Ok(convert_transactions(&context, data)?)
+        })
+    }
+
+    pub async fn get_transaction(
+        self,
+        id: TransactionId,
+        accept_type: AcceptType,
+    ) -> Result<impl Reply, Error> {
         let txn_data = match id.clone() {
             TransactionId::Hash(hash) => self.get_by_hash(hash.into()).await?,
             TransactionId::Version(version) => self.get_by_version(version)?,
         }
         .ok_or_else(|| self.transaction_not_found(id))?;
+        if accept_type == AcceptType::Bcs {
+            return Response::new_bcs(self.ledger_info, &txn_data);
+        }
 
         let resolver = self.context.move_resolver()?;
         let txn = match txn_data {
@@ -355,3 +836,36 @@ impl Transactions {
         })
     }
 }
+
+fn check_batch_size(len: usize) -> Result<(), Error> {
+    if len > MAX_TRANSACTIONS_BATCH_SIZE {
+        return Err(Error::invalid_request_body(format!(
+            "batch of {} transactions exceeds the maximum of {}",
+            len, MAX_TRANSACTIONS_BATCH_SIZE
+        )));
+    }
+    Ok(())
+}
+
+/// Converts on-chain transactions, which must be ordered by version, into their JSON rendering.
+pub(crate) fn convert_transactions(
+    context: &Context,
+    data: Vec<TransactionOnChainData>,
+) -> Result<Vec<Transaction>> {
+    if data.is_empty() {
+        return Ok(vec![]);
+    }
+    let first_version = data[0].version;
+    let mut timestamp = context.get_block_timestamp(first_version)?;
+    let resolver = context.move_resolver()?;
+    let converter = resolver.as_converter();
+    data.into_iter()
+        .map(|t| {
+            let txn = converter.try_into_onchain_transaction(timestamp, t)?;
+            // update timestamp, when txn is metadata block transaction
+            // new timestamp is used for the following transactions
+            timestamp = txn.timestamp();
+            Ok(txn)
+        })
+        .collect()
+}
@@ -0,0 +1,19 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{HashValue, Transaction, U64};
+
+use serde::{Deserialize, Serialize};
+
+/// A committed block, delimited by its `BlockMetadataTransaction` and followed by the
+/// transactions executed in the block. The genesis block has height 0.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Block {
+    pub block_height: U64,
+    pub block_hash: HashValue,
+    pub block_timestamp: U64,
+    pub first_version: U64,
+    pub last_version: U64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transactions: Option<Vec<Transaction>>,
+}
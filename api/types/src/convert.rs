@@ -3,10 +3,11 @@
 
 use crate::{
     transaction::{ModuleBundlePayload, StateCheckpointTransaction},
-    Bytecode, DirectWriteSet, Event, HexEncodedBytes, MoveFunction, MoveModuleBytecode,
-    MoveResource, MoveScriptBytecode, MoveValue, ScriptFunctionId, ScriptFunctionPayload,
-    ScriptPayload, ScriptWriteSet, Transaction, TransactionInfo, TransactionOnChainData,
-    TransactionPayload, UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
+    Bytecode, DirectWriteSet, Event, GasEstimationRequest, HexEncodedBytes, MoveFunction,
+    MoveModuleBytecode, MoveResource, MoveScriptBytecode, MoveValue, RawGasEstimationRequest,
+    ScriptFunctionId, ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, Transaction,
+    TransactionInfo, TransactionOnChainData, TransactionPayload, UserTransactionRequest, WriteSet,
+    WriteSetChange, WriteSetPayload,
 };
 use anyhow::{bail, ensure, format_err, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
@@ -336,6 +337,22 @@ impl<'a, R: MoveResolverExt + ?Sized> MoveConverter<'a, R> {
         ))
     }
 
+    pub fn try_into_raw_gas_estimation_request(
+        &self,
+        req: GasEstimationRequest,
+    ) -> Result<RawGasEstimationRequest> {
+        let GasEstimationRequest {
+            sender,
+            public_key,
+            payload,
+        } = req;
+        Ok(RawGasEstimationRequest {
+            sender: sender.into(),
+            public_key: public_key.inner().try_into()?,
+            payload: self.try_into_aptos_core_transaction_payload(payload)?,
+        })
+    }
+
     pub fn try_into_aptos_core_transaction_payload(
         &self,
         payload: TransactionPayload,
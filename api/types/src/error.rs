@@ -90,6 +90,12 @@ impl From<serde_json::error::Error> for Error {
     }
 }
 
+impl From<bcs::Error> for Error {
+    fn from(err: bcs::Error) -> Self {
+        Self::internal(err.into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::error::Error;
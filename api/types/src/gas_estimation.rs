@@ -0,0 +1,38 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, HexEncodedBytes, TransactionPayload, U64};
+use aptos_crypto::ed25519::Ed25519PublicKey;
+use aptos_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+
+/// Request for estimating the gas of executing `payload` as `sender`.
+///
+/// The `public_key` must match the authentication key of the sender, it is used to pass the
+/// transaction prologue. No signature is required.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GasEstimationRequest {
+    pub sender: Address,
+    pub public_key: HexEncodedBytes,
+    pub payload: TransactionPayload,
+}
+
+/// BCS encoded form of the `GasEstimationRequest`, for clients building payloads with
+/// `aptos_types` directly.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RawGasEstimationRequest {
+    pub sender: AccountAddress,
+    pub public_key: Ed25519PublicKey,
+    pub payload: aptos_types::transaction::TransactionPayload,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GasEstimation {
+    /// Gas units used by executing the payload against the latest ledger state.
+    pub gas_used: U64,
+    /// Suggested `max_gas_amount`, with head room for state changes before the transaction is
+    /// committed.
+    pub max_gas_amount: U64,
+    /// Suggested `gas_unit_price`, based on recently committed user transactions.
+    pub gas_unit_price: U64,
+}
@@ -3,12 +3,15 @@
 
 mod account;
 mod address;
+mod block;
 mod bytecode;
 mod convert;
 mod error;
 mod event_key;
+mod gas_estimation;
 mod hash;
 mod ledger_info;
+mod mempool;
 pub mod mime_types;
 mod move_types;
 mod response;
@@ -17,25 +20,29 @@ mod transaction;
 
 pub use account::AccountData;
 pub use address::Address;
+pub use block::Block;
 pub use bytecode::Bytecode;
 pub use convert::{new_vm_ascii_string, AsConverter, MoveConverter};
 pub use error::Error;
 pub use event_key::EventKey;
+pub use gas_estimation::{GasEstimation, GasEstimationRequest, RawGasEstimationRequest};
 pub use hash::HashValue;
 pub use ledger_info::LedgerInfo;
+pub use mempool::{MempoolStats, MempoolTransaction, MempoolTransactionStatus};
 pub use move_types::{
-    HexEncodedBytes, MoveFunction, MoveModule, MoveModuleBytecode, MoveModuleId, MoveResource,
-    MoveScriptBytecode, MoveStructTag, MoveStructValue, MoveType, MoveValue, ScriptFunctionId,
-    U128, U64,
+    HexEncodedBytes, MoveFunction, MoveModule, MoveModuleAbi, MoveModuleBytecode, MoveModuleId,
+    MoveResource, MoveScriptBytecode, MoveStructTag, MoveStructValue, MoveType, MoveValue,
+    ScriptFunctionId, U128, U64,
 };
 pub use response::{
-    Response, X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+    PageInfo, Response, X_APTOS_CHAIN_ID, X_APTOS_CURSOR, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP,
+    X_APTOS_LEDGER_VERSION, X_APTOS_TOTAL_COUNT,
 };
-pub use table::TableItemRequest;
+pub use table::{RawTableItemRequest, TableItemRequest};
 pub use transaction::{
     BlockMetadataTransaction, DirectWriteSet, Event, GenesisTransaction, PendingTransaction,
-    ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, Transaction, TransactionData,
-    TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
-    TransactionSigningMessage, UserCreateSigningMessageRequest, UserTransaction,
-    UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
+    ScriptFunctionPayload, ScriptPayload, ScriptWriteSet, SubmissionStatusCode, Transaction,
+    TransactionData, TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
+    TransactionSigningMessage, TransactionSubmissionResult, UserCreateSigningMessageRequest,
+    UserTransaction, UserTransactionRequest, WriteSet, WriteSetChange, WriteSetPayload,
 };
@@ -0,0 +1,41 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Transaction, U64};
+
+use serde::{Deserialize, Serialize};
+
+/// A transaction waiting in mempool, along with whether it can be included in the next block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MempoolTransaction {
+    pub status: MempoolTransactionStatus,
+    pub transaction: Transaction,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolTransactionStatus {
+    /// Can be pulled by consensus into the next block.
+    Ready,
+    /// Waiting for the transactions with lower sequence numbers of the same account.
+    Parked,
+}
+
+/// Number of transactions tracked by each mempool index.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MempoolStats {
+    /// Max number of transactions mempool holds.
+    pub capacity: U64,
+    /// Max number of transactions mempool holds per account.
+    pub capacity_per_user: U64,
+    /// All transactions in mempool, ready and parked.
+    pub system_ttl_index: U64,
+    pub expiration_time_index: U64,
+    /// Transactions ready to be pulled by consensus.
+    pub priority_index: U64,
+    /// Transactions waiting on a sequence number gap.
+    pub parking_lot_index: U64,
+    /// Ready transactions, in the order they're broadcast to peers.
+    pub timeline_index: U64,
+    pub transaction_hash_index: U64,
+}
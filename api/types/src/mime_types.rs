@@ -2,4 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub const BCS_SIGNED_TRANSACTION: &str = "application/x.aptos.signed_transaction+bcs";
+pub const BCS_SIGNED_TRANSACTION_BATCH: &str = "application/x.aptos.signed_transaction_batch+bcs";
+pub const BCS_GAS_ESTIMATION_REQUEST: &str = "application/x.aptos.gas_estimation_request+bcs";
 pub const JSON: &str = "application/json";
+pub const BCS: &str = "application/x-bcs";
+pub const NDJSON: &str = "application/x-ndjson";
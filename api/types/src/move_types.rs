@@ -621,6 +621,38 @@ impl From<CompiledModule> for MoveModule {
     }
 }
 
+/// ABI of a module, for building transaction payloads without the compiled module.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MoveModuleAbi {
+    #[serde(flatten)]
+    pub module: MoveModule,
+    /// The exposed functions with `script` visibility, which can be called from a
+    /// `ScriptFunctionPayload`.
+    pub script_functions: Vec<ScriptFunctionId>,
+}
+
+impl From<MoveModule> for MoveModuleAbi {
+    fn from(module: MoveModule) -> Self {
+        let module_id = MoveModuleId {
+            address: module.address,
+            name: module.name.clone(),
+        };
+        let script_functions = module
+            .exposed_functions
+            .iter()
+            .filter(|f| f.visibility == MoveFunctionVisibility::Script)
+            .map(|f| ScriptFunctionId {
+                module: module_id.clone(),
+                name: f.name.clone(),
+            })
+            .collect();
+        Self {
+            module,
+            script_functions,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct MoveModuleId {
     pub address: Address,
@@ -1,7 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{Error, LedgerInfo};
+use crate::{
+    mime_types::{BCS, JSON},
+    Error, LedgerInfo,
+};
 
 use anyhow::Result;
 use serde::Serialize;
@@ -11,10 +14,22 @@ pub const X_APTOS_CHAIN_ID: &str = "X-Aptos-Chain-Id";
 pub const X_APTOS_EPOCH: &str = "X-Aptos-Epoch";
 pub const X_APTOS_LEDGER_VERSION: &str = "X-Aptos-Ledger-Version";
 pub const X_APTOS_LEDGER_TIMESTAMP: &str = "X-Aptos-Ledger-TimestampUsec";
+pub const X_APTOS_CURSOR: &str = "X-Aptos-Cursor";
+pub const X_APTOS_TOTAL_COUNT: &str = "X-Aptos-Total-Count";
+
+/// Pagination state of a list response, returned as headers so the body stays a list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PageInfo {
+    /// Opaque cursor of the next page, `None` on the last page.
+    pub cursor: Option<String>,
+    pub total_count: u64,
+}
 
 pub struct Response {
     pub ledger_info: LedgerInfo,
     pub body: Vec<u8>,
+    pub content_type: &'static str,
+    pub page_info: Option<PageInfo>,
 }
 
 impl Response {
@@ -22,8 +37,36 @@ impl Response {
         Ok(Self {
             ledger_info,
             body: serde_json::to_vec(body)?,
+            content_type: JSON,
+            page_info: None,
+        })
+    }
+
+    /// Creates a response with the BCS encoded `body`, which is returned to clients that send
+    /// the `Accept: application/x-bcs` header.
+    pub fn new_bcs<T: Serialize>(ledger_info: LedgerInfo, body: &T) -> Result<Self, Error> {
+        Ok(Self {
+            ledger_info,
+            body: bcs::to_bytes(body)?,
+            content_type: BCS,
+            page_info: None,
         })
     }
+
+    /// Creates a response with bytes that are already BCS encoded, e.g. state values.
+    pub fn from_bcs_bytes(ledger_info: LedgerInfo, body: Vec<u8>) -> Self {
+        Self {
+            ledger_info,
+            body,
+            content_type: BCS,
+            page_info: None,
+        }
+    }
+
+    pub fn with_page_info(mut self, page_info: PageInfo) -> Self {
+        self.page_info = Some(page_info);
+        self
+    }
 }
 
 impl warp::Reply for Response {
@@ -31,7 +74,7 @@ impl warp::Reply for Response {
         let mut res = warp::reply::Response::new(self.body.into());
         let headers = res.headers_mut();
 
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static(self.content_type));
         headers.insert(X_APTOS_CHAIN_ID, (self.ledger_info.chain_id as u16).into());
         headers.insert(
             X_APTOS_LEDGER_VERSION,
@@ -42,6 +85,15 @@ impl warp::Reply for Response {
             self.ledger_info.ledger_timestamp.into(),
         );
         headers.insert(X_APTOS_EPOCH, self.ledger_info.epoch.into());
+        if let Some(page_info) = self.page_info {
+            if let Some(cursor) = page_info
+                .cursor
+                .and_then(|cursor| HeaderValue::from_str(&cursor).ok())
+            {
+                headers.insert(X_APTOS_CURSOR, cursor);
+            }
+            headers.insert(X_APTOS_TOTAL_COUNT, page_info.total_count.into());
+        }
 
         res
     }
@@ -16,6 +16,7 @@ use aptos_types::{
     account_address::AccountAddress,
     block_metadata::BlockMetadata,
     contract_event::ContractEvent,
+    mempool_status::MempoolStatusCode,
     transaction::{
         authenticator::{AccountAuthenticator, TransactionAuthenticator},
         Script, SignedTransaction, TransactionOutput, TransactionWithProof,
@@ -30,7 +31,7 @@ use std::{
     str::FromStr,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum TransactionData {
     OnChain(TransactionOnChainData),
     Pending(Box<SignedTransaction>),
@@ -48,7 +49,7 @@ impl From<SignedTransaction> for TransactionData {
     }
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub struct TransactionOnChainData {
     pub version: u64,
     pub transaction: aptos_types::transaction::Transaction,
@@ -822,3 +823,45 @@ impl TransactionSigningMessage {
         }
     }
 }
+
+/// Result of submitting a transaction of a batch to mempool.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionSubmissionResult {
+    pub hash: HashValue,
+    pub status: SubmissionStatusCode,
+    /// The reason of the rejection, if given by mempool.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// The VM validation error of transactions rejected with `vm_error`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_status: Option<String>,
+}
+
+/// `MempoolStatusCode` of a submitted transaction, or `SubmissionFailed` when the transaction
+/// couldn't be handed to mempool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStatusCode {
+    Accepted,
+    InvalidSeqNumber,
+    MempoolIsFull,
+    TooManyTransactions,
+    InvalidUpdate,
+    VmError,
+    UnknownStatus,
+    SubmissionFailed,
+}
+
+impl From<MempoolStatusCode> for SubmissionStatusCode {
+    fn from(code: MempoolStatusCode) -> Self {
+        match code {
+            MempoolStatusCode::Accepted => Self::Accepted,
+            MempoolStatusCode::InvalidSeqNumber => Self::InvalidSeqNumber,
+            MempoolStatusCode::MempoolIsFull => Self::MempoolIsFull,
+            MempoolStatusCode::TooManyTransactions => Self::TooManyTransactions,
+            MempoolStatusCode::InvalidUpdate => Self::InvalidUpdate,
+            MempoolStatusCode::VmError => Self::VmError,
+            MempoolStatusCode::UnknownStatus => Self::UnknownStatus,
+        }
+    }
+}
@@ -1,9 +1,10 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+mod rest_interface;
 mod storage_interface;
 
-pub use crate::storage_interface::DBDebuggerInterface;
+pub use crate::{rest_interface::RestDebuggerInterface, storage_interface::DBDebuggerInterface};
 
 use anyhow::{anyhow, Result};
 use aptos_state_view::StateView;
@@ -20,6 +21,9 @@ use aptos_types::{
 };
 use move_deps::move_binary_format::file_format::CompiledModule;
 
+#[cfg(test)]
+mod unit_tests;
+
 // TODO(skedia) Clean up this interfact to remove account specific logic and move to state store
 // key-value interface with fine grained storage project
 pub trait AptosValidatorInterface: Sync {
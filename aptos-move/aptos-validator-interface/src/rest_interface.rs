@@ -0,0 +1,271 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::AptosValidatorInterface;
+use anyhow::{anyhow, bail, Result};
+use aptos_infallible::Mutex;
+use aptos_rest_client::{aptos_api_types::TransactionOnChainData, Client, RestError};
+use aptos_types::{
+    access_path::{AccessPath, Path},
+    account_address::AccountAddress,
+    account_state::AccountState,
+    contract_event::EventWithVersion,
+    event::EventKey,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{Transaction, Version},
+};
+use move_deps::move_binary_format::file_format::CompiledModule;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    future::Future,
+};
+use tokio::runtime::Runtime;
+use url::Url;
+
+/// Maximum number of transactions or events the REST API returns per request
+const PAGE_SIZE: u64 = 1000;
+/// Maximum number of versions whose write sets are read to reuse a cached account state at
+/// another version, beyond which the account state is read again
+const MAX_WRITE_SET_VERSIONS: u64 = 10 * PAGE_SIZE;
+
+/// Reads the state and transactions of a remote node through its REST API
+///
+/// The state of each account is cached with the version it was read at, and reused at other
+/// versions as long as the write sets in between don't modify the account.  Reads are blocking,
+/// so it must not be used from an async context.
+pub struct RestDebuggerInterface {
+    client: Client,
+    runtime: Runtime,
+    account_states: Mutex<HashMap<AccountAddress, (Version, Option<AccountState>)>>,
+    /// The accounts modified by the write set of each version read so far
+    modified_accounts: Mutex<BTreeMap<Version, HashSet<AccountAddress>>>,
+}
+
+impl RestDebuggerInterface {
+    pub fn new(url: Url) -> Result<Self> {
+        Ok(Self {
+            client: Client::new(url),
+            runtime: tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+            account_states: Mutex::new(HashMap::new()),
+            modified_accounts: Mutex::new(BTreeMap::new()),
+        })
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Fetches up to `limit` transactions from `start`, recording the accounts they modify
+    fn fetch_transactions(&self, start: Version, limit: u64) -> Result<Vec<Transaction>> {
+        let mut transactions = Vec::new();
+        while (transactions.len() as u64) < limit {
+            let next = start + transactions.len() as u64;
+            let page_size = std::cmp::min(limit - transactions.len() as u64, PAGE_SIZE);
+            let page = self
+                .block_on(
+                    self.client
+                        .get_transactions_bcs(Some(next), Some(page_size)),
+                )?
+                .into_inner();
+            if page.is_empty() {
+                break;
+            }
+            self.record_write_sets(&page);
+            transactions.extend(page.into_iter().map(|txn| txn.transaction));
+        }
+        Ok(transactions)
+    }
+
+    fn record_write_sets(&self, transactions: &[TransactionOnChainData]) {
+        let mut modified_accounts = self.modified_accounts.lock();
+        for txn in transactions {
+            let accounts = txn
+                .changes
+                .iter()
+                .filter_map(|(state_key, _)| match state_key {
+                    StateKey::AccessPath(access_path) => Some(access_path.address),
+                    _ => None,
+                })
+                .collect();
+            modified_accounts.insert(txn.version, accounts);
+        }
+    }
+
+    /// Whether the write sets of the versions after `from` up to `to` modify the account
+    fn is_modified_between(
+        &self,
+        account: AccountAddress,
+        from: Version,
+        to: Version,
+    ) -> Result<bool> {
+        if to - from > MAX_WRITE_SET_VERSIONS {
+            return Ok(true);
+        }
+        let mut version = from + 1;
+        while version <= to {
+            let missing = !self.modified_accounts.lock().contains_key(&version);
+            if missing
+                && self
+                    .fetch_transactions(version, to - version + 1)?
+                    .is_empty()
+            {
+                bail!("Transaction at version {} not found", version);
+            }
+            if self.modified_accounts.lock()[&version].contains(&account) {
+                return Ok(true);
+            }
+            version += 1;
+        }
+        Ok(false)
+    }
+
+    /// Fetches the resources and modules of the account, `None` if it doesn't exist at `version`
+    fn fetch_account_state(
+        &self,
+        account: AccountAddress,
+        version: Version,
+    ) -> Result<Option<AccountState>> {
+        let resources = match self.block_on(
+            self.client
+                .get_account_resources_at_version_bcs(account, version),
+        ) {
+            Ok(response) => response.into_inner(),
+            Err(err) if is_not_found(&err) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let modules = self
+            .block_on(
+                self.client
+                    .get_account_modules_at_version_bcs(account, version),
+            )?
+            .into_inner();
+
+        let mut account_state = AccountState::default();
+        for (struct_tag, bytes) in resources {
+            account_state.insert(AccessPath::resource_access_vec(struct_tag), bytes);
+        }
+        for bytes in modules {
+            let module = CompiledModule::deserialize(&bytes)
+                .map_err(|e| anyhow!("Failure deserializing module: {:?}", e))?;
+            account_state.insert(AccessPath::code_access_path(module.self_id()).path, bytes);
+        }
+        Ok(Some(account_state))
+    }
+}
+
+impl AptosValidatorInterface for RestDebuggerInterface {
+    fn get_account_state_by_version(
+        &self,
+        account: AccountAddress,
+        version: Version,
+    ) -> Result<Option<AccountState>> {
+        let cached = self.account_states.lock().get(&account).cloned();
+        if let Some((cached_version, account_state)) = cached {
+            let (from, to) = if cached_version < version {
+                (cached_version, version)
+            } else {
+                (version, cached_version)
+            };
+            if !self.is_modified_between(account, from, to)? {
+                return Ok(account_state);
+            }
+        }
+        let account_state = self.fetch_account_state(account, version)?;
+        self.account_states
+            .lock()
+            .insert(account, (version, account_state.clone()));
+        Ok(account_state)
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> Result<Option<StateValue>> {
+        let access_path = match state_key {
+            StateKey::AccessPath(access_path) => access_path,
+            StateKey::TableItem { handle, key } => {
+                return match self.block_on(
+                    self.client
+                        .get_raw_table_item_at_version_bcs(*handle, key, version),
+                ) {
+                    Ok(response) => Ok(Some(StateValue::from(response.into_inner()))),
+                    Err(err) if is_not_found(&err) => Ok(None),
+                    Err(err) => Err(err),
+                };
+            }
+            StateKey::Raw(_) => bail!(
+                "Raw state keys can't be read through the REST API, got {:?}",
+                state_key
+            ),
+        };
+        let path = match access_path.get_path() {
+            Path::Code(module_id) => AccessPath::code_access_path(module_id).path,
+            Path::Resource(struct_tag) => AccessPath::resource_access_vec(struct_tag),
+        };
+        Ok(self
+            .get_account_state_by_version(access_path.address, version)?
+            .and_then(|account_state| account_state.get(&path).cloned())
+            .map(StateValue::from))
+    }
+
+    fn get_events(
+        &self,
+        key: &EventKey,
+        start_seq: u64,
+        limit: u64,
+    ) -> Result<Vec<EventWithVersion>> {
+        let mut events = Vec::new();
+        while (events.len() as u64) < limit {
+            let next = start_seq + events.len() as u64;
+            let page_size = std::cmp::min(limit - events.len() as u64, PAGE_SIZE);
+            let page = match self.block_on(self.client.get_events_bcs(
+                *key,
+                Some(next),
+                Some(page_size),
+            )) {
+                Ok(response) => response.into_inner(),
+                Err(err) if is_not_found(&err) => break,
+                Err(err) => return Err(err),
+            };
+            if page.is_empty() {
+                break;
+            }
+            events.extend(page);
+        }
+        Ok(events)
+    }
+
+    fn get_committed_transactions(&self, start: Version, limit: u64) -> Result<Vec<Transaction>> {
+        self.fetch_transactions(start, limit)
+    }
+
+    fn get_latest_version(&self) -> Result<Version> {
+        Ok(self
+            .block_on(self.client.get_ledger_information())?
+            .into_inner()
+            .version)
+    }
+
+    fn get_version_by_account_sequence(
+        &self,
+        account: AccountAddress,
+        seq: u64,
+    ) -> Result<Option<Version>> {
+        match self.block_on(
+            self.client
+                .get_account_transactions_bcs(account, Some(seq), Some(1)),
+        ) {
+            Ok(response) => Ok(response.into_inner().first().map(|txn| txn.version)),
+            Err(err) if is_not_found(&err) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<RestError>()
+        .map_or(false, |err| err.code == 404)
+}
@@ -0,0 +1,4 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+mod rest_interface_test;
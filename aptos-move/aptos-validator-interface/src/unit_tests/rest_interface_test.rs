@@ -0,0 +1,262 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{AptosValidatorInterface, RestDebuggerInterface};
+use aptos_api_types::{
+    RawTableItemRequest, TransactionOnChainData, X_APTOS_CHAIN_ID, X_APTOS_EPOCH,
+    X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+};
+use aptos_crypto::HashValue;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    contract_event::{ContractEvent, EventWithVersion},
+    event::EventKey,
+    state_store::state_key::StateKey,
+    transaction::{ExecutionStatus, Transaction, TransactionInfo},
+    write_set::{WriteOp, WriteSetMut},
+};
+use move_deps::move_core_types::{
+    language_storage::{StructTag, TypeTag},
+    parser::parse_struct_tag,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tokio::runtime::Runtime;
+use warp::{http::StatusCode, Filter, Reply};
+
+const VERSION: u64 = 10;
+/// The version whose write set modifies the account
+const MODIFIED_VERSION: u64 = 8;
+const NUM_EVENTS: u64 = 1200;
+const TABLE_HANDLE: u128 = 7;
+
+fn resource_tag() -> StructTag {
+    parse_struct_tag("0x1::Test::Resource").unwrap()
+}
+
+fn with_ledger_headers(reply: impl Reply + 'static) -> Box<dyn Reply> {
+    let reply = warp::reply::with_header(reply, X_APTOS_CHAIN_ID, "4");
+    let reply = warp::reply::with_header(reply, X_APTOS_EPOCH, "1");
+    let reply = warp::reply::with_header(reply, X_APTOS_LEDGER_VERSION, VERSION.to_string());
+    Box::new(warp::reply::with_header(
+        reply,
+        X_APTOS_LEDGER_TIMESTAMP,
+        "1000",
+    ))
+}
+
+fn not_found() -> Box<dyn Reply> {
+    let error = serde_json::json!({
+        "code": 404,
+        "message": "not found",
+        "aptos_ledger_version": VERSION.to_string(),
+    });
+    Box::new(warp::reply::with_status(
+        warp::reply::json(&error),
+        StatusCode::NOT_FOUND,
+    ))
+}
+
+fn state_key(account: AccountAddress) -> StateKey {
+    StateKey::AccessPath(AccessPath::new(
+        account,
+        AccessPath::resource_access_vec(resource_tag()),
+    ))
+}
+
+fn event_key(account: AccountAddress) -> EventKey {
+    EventKey::new_from_address(&account, 0)
+}
+
+fn transaction_at(account: AccountAddress, version: u64) -> TransactionOnChainData {
+    let mut changes = WriteSetMut::default();
+    if version == MODIFIED_VERSION {
+        changes.push((state_key(account), WriteOp::Value(vec![4u8])));
+    }
+    TransactionOnChainData {
+        version,
+        transaction: Transaction::StateCheckpoint,
+        info: TransactionInfo::new_placeholder(0, ExecutionStatus::Success),
+        events: vec![],
+        accumulator_root_hash: HashValue::zero(),
+        changes: changes.freeze().unwrap(),
+    }
+}
+
+/// Serves the resources, transactions and events of a single account, counting the resources
+/// requests
+fn start_api_stand_in(
+    runtime: &Runtime,
+    account: AccountAddress,
+    requests: Arc<AtomicUsize>,
+) -> url::Url {
+    let resources = warp::path!("accounts" / String / "resources").map(
+        move |address: String| -> Box<dyn Reply> {
+            requests.fetch_add(1, Ordering::SeqCst);
+            if address != account.to_string() {
+                return not_found();
+            }
+            let mut resources = BTreeMap::new();
+            resources.insert(resource_tag(), vec![1u8, 2, 3]);
+            with_ledger_headers(bcs::to_bytes(&resources).unwrap())
+        },
+    );
+    let modules = warp::path!("accounts" / String / "modules")
+        .map(|_| with_ledger_headers(bcs::to_bytes(&Vec::<Vec<u8>>::new()).unwrap()));
+    let transactions = warp::path!("transactions")
+        .and(warp::query::<HashMap<String, u64>>())
+        .map(move |query: HashMap<String, u64>| {
+            let (start, limit) = (query["start"], query["limit"]);
+            let transactions: Vec<_> = (start..std::cmp::min(start + limit, VERSION + 1))
+                .map(|version| transaction_at(account, version))
+                .collect();
+            with_ledger_headers(bcs::to_bytes(&transactions).unwrap())
+        });
+    let events = warp::path!("events" / String)
+        .and(warp::query::<HashMap<String, u64>>())
+        .map(move |key: String, query: HashMap<String, u64>| {
+            let (start, limit) = (query["start"], query["limit"]);
+            assert_eq!(key, event_key(account).to_string());
+            let events: Vec<_> = (start..std::cmp::min(start + limit, NUM_EVENTS))
+                .map(|sequence_number| {
+                    let event = ContractEvent::new(
+                        event_key(account),
+                        sequence_number,
+                        TypeTag::Bool,
+                        vec![],
+                    );
+                    EventWithVersion::new(sequence_number / 100, event)
+                })
+                .collect();
+            with_ledger_headers(bcs::to_bytes(&events).unwrap())
+        });
+    // Only serves the item at key `[1]` of table `TABLE_HANDLE` at `VERSION`
+    let table_items = warp::path!("tables" / u128 / "raw_item")
+        .and(warp::post())
+        .and(warp::body::json::<RawTableItemRequest>())
+        .and(warp::query::<HashMap<String, u64>>())
+        .map(
+            |handle: u128, body: RawTableItemRequest, query: HashMap<String, u64>| {
+                if handle != TABLE_HANDLE
+                    || body.key.inner() != [1u8]
+                    || query["version"] != VERSION
+                {
+                    return not_found();
+                }
+                with_ledger_headers(vec![5u8, 6])
+            },
+        );
+
+    let _guard = runtime.enter();
+    let routes = resources
+        .or(modules)
+        .or(transactions)
+        .or(events)
+        .or(table_items);
+    let (address, server) = warp::serve(routes).bind_ephemeral(([127, 0, 0, 1], 0));
+    runtime.spawn(server);
+    format!("http://{}/", address).parse().unwrap()
+}
+
+#[test]
+fn test_rest_interface_reads_and_caches_account_state() {
+    let runtime = Runtime::new().unwrap();
+    let account = AccountAddress::random();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let url = start_api_stand_in(&runtime, account, requests.clone());
+    let interface = RestDebuggerInterface::new(url).unwrap();
+
+    let state_key = state_key(account);
+    for _ in 0..2 {
+        let value = interface
+            .get_state_value_by_version(&state_key, VERSION)
+            .unwrap()
+            .unwrap();
+        assert_eq!(Some(vec![1u8, 2, 3]), value.maybe_bytes);
+    }
+    // The second read is served from the cache
+    assert_eq!(1, requests.load(Ordering::SeqCst));
+
+    let missing_key = StateKey::AccessPath(AccessPath::new(
+        account,
+        AccessPath::resource_access_vec(parse_struct_tag("0x1::Test::Missing").unwrap()),
+    ));
+    assert!(interface
+        .get_state_value_by_version(&missing_key, VERSION)
+        .unwrap()
+        .is_none());
+    assert!(interface
+        .get_account_state_by_version(AccountAddress::random(), VERSION)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_rest_interface_reuses_account_state_until_modified() {
+    let runtime = Runtime::new().unwrap();
+    let account = AccountAddress::random();
+    let requests = Arc::new(AtomicUsize::new(0));
+    let url = start_api_stand_in(&runtime, account, requests.clone());
+    let interface = RestDebuggerInterface::new(url).unwrap();
+
+    for version in (MODIFIED_VERSION..=VERSION).rev() {
+        assert!(interface
+            .get_account_state_by_version(account, version)
+            .unwrap()
+            .is_some());
+    }
+    // The write sets after the modifying version don't touch the account
+    assert_eq!(1, requests.load(Ordering::SeqCst));
+
+    interface
+        .get_account_state_by_version(account, MODIFIED_VERSION - 1)
+        .unwrap();
+    assert_eq!(2, requests.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_rest_interface_reads_events() {
+    let runtime = Runtime::new().unwrap();
+    let account = AccountAddress::random();
+    let url = start_api_stand_in(&runtime, account, Arc::new(AtomicUsize::new(0)));
+    let interface = RestDebuggerInterface::new(url).unwrap();
+
+    // Spans pages, and stops at the last event
+    let events = interface
+        .get_events(&event_key(account), 100, NUM_EVENTS)
+        .unwrap();
+    assert_eq!(events.len() as u64, NUM_EVENTS - 100);
+    for (event, sequence_number) in events.iter().zip(100..) {
+        assert_eq!(event.event.sequence_number(), sequence_number);
+        assert_eq!(event.transaction_version, sequence_number / 100);
+    }
+}
+
+#[test]
+fn test_rest_interface_reads_table_items() {
+    let runtime = Runtime::new().unwrap();
+    let account = AccountAddress::random();
+    let url = start_api_stand_in(&runtime, account, Arc::new(AtomicUsize::new(0)));
+    let interface = RestDebuggerInterface::new(url).unwrap();
+
+    let value = interface
+        .get_state_value_by_version(&StateKey::table_item(TABLE_HANDLE, vec![1u8]), VERSION)
+        .unwrap()
+        .unwrap();
+    assert_eq!(Some(vec![5u8, 6]), value.maybe_bytes);
+
+    assert!(interface
+        .get_state_value_by_version(&StateKey::table_item(TABLE_HANDLE, vec![2u8]), VERSION)
+        .unwrap()
+        .is_none());
+    assert!(interface
+        .get_state_value_by_version(&StateKey::table_item(TABLE_HANDLE, vec![1u8]), VERSION - 1)
+        .unwrap()
+        .is_none());
+}
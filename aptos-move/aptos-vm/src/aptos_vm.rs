@@ -11,7 +11,7 @@ use crate::{
         charge_global_write_gas_usage, get_transaction_output, AptosVMImpl, AptosVMInternals,
     },
     counters::*,
-    data_cache::StateViewCache,
+    data_cache::{AsMoveResolver, StateViewCache},
     errors::expect_only_successful_execution,
     logging::AdapterLogSchema,
     move_vm_ext::{MoveResolverExt, SessionExt, SessionId},
@@ -804,6 +804,19 @@ impl AptosVM {
         ))
     }
 
+    /// Executes a single user transaction against `state_view` without verifying its signature.
+    /// The output is returned to the caller and never committed, which makes this suitable for
+    /// dry-running transactions before they are submitted.
+    pub fn simulate_signed_transaction(
+        txn: &SignedTransaction,
+        state_view: &impl StateView,
+    ) -> (VMStatus, TransactionOutput) {
+        let vm = AptosVM::new(state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let txn = txn.clone().into_unchecked_for_simulation();
+        vm.execute_user_transaction(&state_view.as_move_resolver(), &txn, &log_context)
+    }
+
     /// Alternate form of 'execute_block' that keeps the vm_status before it goes into the
     /// `TransactionOutput`
     pub fn execute_block_and_keep_vm_status(
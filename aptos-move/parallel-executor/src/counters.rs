@@ -0,0 +1,25 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics_core::{register_int_counter, IntCounter};
+use once_cell::sync::Lazy;
+
+/// Count the number of incarnations aborted because their read-set failed validation.
+pub static PARALLEL_EXECUTION_ABORTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_parallel_execution_aborts",
+        "Number of incarnations aborted by a validation failure"
+    )
+    .unwrap()
+});
+
+/// Count the number of reads suspending an execution until the estimated write they depend on is
+/// re-executed.  Unlike aborts, which are each followed by a re-execution, the suspended execution
+/// resumes where it stopped.
+pub static PARALLEL_EXECUTION_DEPENDENCY_WAITS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_parallel_execution_dependency_waits",
+        "Number of reads waiting on a dependency on an estimated write"
+    )
+    .unwrap()
+});
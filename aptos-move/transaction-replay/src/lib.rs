@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::profiler::{unattributed_gas, RecordingStateView, TransactionProfile, VmTrace};
 use anyhow::{anyhow, bail, format_err, Result};
 use aptos_resource_viewer::{AnnotatedAccountStateBlob, AnnotatedMoveStruct, AptosValueAnnotator};
 use aptos_state_view::StateView;
@@ -13,10 +14,13 @@ use aptos_types::{
     account_view::AccountView,
     contract_event::{ContractEvent, EventWithVersion},
     event::EventKey,
+    on_chain_config::{OnChainConfig, VMConfig},
     transaction::{ChangeSet, Transaction, TransactionOutput, Version, WriteSetPayload},
     write_set::WriteOp,
 };
-use aptos_validator_interface::{AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView};
+use aptos_validator_interface::{
+    AptosValidatorInterface, DBDebuggerInterface, DebuggerStateView, RestDebuggerInterface,
+};
 use aptos_vm::{
     data_cache::{AsMoveResolver, RemoteStorage},
     logging::AdapterLogSchema,
@@ -39,6 +43,9 @@ use std::{
     convert::TryFrom,
     path::{Path, PathBuf},
 };
+use url::Url;
+
+pub mod profiler;
 
 #[cfg(test)]
 mod unit_tests;
@@ -64,6 +71,12 @@ impl AptosDebugger {
         )?)))
     }
 
+    pub fn rest_client(rest_endpoint: Url) -> Result<Self> {
+        Ok(Self::new(Box::new(RestDebuggerInterface::new(
+            rest_endpoint,
+        )?)))
+    }
+
     pub fn execute_transactions_at_version(
         &self,
         version: Version,
@@ -97,6 +110,51 @@ impl AptosDebugger {
         Ok(ret)
     }
 
+    /// Executes the transactions one at a time against the state of their committed version, and
+    /// profiles each of them
+    ///
+    /// The call trees are only built if the Move VM trace was enabled, see [`VmTrace::enable`].
+    pub fn profile_past_transactions(
+        &self,
+        begin: Version,
+        limit: u64,
+        mut vm_trace: Option<&mut VmTrace>,
+    ) -> Result<Vec<TransactionProfile>> {
+        let txns = self.debugger.get_committed_transactions(begin, limit)?;
+        let mut profiles = vec![];
+        for (version, txn) in (begin..).zip(txns.into_iter()) {
+            let state_view = DebuggerStateView::new(&*self.debugger, version.checked_sub(1));
+            let recording_view = RecordingStateView::new(&state_view);
+            let output = AptosVM::execute_block(vec![txn], &recording_view)
+                .map_err(|err| format_err!("Unexpected VM Error: {:?}", err))?
+                .pop()
+                .ok_or_else(|| anyhow!("No output for transaction {}", version))?;
+            let (call_tree, unattributed_gas) = match vm_trace.as_mut() {
+                Some(vm_trace) => {
+                    let vm_config = VMConfig::fetch_config(&RemoteStorage::new(&state_view))
+                        .ok_or_else(|| anyhow!("No VM config at version {}", version))?;
+                    let call_tree = vm_trace.next_call_tree(&vm_config.gas_schedule)?;
+                    let unattributed_gas = unattributed_gas(
+                        output.gas_used(),
+                        call_tree.as_ref(),
+                        vm_config.gas_schedule.gas_constants.gas_unit_scaling_factor,
+                    );
+                    (call_tree, unattributed_gas)
+                }
+                None => (None, output.gas_used()),
+            };
+            profiles.push(TransactionProfile {
+                version,
+                gas_used: output.gas_used(),
+                unattributed_gas,
+                status: format!("{:?}", output.status()),
+                call_tree,
+                storage: recording_view.into_storage_accesses(&output),
+            });
+        }
+        Ok(profiles)
+    }
+
     pub fn execute_transactions_by_epoch(
         &self,
         begin: Version,
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{bail, Result};
-use aptos_transaction_replay::AptosDebugger;
+use aptos_transaction_replay::{
+    profiler::{write_folded_stacks, VmTrace},
+    AptosDebugger,
+};
 use aptos_types::{
     account_address::AccountAddress,
     event::EventKey,
@@ -10,17 +13,30 @@ use aptos_types::{
 };
 use difference::Changeset;
 use move_deps::move_core_types::effects::ChangeSet;
-use std::{fs, path::PathBuf};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 use structopt::StructOpt;
+use url::Url;
 
 #[derive(Debug, StructOpt)]
 struct Opt {
     /// Path to the local AptosDB file
-    #[structopt(long, parse(from_os_str))]
+    #[structopt(long, parse(from_os_str), conflicts_with = "rest-endpoint")]
     db: Option<PathBuf>,
+    /// Full URL of the REST API of a node to replay from, e.g. https://fullnode.devnet.aptoslabs.com
+    #[structopt(long)]
+    rest_endpoint: Option<Url>,
     /// If true, persist the effects of replaying transactions via `cmd` to disk in a format understood by the Move CLI
     #[structopt(short = "s", global = true)]
     save_write_sets: bool,
+    /// Profile the replayed transactions one by one instead, and write the call trees as folded
+    /// stacks to `profile.folded` and the full profiles to `profile.json` in this directory
+    ///
+    /// The call trees are only built with the `profiler` feature, which traces the Move VM.
+    #[structopt(long, global = true, parse(from_os_str))]
+    profile: Option<PathBuf>,
     #[structopt(subcommand)] // Note that we mark a field as a subcommand
     cmd: Command,
 }
@@ -85,10 +101,65 @@ enum Command {
     },
 }
 
+/// Replays the transactions, or profiles them into `profile_dir` if given
+fn replay_transactions(
+    debugger: &AptosDebugger,
+    start: Version,
+    limit: u64,
+    save_write_sets: bool,
+    profile_dir: Option<&Path>,
+) -> Result<()> {
+    let profile_dir = match profile_dir {
+        Some(profile_dir) => profile_dir,
+        None => {
+            println!(
+                "{:#?}",
+                debugger.execute_past_transactions(start, limit, save_write_sets)
+            );
+            return Ok(());
+        }
+    };
+
+    fs::create_dir_all(profile_dir)?;
+    let mut vm_trace = if cfg!(feature = "profiler") {
+        Some(VmTrace::enable(&profile_dir.join("move_vm.trace"))?)
+    } else {
+        eprintln!("Built without the `profiler` feature, the profiles have no call trees");
+        None
+    };
+    let profiles = debugger.profile_past_transactions(start, limit, vm_trace.as_mut())?;
+    write_folded_stacks(
+        &profiles,
+        &mut fs::File::create(profile_dir.join("profile.folded"))?,
+    )?;
+    fs::write(
+        profile_dir.join("profile.json"),
+        serde_json::to_vec_pretty(&profiles)?,
+    )?;
+    for profile in &profiles {
+        println!(
+            "Version {}: {} gas used ({} unattributed), {} instructions, {} state keys accessed, {}",
+            profile.version,
+            profile.gas_used,
+            profile.unattributed_gas,
+            profile
+                .call_tree
+                .as_ref()
+                .map_or(0, |call_tree| call_tree.total_instructions),
+            profile.storage.len(),
+            profile.status
+        );
+    }
+    println!("Profiles written to {}", profile_dir.display());
+    Ok(())
+}
+
 fn main() -> Result<()> {
     let opt = Opt::from_args();
     let debugger = if let Some(p) = opt.db {
         AptosDebugger::db(p)?
+    } else if let Some(url) = opt.rest_endpoint {
+        AptosDebugger::rest_client(url)?
     } else {
         panic!("No debugger attached")
     };
@@ -97,34 +168,39 @@ fn main() -> Result<()> {
 
     match opt.cmd {
         Command::ReplayTransactions { start, limit } => {
-            println!(
-                "{:#?}",
-                debugger.execute_past_transactions(start, limit, opt.save_write_sets)
-            );
+            replay_transactions(
+                &debugger,
+                start,
+                limit,
+                opt.save_write_sets,
+                opt.profile.as_deref(),
+            )?;
         }
         Command::ReplayRecentTransactions { txns } => {
             let latest_version = debugger
                 .get_latest_version()
                 .expect("Failed to get latest version");
             assert!(latest_version >= txns);
-            println!(
-                "{:#?}",
-                debugger.execute_past_transactions(
-                    latest_version - txns,
-                    txns,
-                    opt.save_write_sets
-                )
-            );
+            replay_transactions(
+                &debugger,
+                latest_version - txns,
+                txns,
+                opt.save_write_sets,
+                opt.profile.as_deref(),
+            )?;
         }
         Command::ReplayTransactionBySequence { account, seq } => {
             let version = debugger
                 .get_version_by_account_sequence(account, seq)?
                 .expect("Version not found");
-            println!(
-                "Executing transaction at version: {:?}\n{:#?}",
+            println!("Executing transaction at version: {:?}", version);
+            replay_transactions(
+                &debugger,
                 version,
-                debugger.execute_past_transactions(version, 1, opt.save_write_sets)
-            );
+                1,
+                opt.save_write_sets,
+                opt.profile.as_deref(),
+            )?;
         }
         Command::ReplayWriteSetAtVersion {
             write_set_blob_path: path,
@@ -0,0 +1,407 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Profiling of replayed transactions
+//!
+//! The call tree of a transaction is rebuilt from the instruction trace of the Move VM, which it
+//! writes to the file named by `MOVE_VM_TRACE` when built with the `profiler` feature.  Each line
+//! of the trace is `<function>,<pc>,<instruction>`.
+//!
+//! The trace doesn't record the gas meter, so each instruction is estimated at the base cost of
+//! its opcode in the gas schedule of the transaction's version.  The size dependent part of the
+//! instruction costs, the natives, the intrinsic gas and the storage gas aren't attributed to any
+//! frame, and are reported by transaction as its unattributed gas instead.
+
+use anyhow::{bail, format_err, Result};
+use aptos_infallible::Mutex;
+use aptos_state_view::{StateView, StateViewId};
+use aptos_types::{
+    state_store::state_key::StateKey,
+    transaction::{TransactionOutput, Version},
+    write_set::WriteOp,
+};
+use move_deps::{
+    move_binary_format::file_format_common::Opcodes,
+    move_core_types::gas_schedule::{CostTable, GasAlgebra},
+};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+const MOVE_VM_TRACE_ENV_VAR: &str = "MOVE_VM_TRACE";
+
+/// Profile of the execution of a single transaction
+#[derive(Clone, Debug, Serialize)]
+pub struct TransactionProfile {
+    pub version: Version,
+    pub gas_used: u64,
+    /// Gas units of `gas_used` the estimated instruction gas of the call tree doesn't account for,
+    /// all of `gas_used` for transactions without a call tree
+    pub unattributed_gas: u64,
+    pub status: String,
+    /// Call tree of the Move functions, `None` for transactions which don't run Move code
+    pub call_tree: Option<Frame>,
+    /// Storage accesses by state key, including the reads of the VM to set itself up
+    pub storage: Vec<StorageAccess>,
+}
+
+/// A function in the call tree, merging all its calls from the same parent frame
+#[derive(Clone, Debug, Serialize)]
+pub struct Frame {
+    pub function: String,
+    pub calls: u64,
+    /// Instructions executed in the function itself
+    pub instructions: u64,
+    /// Instructions executed in the function and its callees
+    pub total_instructions: u64,
+    /// Internal gas units of the instructions of the function itself, at the base cost of their
+    /// opcodes
+    pub estimated_instruction_gas: u64,
+    /// Internal gas units of the instructions of the function and its callees, at the base cost
+    /// of their opcodes
+    pub total_estimated_instruction_gas: u64,
+    pub callees: Vec<Frame>,
+}
+
+impl Frame {
+    fn new(function: String) -> Self {
+        Self {
+            function,
+            calls: 1,
+            instructions: 0,
+            total_instructions: 0,
+            estimated_instruction_gas: 0,
+            total_estimated_instruction_gas: 0,
+            callees: vec![],
+        }
+    }
+
+    fn compute_totals(&mut self) {
+        self.total_instructions = self.instructions;
+        self.total_estimated_instruction_gas = self.estimated_instruction_gas;
+        for callee in self.callees.iter_mut() {
+            callee.compute_totals();
+            self.total_instructions += callee.total_instructions;
+            self.total_estimated_instruction_gas += callee.total_estimated_instruction_gas;
+        }
+    }
+
+    /// Writes the frames as folded stacks, weighted by their own instructions
+    fn write_folded(&self, prefix: &str, out: &mut impl Write) -> std::io::Result<()> {
+        let stack = if prefix.is_empty() {
+            self.function.clone()
+        } else {
+            format!("{};{}", prefix, self.function)
+        };
+        if self.instructions > 0 {
+            writeln!(out, "{} {}", stack, self.instructions)?;
+        }
+        for callee in &self.callees {
+            callee.write_folded(&stack, out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads and writes of a single state key
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct StorageAccess {
+    pub key: String,
+    pub reads: u64,
+    pub bytes_read: u64,
+    pub writes: u64,
+    pub bytes_written: u64,
+    pub deletions: u64,
+}
+
+/// Writes the profiles as folded stacks, e.g. for `inferno-flamegraph` or `flamegraph.pl`
+pub fn write_folded_stacks(profiles: &[TransactionProfile], out: &mut impl Write) -> Result<()> {
+    for call_tree in profiles
+        .iter()
+        .filter_map(|profile| profile.call_tree.as_ref())
+    {
+        call_tree.write_folded("", out)?;
+    }
+    Ok(())
+}
+
+/// Follows the Move VM instruction trace, to split it by transaction
+pub struct VmTrace {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl VmTrace {
+    /// Makes the Move VM trace to `path`
+    ///
+    /// The VM only checks for tracing once, so it has to be enabled before any transaction is
+    /// executed in the process.
+    pub fn enable(path: &Path) -> Result<Self> {
+        if !cfg!(feature = "profiler") {
+            bail!("Tracing the Move VM requires building with the `profiler` feature");
+        }
+        File::create(path)?;
+        std::env::set_var(MOVE_VM_TRACE_ENV_VAR, path);
+        Ok(Self {
+            path: path.to_path_buf(),
+            offset: 0,
+        })
+    }
+
+    /// Builds the call tree from the instructions traced since the last call, estimating their gas
+    /// with the gas schedule of the transaction
+    pub fn next_call_tree(&mut self, cost_table: &CostTable) -> Result<Option<Frame>> {
+        #[cfg(feature = "profiler")]
+        move_deps::move_vm_runtime::tracing::flush_tracing_buffer();
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut trace = String::new();
+        self.offset += file.read_to_string(&mut trace)? as u64;
+        build_call_tree(&trace, |instruction| {
+            instruction_gas(cost_table, instruction)
+        })
+    }
+}
+
+/// Internal gas units of a traced instruction, at the base cost of its opcode
+fn instruction_gas(cost_table: &CostTable, instruction: &str) -> u64 {
+    let name = instruction.split('(').next().unwrap_or_default();
+    opcode(name)
+        .and_then(|opcode| cost_table.instruction_table.get(opcode as usize - 1))
+        .map_or(0, |cost| cost.total().get())
+}
+
+/// The opcode of the name of a [`Bytecode`](move_deps::move_binary_format::file_format::Bytecode)
+fn opcode(name: &str) -> Option<Opcodes> {
+    Some(match name {
+        "Pop" => Opcodes::POP,
+        "Ret" => Opcodes::RET,
+        "BrTrue" => Opcodes::BR_TRUE,
+        "BrFalse" => Opcodes::BR_FALSE,
+        "Branch" => Opcodes::BRANCH,
+        "LdU8" => Opcodes::LD_U8,
+        "LdU64" => Opcodes::LD_U64,
+        "LdU128" => Opcodes::LD_U128,
+        "CastU8" => Opcodes::CAST_U8,
+        "CastU64" => Opcodes::CAST_U64,
+        "CastU128" => Opcodes::CAST_U128,
+        "LdConst" => Opcodes::LD_CONST,
+        "LdTrue" => Opcodes::LD_TRUE,
+        "LdFalse" => Opcodes::LD_FALSE,
+        "CopyLoc" => Opcodes::COPY_LOC,
+        "MoveLoc" => Opcodes::MOVE_LOC,
+        "StLoc" => Opcodes::ST_LOC,
+        "Call" => Opcodes::CALL,
+        "CallGeneric" => Opcodes::CALL_GENERIC,
+        "Pack" => Opcodes::PACK,
+        "PackGeneric" => Opcodes::PACK_GENERIC,
+        "Unpack" => Opcodes::UNPACK,
+        "UnpackGeneric" => Opcodes::UNPACK_GENERIC,
+        "ReadRef" => Opcodes::READ_REF,
+        "WriteRef" => Opcodes::WRITE_REF,
+        "FreezeRef" => Opcodes::FREEZE_REF,
+        "MutBorrowLoc" => Opcodes::MUT_BORROW_LOC,
+        "ImmBorrowLoc" => Opcodes::IMM_BORROW_LOC,
+        "MutBorrowField" => Opcodes::MUT_BORROW_FIELD,
+        "MutBorrowFieldGeneric" => Opcodes::MUT_BORROW_FIELD_GENERIC,
+        "ImmBorrowField" => Opcodes::IMM_BORROW_FIELD,
+        "ImmBorrowFieldGeneric" => Opcodes::IMM_BORROW_FIELD_GENERIC,
+        "MutBorrowGlobal" => Opcodes::MUT_BORROW_GLOBAL,
+        "MutBorrowGlobalGeneric" => Opcodes::MUT_BORROW_GLOBAL_GENERIC,
+        "ImmBorrowGlobal" => Opcodes::IMM_BORROW_GLOBAL,
+        "ImmBorrowGlobalGeneric" => Opcodes::IMM_BORROW_GLOBAL_GENERIC,
+        "Add" => Opcodes::ADD,
+        "Sub" => Opcodes::SUB,
+        "Mul" => Opcodes::MUL,
+        "Mod" => Opcodes::MOD,
+        "Div" => Opcodes::DIV,
+        "BitOr" => Opcodes::BIT_OR,
+        "BitAnd" => Opcodes::BIT_AND,
+        "Xor" => Opcodes::XOR,
+        "Or" => Opcodes::OR,
+        "And" => Opcodes::AND,
+        "Not" => Opcodes::NOT,
+        "Eq" => Opcodes::EQ,
+        "Neq" => Opcodes::NEQ,
+        "Lt" => Opcodes::LT,
+        "Gt" => Opcodes::GT,
+        "Le" => Opcodes::LE,
+        "Ge" => Opcodes::GE,
+        "Abort" => Opcodes::ABORT,
+        "Nop" => Opcodes::NOP,
+        "Exists" => Opcodes::EXISTS,
+        "ExistsGeneric" => Opcodes::EXISTS_GENERIC,
+        "MoveFrom" => Opcodes::MOVE_FROM,
+        "MoveFromGeneric" => Opcodes::MOVE_FROM_GENERIC,
+        "MoveTo" => Opcodes::MOVE_TO,
+        "MoveToGeneric" => Opcodes::MOVE_TO_GENERIC,
+        "Shl" => Opcodes::SHL,
+        "Shr" => Opcodes::SHR,
+        "VecPack" => Opcodes::VEC_PACK,
+        "VecLen" => Opcodes::VEC_LEN,
+        "VecImmBorrow" => Opcodes::VEC_IMM_BORROW,
+        "VecMutBorrow" => Opcodes::VEC_MUT_BORROW,
+        "VecPushBack" => Opcodes::VEC_PUSH_BACK,
+        "VecPopBack" => Opcodes::VEC_POP_BACK,
+        "VecUnpack" => Opcodes::VEC_UNPACK,
+        "VecSwap" => Opcodes::VEC_SWAP,
+        _ => return None,
+    })
+}
+
+/// Builds the call tree of a single transaction's trace, estimating the gas of each instruction
+/// with `instruction_gas`
+pub(crate) fn build_call_tree(
+    trace: &str,
+    instruction_gas: impl Fn(&str) -> u64,
+) -> Result<Option<Frame>> {
+    // The root is a placeholder parent for the entry functions of the transaction, e.g. the
+    // prologue, the script function and the epilogue
+    let mut root = Frame::new(String::new());
+    // Path of callee indices from the root to the current frame
+    let mut stack: Vec<usize> = vec![];
+    let mut previous_instruction = "";
+
+    for line in trace.lines() {
+        let mut parts = line.splitn(3, ',');
+        let (function, pc, instruction) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(function), Some(pc), Some(instruction)) => (function, pc, instruction),
+            _ => return Err(format_err!("Malformed VM trace line: {}", line)),
+        };
+        let pc: u16 = pc
+            .parse()
+            .map_err(|_| format_err!("Malformed VM trace line: {}", line))?;
+
+        // A callee starts at its first instruction, native callees aren't traced
+        let is_call = previous_instruction.starts_with("Call") && pc == 0;
+        if previous_instruction.starts_with("Ret") {
+            stack.pop();
+        }
+        let current = frame_at(&mut root, &stack);
+        if is_call || stack.is_empty() || current.function != function {
+            // After an abort, the next entry function starts from the root
+            if !is_call {
+                stack.clear();
+            }
+            let parent = frame_at(&mut root, &stack);
+            let index = match parent
+                .callees
+                .iter()
+                .position(|callee| callee.function == function)
+            {
+                Some(index) => {
+                    parent.callees[index].calls += 1;
+                    index
+                }
+                None => {
+                    parent.callees.push(Frame::new(function.to_string()));
+                    parent.callees.len() - 1
+                }
+            };
+            stack.push(index);
+        }
+        let frame = frame_at(&mut root, &stack);
+        frame.instructions += 1;
+        frame.estimated_instruction_gas += instruction_gas(instruction);
+        previous_instruction = instruction;
+    }
+
+    Ok(match root.callees.len() {
+        0 => None,
+        // A single entry function is the root itself
+        1 => {
+            let mut frame = root.callees.pop().unwrap();
+            frame.compute_totals();
+            Some(frame)
+        }
+        _ => {
+            root.function = "transaction".to_string();
+            root.calls = 1;
+            root.compute_totals();
+            Some(root)
+        }
+    })
+}
+
+/// Gas units of `gas_used` the estimated instruction gas of the call tree doesn't account for
+pub fn unattributed_gas(
+    gas_used: u64,
+    call_tree: Option<&Frame>,
+    gas_unit_scaling_factor: u64,
+) -> u64 {
+    let estimated_gas = call_tree.map_or(0, |call_tree| {
+        call_tree.total_estimated_instruction_gas / gas_unit_scaling_factor
+    });
+    gas_used.saturating_sub(estimated_gas)
+}
+
+fn frame_at<'a>(root: &'a mut Frame, stack: &[usize]) -> &'a mut Frame {
+    stack
+        .iter()
+        .fold(root, |frame, index| &mut frame.callees[*index])
+}
+
+/// State view recording the reads reaching the underlying storage
+pub struct RecordingStateView<'a> {
+    inner: &'a dyn StateView,
+    accesses: Mutex<BTreeMap<StateKey, StorageAccess>>,
+}
+
+impl<'a> RecordingStateView<'a> {
+    pub fn new(inner: &'a dyn StateView) -> Self {
+        Self {
+            inner,
+            accesses: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Adds the writes of the transaction's output to the recorded reads
+    pub fn into_storage_accesses(self, output: &TransactionOutput) -> Vec<StorageAccess> {
+        let mut accesses = std::mem::take(&mut *self.accesses.lock());
+        for (state_key, op) in output.write_set().iter() {
+            let access = accesses
+                .entry(state_key.clone())
+                .or_insert_with(|| new_access(state_key));
+            match op {
+                WriteOp::Value(bytes) => {
+                    access.writes += 1;
+                    access.bytes_written += bytes.len() as u64;
+                }
+                WriteOp::Deletion => access.deletions += 1,
+            }
+        }
+        accesses.into_values().collect()
+    }
+}
+
+impl<'a> StateView for RecordingStateView<'a> {
+    fn id(&self) -> StateViewId {
+        self.inner.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<Vec<u8>>> {
+        let value = self.inner.get_state_value(state_key)?;
+        let mut accesses = self.accesses.lock();
+        let access = accesses
+            .entry(state_key.clone())
+            .or_insert_with(|| new_access(state_key));
+        access.reads += 1;
+        access.bytes_read += value.as_ref().map_or(0, |bytes| bytes.len() as u64);
+        Ok(value)
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.inner.is_genesis()
+    }
+}
+
+fn new_access(state_key: &StateKey) -> StorageAccess {
+    StorageAccess {
+        key: format!("{:?}", state_key),
+        ..Default::default()
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod bisection_tests;
+mod profiler_tests;
 
 use crate::AptosValidatorInterface;
 use anyhow::{bail, Result};
@@ -0,0 +1,91 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::profiler::{build_call_tree, unattributed_gas};
+
+/// Charges calls 10 internal gas units, and other instructions 1
+fn call_gas(instruction: &str) -> u64 {
+    if instruction.starts_with("Call") {
+        10
+    } else {
+        1
+    }
+}
+
+#[test]
+fn test_call_tree_from_vm_trace() {
+    // The second call is to a native, which isn't traced
+    let trace = "\
+0x1::M::main,0,LdU64(1)
+0x1::M::main,1,Call(FunctionHandleIndex(1))
+0x1::M::helper,0,LdTrue
+0x1::M::helper,1,Ret
+0x1::M::main,2,Call(FunctionHandleIndex(2))
+0x1::M::main,3,Call(FunctionHandleIndex(1))
+0x1::M::helper,0,LdTrue
+0x1::M::helper,1,Ret
+0x1::M::main,4,Ret
+";
+    let root = build_call_tree(trace, call_gas).unwrap().unwrap();
+    assert_eq!("0x1::M::main", root.function);
+    assert_eq!(5, root.instructions);
+    assert_eq!(9, root.total_instructions);
+    assert_eq!(32, root.estimated_instruction_gas);
+    assert_eq!(36, root.total_estimated_instruction_gas);
+    assert_eq!(1, root.callees.len());
+    let helper = &root.callees[0];
+    assert_eq!("0x1::M::helper", helper.function);
+    assert_eq!(2, helper.calls);
+    assert_eq!(4, helper.instructions);
+    assert_eq!(4, helper.estimated_instruction_gas);
+}
+
+#[test]
+fn test_call_tree_with_several_entry_functions() {
+    let trace = "\
+0x1::Account::prologue,0,Ret
+0x1::M::main,0,Call(FunctionHandleIndex(1))
+0x1::M::main,0,Ret
+0x1::M::main,1,Ret
+0x1::Account::epilogue,0,Ret
+";
+    let root = build_call_tree(trace, call_gas).unwrap().unwrap();
+    assert_eq!("transaction", root.function);
+    assert_eq!(5, root.total_instructions);
+    assert_eq!(14, root.total_estimated_instruction_gas);
+    let functions: Vec<_> = root
+        .callees
+        .iter()
+        .map(|frame| frame.function.as_str())
+        .collect();
+    assert_eq!(
+        vec![
+            "0x1::Account::prologue",
+            "0x1::M::main",
+            "0x1::Account::epilogue"
+        ],
+        functions
+    );
+    // The recursive call is a callee of the first call
+    let main = &root.callees[1];
+    assert_eq!(3, main.total_instructions);
+    assert_eq!("0x1::M::main", main.callees[0].function);
+
+    assert!(build_call_tree("", call_gas).unwrap().is_none());
+    assert!(build_call_tree("not a trace", call_gas).is_err());
+}
+
+#[test]
+fn test_unattributed_gas() {
+    let trace = "\
+0x1::M::main,0,Call(FunctionHandleIndex(1))
+0x1::M::helper,0,Ret
+0x1::M::main,1,Ret
+";
+    let root = build_call_tree(trace, call_gas).unwrap().unwrap();
+    assert_eq!(12, root.total_estimated_instruction_gas);
+    // 12 internal gas units are 3 gas units
+    assert_eq!(7, unattributed_gas(10, Some(&root), 4));
+    assert_eq!(0, unattributed_gas(2, Some(&root), 4));
+    assert_eq!(10, unattributed_gas(10, None, 4));
+}
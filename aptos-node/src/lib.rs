@@ -13,6 +13,7 @@ use aptos_config::{
 use aptos_data_client::aptosnet::AptosNetDataClient;
 use aptos_infallible::RwLock;
 use aptos_logger::{prelude::*, Logger};
+use aptos_mempool::MempoolClientSender;
 use aptos_metrics::metric_server;
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
 use aptos_time_service::TimeService;
@@ -45,7 +46,7 @@ use std::{
     collections::{HashMap, HashSet},
     io::Write,
     net::ToSocketAddrs,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -65,6 +66,9 @@ const AC_SMP_CHANNEL_BUFFER_SIZE: usize = 1_024;
 const INTRA_NODE_CHANNEL_BUFFER_SIZE: usize = 1;
 const MEMPOOL_NETWORK_CHANNEL_BUFFER_SIZE: usize = 1_024;
 
+/// File of a single validator test network holding the key of the aptos root account.
+pub const TEST_ROOT_KEY_FILE: &str = "mint.key";
+
 pub struct AptosHandle {
     _api: Runtime,
     _backup: Runtime,
@@ -125,64 +129,23 @@ pub fn load_test_environment<R>(
     R: ::rand::RngCore + ::rand::CryptoRng,
 {
     let config_temp_path = aptos_temppath::TempPath::new();
-
-    let (try_load, config_path) = if let Some(config_path) = config_path {
-        (
-            config_path.join("0").join("node.yaml").exists(),
-            config_path,
-        )
-    } else {
-        (false, config_temp_path.as_ref().to_path_buf())
-    };
-
-    std::fs::DirBuilder::new()
-        .recursive(true)
-        .create(&config_path)
-        .unwrap();
-
+    let config_path = config_path.unwrap_or_else(|| config_temp_path.as_ref().to_path_buf());
+
+    let mut maybe_config = PathBuf::from(&config_path);
+    maybe_config.push("validator_node_template.yaml");
+    let template = NodeConfig::load_config(maybe_config)
+        .unwrap_or_else(|_| NodeConfig::default_for_validator());
+
+    let config = create_single_node_test_config(
+        &config_path,
+        template,
+        random_ports,
+        lazy,
+        genesis_modules,
+        rng,
+    );
     let config_path = config_path.canonicalize().unwrap();
-
-    let validator_config_path = config_path.join("0").join("node.yaml");
-    let aptos_root_key_path = config_path.join("mint.key");
-
-    let config = if try_load {
-        NodeConfig::load(&validator_config_path).expect("Unable to load config:")
-    } else {
-        // Build a single validator network
-        let mut maybe_config = PathBuf::from(&config_path);
-        maybe_config.push("validator_node_template.yaml");
-        let mut template = NodeConfig::load_config(maybe_config)
-            .unwrap_or_else(|_| NodeConfig::default_for_validator());
-
-        // enable REST and JSON-RPC API
-        template.api.address = format!("0.0.0.0:{}", template.api.address.port())
-            .parse()
-            .unwrap();
-        if lazy {
-            template.consensus.quorum_store_poll_count = u64::MAX;
-        }
-
-        let builder = aptos_genesis::builder::Builder::new(&config_path, genesis_modules)
-            .unwrap()
-            .with_template(template)
-            .with_randomize_first_validator_ports(random_ports);
-
-        let (root_key, _genesis, genesis_waypoint, validators) = builder.build(rng).unwrap();
-
-        let serialized_keys = bcs::to_bytes(&root_key).unwrap();
-        let mut key_file = std::fs::File::create(&aptos_root_key_path).unwrap();
-        key_file.write_all(&serialized_keys).unwrap();
-
-        // Build a waypoint file so that clients / docker can grab it easily
-        let waypoint_file_path = config_path.join("waypoint.txt");
-        std::io::Write::write_all(
-            &mut std::fs::File::create(&waypoint_file_path).unwrap(),
-            genesis_waypoint.to_string().as_bytes(),
-        )
-        .unwrap();
-
-        validators[0].config.clone()
-    };
+    let aptos_root_key_path = config_path.join(TEST_ROOT_KEY_FILE);
 
     // Prepare log file since we cannot automatically route logs to stderr
     let log_file = config_path.join("validator.log");
@@ -207,6 +170,66 @@ pub fn load_test_environment<R>(
     start(&config, Some(log_file))
 }
 
+/// Creates a single validator test network in `config_path`: its genesis, the config of the
+/// validator in `0/node.yaml` and the key of the aptos root account in `mint.key`.
+/// If a network was already created in `config_path`, its config is loaded instead so the
+/// existing chain is resumed, and `template` is ignored.
+pub fn create_single_node_test_config<R>(
+    config_path: &Path,
+    mut template: NodeConfig,
+    random_ports: bool,
+    lazy: bool,
+    genesis_modules: Vec<Vec<u8>>,
+    rng: R,
+) -> NodeConfig
+where
+    R: ::rand::RngCore + ::rand::CryptoRng,
+{
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .create(config_path)
+        .unwrap();
+
+    let config_path = config_path.canonicalize().unwrap();
+
+    let validator_config_path = config_path.join("0").join("node.yaml");
+    let aptos_root_key_path = config_path.join(TEST_ROOT_KEY_FILE);
+
+    if validator_config_path.exists() {
+        return NodeConfig::load(&validator_config_path).expect("Unable to load config:");
+    }
+
+    // enable REST and JSON-RPC API
+    template.api.address = format!("0.0.0.0:{}", template.api.address.port())
+        .parse()
+        .unwrap();
+    if lazy {
+        template.consensus.quorum_store_poll_count = u64::MAX;
+    }
+
+    // Build a single validator network
+    let builder = aptos_genesis::builder::Builder::new(&config_path, genesis_modules)
+        .unwrap()
+        .with_template(template)
+        .with_randomize_first_validator_ports(random_ports);
+
+    let (root_key, _genesis, genesis_waypoint, validators) = builder.build(rng).unwrap();
+
+    let serialized_keys = bcs::to_bytes(&root_key).unwrap();
+    let mut key_file = std::fs::File::create(&aptos_root_key_path).unwrap();
+    key_file.write_all(&serialized_keys).unwrap();
+
+    // Build a waypoint file so that clients / docker can grab it easily
+    let waypoint_file_path = config_path.join("waypoint.txt");
+    std::io::Write::write_all(
+        &mut std::fs::File::create(&waypoint_file_path).unwrap(),
+        genesis_waypoint.to_string().as_bytes(),
+    )
+    .unwrap();
+
+    validators[0].config.clone()
+}
+
 // Fetch chain ID from on-chain resource
 fn fetch_chain_id(db: &DbReaderWriter) -> ChainId {
     let db_state_view = db
@@ -221,7 +244,11 @@ fn fetch_chain_id(db: &DbReaderWriter) -> ChainId {
         .chain_id()
 }
 
-fn setup_debug_interface(config: &NodeConfig, logger: Option<Arc<Logger>>) -> NodeDebugService {
+fn setup_debug_interface(
+    config: &NodeConfig,
+    logger: Option<Arc<Logger>>,
+    mempool_client_sender: MempoolClientSender,
+) -> NodeDebugService {
     let addr = format!(
         "{}:{}",
         config.debug_interface.address, config.debug_interface.admission_control_node_debug_port,
@@ -231,7 +258,7 @@ fn setup_debug_interface(config: &NodeConfig, logger: Option<Arc<Logger>>) -> No
     .next()
     .unwrap();
 
-    NodeDebugService::new(addr, logger, config)
+    NodeDebugService::new(addr, logger, config, mempool_client_sender)
 }
 
 fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
@@ -425,7 +452,8 @@ async fn periodic_state_dump(node_config: NodeConfig, db: DbReaderWriter) {
 }
 
 pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>) -> AptosHandle {
-    let debug_if = setup_debug_interface(node_config, logger);
+    let (mp_client_sender, mp_client_events) = channel(AC_SMP_CHANNEL_BUFFER_SIZE);
+    let debug_if = setup_debug_interface(node_config, logger, mp_client_sender.clone());
 
     let metrics_port = node_config.debug_interface.metrics_server_port;
     let metric_host = node_config.debug_interface.address.clone();
@@ -611,8 +639,6 @@ pub fn setup_environment(node_config: &NodeConfig, logger: Option<Arc<Logger>>)
         db_rw.clone(),
     );
 
-    let (mp_client_sender, mp_client_events) = channel(AC_SMP_CHANNEL_BUFFER_SIZE);
-
     let api_runtime = bootstrap_api(node_config, chain_id, aptos_db, mp_client_sender).unwrap();
 
     let mut consensus_runtime = None;
@@ -18,11 +18,21 @@ pub struct ApiConfig {
     // optional for compatible with old configuration
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_length_limit: Option<u64>,
+    /// Throttles requests per client. No rate limiting is applied when not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<ApiRateLimitConfig>,
+    /// Maximum number of NDJSON streams served at the same time
+    pub max_concurrent_streams: usize,
 }
 
 pub const DEFAULT_ADDRESS: &str = "127.0.0.1";
 pub const DEFAULT_PORT: u16 = 8080;
 pub const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 4 * 1024 * 1024; // 4mb
+pub const IP_REQUEST_BUCKET_RATE: usize = 100; // requests/s
+pub const IP_REQUEST_BUCKET_SIZE: usize = 200;
+pub const API_KEY_REQUEST_BUCKET_RATE: usize = 1000; // requests/s
+pub const API_KEY_REQUEST_BUCKET_SIZE: usize = 2000;
+pub const DEFAULT_MAX_CONCURRENT_STREAMS: usize = 100;
 
 fn default_enabled() -> bool {
     true
@@ -38,6 +48,8 @@ impl Default for ApiConfig {
             tls_cert_path: None,
             tls_key_path: None,
             content_length_limit: None,
+            rate_limit: None,
+            max_concurrent_streams: DEFAULT_MAX_CONCURRENT_STREAMS,
         }
     }
 }
@@ -54,3 +66,40 @@ impl ApiConfig {
         }
     }
 }
+
+/// Token bucket rate limits of the API, counted in requests.
+///
+/// Requests carrying one of the configured `api_keys` are throttled by their API key, all the
+/// other requests are throttled by the IP address of the client.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiRateLimitConfig {
+    /// Maximum number of requests/s for an IP
+    pub ip_bucket_rate: usize,
+    /// Maximum burst of requests for an IP
+    pub ip_bucket_size: usize,
+    /// Maximum number of requests/s for an API key
+    pub api_key_bucket_rate: usize,
+    /// Maximum burst of requests for an API key
+    pub api_key_bucket_size: usize,
+    /// Initial amount of tokens initially in the bucket
+    pub initial_bucket_fill_percentage: u8,
+    /// API keys that get their own quota instead of the IP based one
+    pub api_keys: Vec<String>,
+    /// Allow for disabling the throttles
+    pub enabled: bool,
+}
+
+impl Default for ApiRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            ip_bucket_rate: IP_REQUEST_BUCKET_RATE,
+            ip_bucket_size: IP_REQUEST_BUCKET_SIZE,
+            api_key_bucket_rate: API_KEY_REQUEST_BUCKET_RATE,
+            api_key_bucket_size: API_KEY_REQUEST_BUCKET_SIZE,
+            initial_bucket_fill_percentage: 100,
+            api_keys: vec![],
+            enabled: true,
+        }
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -20,6 +21,26 @@ pub struct MempoolConfig {
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
     pub shared_mempool_validator_broadcast: bool,
+    /// Minimum increase of the gas unit price, in percent, for a transaction to replace the pending
+    /// one with the same sender and sequence number
+    pub replacement_gas_price_bump_percent: u64,
+    /// When mempool is full, evict the ready transactions with the lowest ranking score to make
+    /// room for a ready transaction ranked higher
+    pub evict_lower_ranked_txns: bool,
+    /// Maximum share, in percent, of a batch pulled from mempool that can come from a single
+    /// sender.  No limit if not set.
+    pub max_sender_batch_share_percent: Option<u64>,
+    /// Journal of the accepted and removed transactions, replayed on startup so that mempool
+    /// survives restarts.  Mempool is only kept in memory if not set.  A relative path is
+    /// resolved from the data dir.
+    pub journal_path: Option<PathBuf>,
+    /// Number of records appended to the journal before it is rewritten with only the
+    /// transactions currently in mempool
+    pub journal_compaction_threshold: usize,
+    /// Record the lifecycle trace of each transaction, served by the node debug interface
+    pub trace_transactions: bool,
+    #[serde(skip)]
+    data_dir: PathBuf,
 }
 
 impl Default for MempoolConfig {
@@ -38,6 +59,29 @@ impl Default for MempoolConfig {
             system_transaction_timeout_secs: 600,
             system_transaction_gc_interval_ms: 60_000,
             shared_mempool_validator_broadcast: true,
+            replacement_gas_price_bump_percent: 10,
+            evict_lower_ranked_txns: false,
+            max_sender_batch_share_percent: None,
+            journal_path: None,
+            journal_compaction_threshold: 100_000,
+            trace_transactions: false,
+            data_dir: PathBuf::from("/opt/aptos/data"),
         }
     }
 }
+
+impl MempoolConfig {
+    pub fn journal_path(&self) -> Option<PathBuf> {
+        self.journal_path.as_ref().map(|path| {
+            if path.is_relative() {
+                self.data_dir.join(path)
+            } else {
+                path.clone()
+            }
+        })
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
+}
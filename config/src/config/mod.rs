@@ -245,6 +245,7 @@ impl NodeConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
         self.base.data_dir = data_dir.clone();
         self.consensus.set_data_dir(data_dir.clone());
+        self.mempool.set_data_dir(data_dir.clone());
         self.storage.set_data_dir(data_dir);
     }
 
@@ -5,7 +5,9 @@ use crate::{
     config::{IdentityBlob, LoggerConfig, SecureBackend, WaypointConfig},
     keys::ConfigKey,
 };
-use aptos_crypto::{ed25519::Ed25519PrivateKey, Uniform};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, x25519, Uniform};
+use aptos_global_constants::SAFETY_RULES_NOISE_KEY;
+use aptos_secure_storage::{CryptoStorage, Storage};
 use aptos_types::{network_address::NetworkAddress, waypoint::Waypoint, PeerId};
 use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
@@ -49,7 +51,15 @@ impl Default for SafetyRulesConfig {
 impl SafetyRulesConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
         if let SecureBackend::OnDiskStorage(backend) = &mut self.backend {
-            backend.set_data_dir(data_dir);
+            backend.set_data_dir(data_dir.clone());
+        }
+        if let SafetyRulesService::Process(RemoteService {
+            noise: Some(noise), ..
+        }) = &mut self.service
+        {
+            if let SecureBackend::OnDiskStorage(backend) = &mut noise.backend {
+                backend.set_data_dir(data_dir);
+            }
         }
     }
 }
@@ -109,6 +119,9 @@ pub enum SafetyRulesService {
 #[serde(deny_unknown_fields)]
 pub struct RemoteService {
     pub server_address: NetworkAddress,
+    /// When set, the connection is authenticated and encrypted with a Noise IK handshake
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise: Option<RemoteServiceNoiseConfig>,
 }
 
 impl RemoteService {
@@ -121,6 +134,30 @@ impl RemoteService {
     }
 }
 
+/// Keys used to secure the channel between consensus and the safety rules process. Each side
+/// holds its own x25519 key in secure storage and pins the public key of the other side, any other
+/// peer is rejected during the handshake.
+///
+/// The key is always read from `SAFETY_RULES_NOISE_KEY`, so that the channel can't be configured
+/// with a key meant for something else, such as the consensus key.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteServiceNoiseConfig {
+    pub backend: SecureBackend,
+    pub remote_public_key: x25519::PublicKey,
+}
+
+impl RemoteServiceNoiseConfig {
+    pub fn private_key(&self) -> x25519::PrivateKey {
+        let storage: Storage = (&self.backend).into();
+        let key = storage
+            .export_private_key(SAFETY_RULES_NOISE_KEY)
+            .expect("Unable to read key");
+        x25519::PrivateKey::from_ed25519_private_bytes(&key.to_bytes())
+            .expect("Unable to convert key")
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct SafetyRulesTestConfig {
     pub author: PeerId,
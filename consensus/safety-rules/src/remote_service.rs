@@ -6,17 +6,22 @@ use crate::{
     serializer::{SafetyRulesInput, SerializerClient, SerializerService, TSerializerClient},
     Error, SafetyRules, TSafetyRules,
 };
+use aptos_config::config::RemoteServiceNoiseConfig;
 use aptos_logger::warn;
 use aptos_secure_net::{NetworkClient, NetworkServer};
 use std::net::SocketAddr;
 
 pub trait RemoteService {
     fn client(&self) -> SerializerClient {
-        let network_client = NetworkClient::new(
+        let mut network_client = NetworkClient::new(
             "safety-rules",
             self.server_address(),
             self.network_timeout_ms(),
         );
+        if let Some(noise) = self.noise() {
+            network_client =
+                network_client.with_noise(noise.private_key(), noise.remote_public_key);
+        }
         let service = Box::new(RemoteClient::new(network_client));
         SerializerClient::new_client(service)
     }
@@ -25,6 +30,11 @@ pub trait RemoteService {
 
     /// Network Timeout in milliseconds.
     fn network_timeout_ms(&self) -> u64;
+
+    /// Keys securing the connection to the service, if any.
+    fn noise(&self) -> Option<&RemoteServiceNoiseConfig> {
+        None
+    }
 }
 
 pub fn execute(
@@ -33,6 +43,7 @@ pub fn execute(
     verify_vote_proposal_signature: bool,
     export_consensus_key: bool,
     network_timeout_ms: u64,
+    noise: Option<RemoteServiceNoiseConfig>,
 ) {
     let mut safety_rules = SafetyRules::new(
         storage,
@@ -45,6 +56,9 @@ pub fn execute(
 
     let mut serializer_service = SerializerService::new(safety_rules);
     let mut network_server = NetworkServer::new("safety-rules", listen_addr, network_timeout_ms);
+    if let Some(noise) = noise {
+        network_server = network_server.with_noise(noise.private_key(), noise.remote_public_key);
+    }
 
     loop {
         if let Err(e) = process_one_message(&mut network_server, &mut serializer_service) {
@@ -0,0 +1,105 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{remote_service, test_utils, SafetyRulesManager};
+use aptos_config::{
+    config::{OnDiskStorageConfig, RemoteServiceNoiseConfig, SecureBackend},
+    utils,
+};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, x25519, Uniform};
+use aptos_global_constants::SAFETY_RULES_NOISE_KEY;
+use aptos_secure_net::NetworkClient;
+use aptos_secure_storage::{CryptoStorage, Storage};
+use aptos_temppath::TempPath;
+use aptos_types::validator_signer::ValidatorSigner;
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    thread,
+};
+
+/// Test value for network timeout, in milliseconds.
+const NETWORK_TIMEOUT: u64 = 5_000;
+
+/// Creates a secure storage holding a noise key, returning its backend and the public key.
+fn noise_backend(path: &TempPath) -> (SecureBackend, x25519::PublicKey) {
+    let mut config = OnDiskStorageConfig::default();
+    config.path = path.path().to_path_buf();
+    let backend = SecureBackend::OnDiskStorage(config);
+
+    let key = Ed25519PrivateKey::generate_for_testing();
+    let public_key = x25519::PrivateKey::from_ed25519_private_bytes(&key.to_bytes())
+        .unwrap()
+        .public_key();
+    let mut storage: Storage = (&backend).into();
+    storage
+        .import_private_key(SAFETY_RULES_NOISE_KEY, key)
+        .unwrap();
+    (backend, public_key)
+}
+
+/// Starts a safety rules service only accepting the client holding `client_public_key`.
+fn start_service(
+    server_backend: SecureBackend,
+    client_public_key: x25519::PublicKey,
+) -> SocketAddr {
+    let signer = ValidatorSigner::from_int(0);
+    let storage = test_utils::test_storage(&signer);
+    let server_port = utils::get_available_port();
+    let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+    let noise = RemoteServiceNoiseConfig {
+        backend: server_backend,
+        remote_public_key: client_public_key,
+    };
+    thread::spawn(move || {
+        remote_service::execute(
+            storage,
+            server_addr,
+            false,
+            false,
+            NETWORK_TIMEOUT,
+            Some(noise),
+        )
+    });
+    server_addr
+}
+
+#[test]
+fn test_noise_handshake() {
+    let (server_path, client_path) = (TempPath::new(), TempPath::new());
+    let (server_backend, server_public_key) = noise_backend(&server_path);
+    let (client_backend, client_public_key) = noise_backend(&client_path);
+    let server_addr = start_service(server_backend, client_public_key);
+
+    let safety_rules_manager = SafetyRulesManager::new_process(
+        server_addr,
+        NETWORK_TIMEOUT,
+        Some(RemoteServiceNoiseConfig {
+            backend: client_backend,
+            remote_public_key: server_public_key,
+        }),
+    );
+
+    // Reconnecting performs a new handshake
+    let state0 = safety_rules_manager.client().consensus_state().unwrap();
+    let state1 = safety_rules_manager.client().consensus_state().unwrap();
+    assert_eq!(state0, state1);
+}
+
+#[test]
+fn test_noise_handshake_unexpected_client_key() {
+    let (server_path, client_path, other_path) =
+        (TempPath::new(), TempPath::new(), TempPath::new());
+    let (server_backend, server_public_key) = noise_backend(&server_path);
+    let (_, client_public_key) = noise_backend(&client_path);
+    let (other_backend, _) = noise_backend(&other_path);
+    let server_addr = start_service(server_backend, client_public_key);
+
+    // The safety rules client retries forever, so the rejection is observed on the network client.
+    let other_noise = RemoteServiceNoiseConfig {
+        backend: other_backend,
+        remote_public_key: server_public_key,
+    };
+    let mut network_client = NetworkClient::new("safety-rules", server_addr, NETWORK_TIMEOUT)
+        .with_noise(other_noise.private_key(), other_noise.remote_public_key);
+    assert!(network_client.write(&[0, 1, 2, 3]).is_err());
+}
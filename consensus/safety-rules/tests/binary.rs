@@ -22,7 +22,10 @@ fn test_consensus_state() {
 
     let server_port = utils::get_available_port();
     let server_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port).into();
-    config.service = SafetyRulesService::Process(RemoteService { server_address });
+    config.service = SafetyRulesService::Process(RemoteService {
+        server_address,
+        noise: None,
+    });
 
     let config_path = aptos_temppath::TempPath::new();
     config_path.create_as_file().unwrap();
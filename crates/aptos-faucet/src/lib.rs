@@ -20,7 +20,6 @@
 //! ```
 
 use anyhow::Result;
-use aptos::common::types::EncodingType;
 use aptos_config::keys::ConfigKey;
 use aptos_crypto::ed25519::Ed25519PrivateKey;
 use aptos_logger::info;
@@ -34,7 +33,7 @@ use aptos_sdk::{
 };
 use futures::lock::Mutex;
 use reqwest::StatusCode;
-use std::{convert::Infallible, fmt, path::Path, sync::Arc};
+use std::{convert::Infallible, fmt, sync::Arc};
 use structopt::StructOpt;
 use url::Url;
 use warp::{http, Filter, Rejection, Reply};
@@ -100,9 +99,9 @@ impl FaucetArgs {
         let key = if let Some(ref key) = self.mint_key {
             key.private_key()
         } else {
-            EncodingType::BCS
-                .load_key::<Ed25519PrivateKey>("mint key", Path::new(&self.mint_key_file_path))
-                .unwrap()
+            let bytes =
+                std::fs::read(&self.mint_key_file_path).expect("unable to read mint key file");
+            bcs::from_bytes::<Ed25519PrivateKey>(&bytes).expect("unable to decode mint key")
         };
 
         let faucet_address: AccountAddress =
@@ -171,6 +171,16 @@ impl<Key: Eq + Hash + Clone + Debug> TokenBucketRateLimiter<Key> {
         }
         remove
     }
+
+    /// Garbage collects the unused buckets that refilled completely, as they behave like new
+    /// ones. Useful when keys aren't known, e.g. client IP addresses. Returns the number of
+    /// buckets removed.
+    pub fn garbage_collect_full_buckets(&self) -> usize {
+        let mut buckets = self.buckets.write();
+        let num_buckets = buckets.len();
+        buckets.retain(|_, bucket| Arc::strong_count(bucket) > 1 || !bucket.lock().is_full());
+        num_buckets - buckets.len()
+    }
 }
 
 /// A token bucket object that keeps track of everything related to a key
@@ -288,6 +298,12 @@ impl Bucket {
         }
     }
 
+    /// Whether the bucket holds as many tokens as it can, after refilling it
+    pub fn is_full(&mut self) -> bool {
+        self.refill();
+        self.tokens >= self.size
+    }
+
     /// Determine if an entire batch can be passed through
     /// This is important for message based rate limiting, where the whole message has
     /// to make it through, or else it must be rejected.  A result of `None` means it cannot
@@ -546,4 +562,25 @@ mod tests {
         assert!(!rate_limiter.try_garbage_collect_key(&key_to_keep));
         assert_num_keys(&rate_limiter, 1);
     }
+
+    #[test]
+    fn test_garbage_collect_full_buckets() {
+        let rate_limiter = TokenBucketRateLimiter::test(5, 1);
+
+        // Full and unused
+        rate_limiter.bucket("full");
+        // Used and unused
+        rate_limiter
+            .bucket("used")
+            .lock()
+            .acquire_all_tokens(1)
+            .unwrap();
+        // Full and in use
+        let _bucket_arc = rate_limiter.bucket("in use");
+        assert_num_keys(&rate_limiter, 3);
+
+        assert_eq!(1, rate_limiter.garbage_collect_full_buckets());
+        assert_num_keys(&rate_limiter, 2);
+        assert!(!rate_limiter.buckets.read().contains_key("full"));
+    }
 }
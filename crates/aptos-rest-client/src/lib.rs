@@ -2,18 +2,39 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{anyhow, Result};
-use aptos_api_types::mime_types::BCS_SIGNED_TRANSACTION as BCS_CONTENT_TYPE;
-pub use aptos_api_types::{self, MoveModuleBytecode, PendingTransaction, Transaction};
-use aptos_crypto::HashValue;
+pub use aptos_api_types::{
+    self, Block, Event, GasEstimation, MempoolStats, MempoolTransaction, MempoolTransactionStatus,
+    MoveModuleAbi, MoveModuleBytecode, PendingTransaction, SubmissionStatusCode, Transaction,
+    TransactionData, TransactionOnChainData, TransactionSubmissionResult,
+};
+use aptos_api_types::{
+    mime_types::{
+        BCS, BCS_GAS_ESTIMATION_REQUEST, BCS_SIGNED_TRANSACTION as BCS_CONTENT_TYPE,
+        BCS_SIGNED_TRANSACTION_BATCH,
+    },
+    HexEncodedBytes, RawGasEstimationRequest,
+};
+use aptos_crypto::{ed25519::Ed25519PublicKey, HashValue};
 use aptos_types::{
-    account_address::AccountAddress, account_config::aptos_root_address,
-    transaction::SignedTransaction,
+    account_address::AccountAddress,
+    account_config::{aptos_root_address, AccountResource},
+    contract_event::EventWithVersion,
+    event::EventKey,
+    transaction::{SignedTransaction, TransactionPayload},
+};
+use futures::{Stream, StreamExt};
+use move_deps::move_core_types::language_storage::StructTag;
+use reqwest::{
+    header::{ACCEPT, CONTENT_TYPE},
+    Client as ReqwestClient, StatusCode,
 };
-use reqwest::{header::CONTENT_TYPE, Client as ReqwestClient, StatusCode};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 use state::State;
-use std::time::Duration;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::Duration,
+};
 use url::Url;
 
 pub mod error;
@@ -28,6 +49,8 @@ pub use types::{Account, Resource, RestError};
 pub mod aptos;
 
 const USER_AGENT: &str = concat!("aptos-client-sdk-rust / ", env!("CARGO_PKG_VERSION"));
+/// How long a streaming request may stay open.
+const STREAM_TIMEOUT: Duration = Duration::from_secs(30 * 24 * 60 * 60);
 
 #[derive(Clone, Debug)]
 pub struct Client {
@@ -83,6 +106,8 @@ impl Client {
             epoch: r.epoch,
             version: r.ledger_version,
             timestamp_usecs: r.ledger_timestamp,
+            cursor: None,
+            total_count: None,
         });
 
         Ok(response)
@@ -103,6 +128,50 @@ impl Client {
         self.json(response).await
     }
 
+    /// Submits the transactions in one request, the result of each transaction is returned in
+    /// order and a rejected transaction doesn't fail the others.
+    pub async fn submit_batch(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<Response<Vec<TransactionSubmissionResult>>> {
+        let txns_payload = bcs::to_bytes(txns)?;
+        let url = self.base_url.join("transactions/batch")?;
+
+        let response = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION_BATCH)
+            .body(txns_payload)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn estimate_gas(
+        &self,
+        sender: AccountAddress,
+        public_key: &Ed25519PublicKey,
+        payload: TransactionPayload,
+    ) -> Result<Response<GasEstimation>> {
+        let request = bcs::to_bytes(&RawGasEstimationRequest {
+            sender,
+            public_key: public_key.clone(),
+            payload,
+        })?;
+        let url = self.base_url.join("transactions/estimate_gas")?;
+
+        let response = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, BCS_GAS_ESTIMATION_REQUEST)
+            .body(request)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
     pub async fn submit_and_wait(&self, txn: &SignedTransaction) -> Result<Response<Transaction>> {
         self.submit(txn).await?;
         self.wait_for_signed_transaction(txn).await
@@ -192,6 +261,30 @@ impl Client {
         self.json(response).await
     }
 
+    /// Streams the committed transactions from the `start` version, or from the next committed
+    /// transaction if `start` isn't provided. The stream waits for new transactions and only ends
+    /// on errors.
+    pub async fn stream_transactions(
+        &self,
+        start: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<Transaction>>> {
+        let url = self.base_url.join("transactions/stream")?;
+        self.ndjson_stream(url, start).await
+    }
+
+    /// Streams the events of `event_key` from the `start` sequence number, or from the first
+    /// event if `start` isn't provided.
+    pub async fn stream_events(
+        &self,
+        event_key: EventKey,
+        start: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<Event>>> {
+        let url = self
+            .base_url
+            .join(&format!("events/{}/stream", event_key))?;
+        self.ndjson_stream(url, start).await
+    }
+
     pub async fn get_transaction(&self, hash: HashValue) -> Result<Response<Transaction>> {
         self.json(
             self.get_transaction_by_version_or_hash(hash.to_hex_literal())
@@ -243,6 +336,104 @@ impl Client {
         self.json(response).await
     }
 
+    /// Iterates over the transactions sent by `address` across pages of `page_size`, from the
+    /// oldest or, when `reverse` is set, from the newest.
+    pub fn paginate_account_transactions(
+        &self,
+        address: AccountAddress,
+        reverse: bool,
+        page_size: Option<u16>,
+    ) -> impl Stream<Item = Result<Transaction>> + '_ {
+        self.paginate(
+            format!("accounts/{}/transactions", address),
+            reverse,
+            page_size,
+        )
+    }
+
+    /// Iterates over the events of `event_key` across pages of `page_size`, from the oldest or,
+    /// when `reverse` is set, from the newest.
+    pub fn paginate_events(
+        &self,
+        event_key: EventKey,
+        reverse: bool,
+        page_size: Option<u16>,
+    ) -> impl Stream<Item = Result<Event>> + '_ {
+        self.paginate(format!("events/{}", event_key), reverse, page_size)
+    }
+
+    /// Iterates over the events of the `field_name` event handle of the `struct_tag` resource of
+    /// `address` across pages of `page_size`, from the oldest or, when `reverse` is set, from the
+    /// newest.
+    pub fn paginate_events_by_event_handle(
+        &self,
+        address: AccountAddress,
+        struct_tag: &StructTag,
+        field_name: &str,
+        reverse: bool,
+        page_size: Option<u16>,
+    ) -> impl Stream<Item = Result<Event>> + '_ {
+        self.paginate(
+            format!("accounts/{}/events/{}/{}", address, struct_tag, field_name),
+            reverse,
+            page_size,
+        )
+    }
+
+    pub async fn get_block_by_height(
+        &self,
+        height: u64,
+        with_transactions: bool,
+    ) -> Result<Response<Block>> {
+        let url = self
+            .base_url
+            .join(&format!("blocks/by_height/{}", height))?;
+        let response = self
+            .inner
+            .get(url)
+            .query(&[("with_transactions", with_transactions)])
+            .send()
+            .await?;
+        self.json(response).await
+    }
+
+    pub async fn get_block_by_version(
+        &self,
+        version: u64,
+        with_transactions: bool,
+    ) -> Result<Response<Block>> {
+        let url = self
+            .base_url
+            .join(&format!("blocks/by_version/{}", version))?;
+        let response = self
+            .inner
+            .get(url)
+            .query(&[("with_transactions", with_transactions)])
+            .send()
+            .await?;
+        self.json(response).await
+    }
+
+    pub async fn get_mempool_transactions(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Response<Vec<MempoolTransaction>>> {
+        let url = self.base_url.join("mempool/transactions")?;
+        let response = self
+            .inner
+            .get(url)
+            .query(&[("account", address.to_hex_literal())])
+            .send()
+            .await?;
+        self.json(response).await
+    }
+
+    pub async fn get_mempool_stats(&self) -> Result<Response<MempoolStats>> {
+        let url = self.base_url.join("mempool/stats")?;
+        let response = self.inner.get(url).send().await?;
+        self.json(response).await
+    }
+
     pub async fn get_account_resources(
         &self,
         address: AccountAddress,
@@ -301,6 +492,31 @@ impl Client {
         self.json(response).await
     }
 
+    pub async fn get_account_module_abi(
+        &self,
+        address: AccountAddress,
+        module_name: &str,
+    ) -> Result<Response<MoveModuleAbi>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/modules/{}/abi", address, module_name))?;
+
+        let response = self.inner.get(url).send().await?;
+        self.json(response).await
+    }
+
+    pub async fn get_account_modules_abi(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Response<Vec<MoveModuleAbi>>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/modules/abi", address))?;
+
+        let response = self.inner.get(url).send().await?;
+        self.json(response).await
+    }
+
     pub async fn get_table_item<K: Serialize>(
         &self,
         table_handle: u128,
@@ -327,13 +543,233 @@ impl Client {
         self.json(response).await
     }
 
+    pub async fn get_account_bcs(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Response<AccountResource>> {
+        let url = self.base_url.join(&format!("accounts/{}", address))?;
+        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_account_resources_bcs(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Response<BTreeMap<StructTag, Vec<u8>>>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/resources", address))?;
+
+        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_account_resources_at_version_bcs(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<Response<BTreeMap<StructTag, Vec<u8>>>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/resources", address))?;
+
+        let response = self
+            .inner
+            .get(url)
+            .header(ACCEPT, BCS)
+            .query(&[("version", version)])
+            .send()
+            .await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_account_resource_bcs<T: DeserializeOwned>(
+        &self,
+        address: AccountAddress,
+        resource_type: &str,
+    ) -> Result<Response<T>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/resource/{}", address, resource_type))?;
+
+        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_account_modules_bcs(
+        &self,
+        address: AccountAddress,
+    ) -> Result<Response<Vec<Vec<u8>>>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/modules", address))?;
+
+        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_account_modules_at_version_bcs(
+        &self,
+        address: AccountAddress,
+        version: u64,
+    ) -> Result<Response<Vec<Vec<u8>>>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/modules", address))?;
+
+        let response = self
+            .inner
+            .get(url)
+            .header(ACCEPT, BCS)
+            .query(&[("version", version)])
+            .send()
+            .await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_table_item_bcs<K: Serialize, T: DeserializeOwned>(
+        &self,
+        table_handle: u128,
+        key_type: &str,
+        value_type: &str,
+        key: K,
+    ) -> Result<Response<T>> {
+        let url = self
+            .base_url
+            .join(&format!("tables/{}/item", table_handle))?;
+        let data = json!({
+            "key_type": key_type,
+            "value_type": value_type,
+            "key": json!(key),
+        });
+
+        let response = self
+            .inner
+            .post(url)
+            .header(ACCEPT, BCS)
+            .json(&data)
+            .send()
+            .await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_raw_table_item_at_version_bcs(
+        &self,
+        table_handle: u128,
+        key: &[u8],
+        version: u64,
+    ) -> Result<Response<Vec<u8>>> {
+        let url = self
+            .base_url
+            .join(&format!("tables/{}/raw_item", table_handle))?;
+        let data = json!({
+            "key": HexEncodedBytes::from(key.to_vec()),
+        });
+
+        let response = self
+            .inner
+            .post(url)
+            .header(ACCEPT, BCS)
+            .query(&[("version", version)])
+            .json(&data)
+            .send()
+            .await?;
+        // The value is returned as its raw bytes rather than as the BCS of a byte vector.
+        let (response, state) = self.check_response(response).await?;
+        Ok(Response::new(response.bytes().await?.to_vec(), state))
+    }
+
+    pub async fn get_transactions_bcs(
+        &self,
+        start: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Response<Vec<TransactionOnChainData>>> {
+        let url = self.base_url.join("transactions")?;
+
+        let mut request = self.inner.get(url).header(ACCEPT, BCS);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        let response = request.send().await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_transaction_bcs(&self, hash: HashValue) -> Result<Response<TransactionData>> {
+        let url = self
+            .base_url
+            .join(&format!("transactions/{}", hash.to_hex_literal()))?;
+
+        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_transaction_by_version_bcs(
+        &self,
+        version: u64,
+    ) -> Result<Response<TransactionData>> {
+        let url = self.base_url.join(&format!("transactions/{}", version))?;
+
+        let response = self.inner.get(url).header(ACCEPT, BCS).send().await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_account_transactions_bcs(
+        &self,
+        address: AccountAddress,
+        start: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Response<Vec<TransactionOnChainData>>> {
+        let url = self
+            .base_url
+            .join(&format!("accounts/{}/transactions", address))?;
+
+        let mut request = self.inner.get(url).header(ACCEPT, BCS);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        let response = request.send().await?;
+        self.bcs(response).await
+    }
+
+    pub async fn get_events_bcs(
+        &self,
+        event_key: EventKey,
+        start: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Response<Vec<EventWithVersion>>> {
+        let url = self.base_url.join(&format!("events/{}", event_key))?;
+
+        let mut request = self.inner.get(url).header(ACCEPT, BCS);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit)])
+        }
+
+        let response = request.send().await?;
+        self.bcs(response).await
+    }
+
     async fn check_response(
         &self,
         response: reqwest::Response,
     ) -> Result<(reqwest::Response, State)> {
         if !response.status().is_success() {
+            // Kept as the error's source, so callers can downcast it to check the status code
             let error_response = response.json::<RestError>().await?;
-            return Err(anyhow::anyhow!("Request failed: {:?}", error_response));
+            return Err(error_response.into());
         }
         let state = State::from_headers(response.headers())?;
 
@@ -349,6 +785,101 @@ impl Client {
         Ok(Response::new(json, state))
     }
 
+    async fn bcs<T: DeserializeOwned>(&self, response: reqwest::Response) -> Result<Response<T>> {
+        let (response, state) = self.check_response(response).await?;
+        let inner = bcs::from_bytes(&response.bytes().await?)?;
+        Ok(Response::new(inner, state))
+    }
+
+    /// Follows the `X-Aptos-Cursor` of the pages of the list at `path` until the last page.
+    fn paginate<T: DeserializeOwned>(
+        &self,
+        path: String,
+        reverse: bool,
+        page_size: Option<u16>,
+    ) -> impl Stream<Item = Result<T>> + '_ {
+        // `None` once the last page is read, `Some(None)` before reading the first page.
+        let next_page: Option<Option<String>> = Some(None);
+        futures::stream::unfold(
+            (next_page, VecDeque::new()),
+            move |(mut next_page, mut items)| {
+                let path = path.clone();
+                async move {
+                    loop {
+                        if let Some(item) = items.pop_front() {
+                            return Some((Ok(item), (next_page, items)));
+                        }
+                        let cursor = next_page?;
+                        match self.get_page(&path, cursor, reverse, page_size).await {
+                            Ok(response) => {
+                                let (page, state) = response.into_parts();
+                                next_page = state.cursor.map(Some);
+                                items = page.into();
+                            }
+                            Err(err) => return Some((Err(err), (None, VecDeque::new()))),
+                        }
+                    }
+                }
+            },
+        )
+    }
+
+    async fn get_page<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        cursor: Option<String>,
+        reverse: bool,
+        page_size: Option<u16>,
+    ) -> Result<Response<Vec<T>>> {
+        let url = self.base_url.join(path)?;
+        let mut request = self.inner.get(url);
+        match cursor {
+            Some(cursor) => request = request.query(&[("cursor", cursor)]),
+            None if reverse => request = request.query(&[("reverse", true)]),
+            None => {}
+        }
+        if let Some(page_size) = page_size {
+            request = request.query(&[("limit", page_size)])
+        }
+        self.json(request.send().await?).await
+    }
+
+    async fn ndjson_stream<T: DeserializeOwned>(
+        &self,
+        url: Url,
+        start: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<T>>> {
+        // Streams never end on their own, so they override the client timeout
+        let mut request = self.inner.get(url).timeout(STREAM_TIMEOUT);
+        if let Some(start) = start {
+            request = request.query(&[("start", start)])
+        }
+        let (response, _state) = self.check_response(request.send().await?).await?;
+
+        let bytes = Box::pin(response.bytes_stream());
+        Ok(futures::stream::unfold(
+            Some((bytes, Vec::new())),
+            |state| async move {
+                let (mut bytes, mut buffer) = state?;
+                loop {
+                    if let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                        let line: Vec<u8> = buffer.drain(..=pos).collect();
+                        let item = match serde_json::from_slice::<NdjsonItem<T>>(&line) {
+                            Ok(NdjsonItem::Item(item)) => Ok(item),
+                            Ok(NdjsonItem::Error(err)) => Err(anyhow!("Stream failed: {:?}", err)),
+                            Err(err) => Err(err.into()),
+                        };
+                        return Some((item, Some((bytes, buffer))));
+                    }
+                    match bytes.next().await? {
+                        Ok(chunk) => buffer.extend_from_slice(&chunk),
+                        Err(err) => return Some((Err(err.into()), None)),
+                    }
+                }
+            },
+        ))
+    }
+
     pub async fn health_check(&self, seconds: u64) -> Result<()> {
         let url = self.base_url.join("-/healthy")?;
         let response = self
@@ -365,3 +896,11 @@ impl Client {
         Ok(())
     }
 }
+
+/// A line of a streaming response: the server ends the stream with an error line on failures.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NdjsonItem<T> {
+    Item(T),
+    Error(RestError),
+}
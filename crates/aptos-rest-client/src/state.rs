@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_api_types::{
-    X_APTOS_CHAIN_ID, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP, X_APTOS_LEDGER_VERSION,
+    X_APTOS_CHAIN_ID, X_APTOS_CURSOR, X_APTOS_EPOCH, X_APTOS_LEDGER_TIMESTAMP,
+    X_APTOS_LEDGER_VERSION, X_APTOS_TOTAL_COUNT,
 };
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -11,6 +12,10 @@ pub struct State {
     pub epoch: u64,
     pub version: u64,
     pub timestamp_usecs: u64,
+    /// Cursor of the next page of a paginated list, `None` on the last page.
+    pub cursor: Option<String>,
+    /// Total number of items of a paginated list.
+    pub total_count: Option<u64>,
 }
 
 impl State {
@@ -31,6 +36,14 @@ impl State {
             .get(X_APTOS_EPOCH)
             .and_then(|h| h.to_str().ok())
             .and_then(|s| s.parse().ok());
+        let cursor = headers
+            .get(X_APTOS_CURSOR)
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_owned());
+        let total_count = headers
+            .get(X_APTOS_TOTAL_COUNT)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|s| s.parse().ok());
 
         let state = if let (Some(chain_id), Some(version), Some(timestamp_usecs), Some(epoch)) =
             (maybe_chain_id, maybe_version, maybe_timestamp, maybe_epoch)
@@ -40,6 +53,8 @@ impl State {
                 epoch,
                 version,
                 timestamp_usecs,
+                cursor,
+                total_count,
             }
         } else {
             todo!()
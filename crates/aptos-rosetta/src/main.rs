@@ -3,7 +3,7 @@
 
 #![forbid(unsafe_code)]
 
-use aptos_config::config::ApiConfig;
+use aptos_config::config::{ApiConfig, DEFAULT_MAX_CONCURRENT_STREAMS};
 use aptos_rosetta::bootstrap;
 use aptos_types::chain_id::ChainId;
 use clap::Parser;
@@ -27,6 +27,8 @@ async fn main() {
         tls_cert_path: args.tls_cert_path,
         tls_key_path: args.tls_key_path,
         content_length_limit: args.content_length_limit,
+        rate_limit: None,
+        max_concurrent_streams: DEFAULT_MAX_CONCURRENT_STREAMS,
     };
 
     // Ensure runtime for Rosetta is up and running
@@ -75,6 +75,8 @@ impl CreateAccount {
             self.write_options
                 .chain_id(&self.profile_options.profile)
                 .await?,
+            self.write_options
+                .sender_address(&self.profile_options.profile, &sender_key)?,
             sender_key,
             aptos_stdlib::encode_account_create_account(address),
             self.write_options.max_gas,
@@ -7,6 +7,7 @@ use clap::Subcommand;
 pub mod create;
 pub mod fund;
 pub mod list;
+pub mod rotate_key;
 pub mod transfer;
 
 /// CLI tool for interacting with accounts
@@ -16,6 +17,7 @@ pub enum AccountTool {
     Create(create::CreateAccount),
     Fund(fund::FundAccount),
     List(list::ListAccount),
+    RotateKey(rotate_key::RotateKey),
     Transfer(transfer::TransferCoins),
 }
 
@@ -25,6 +27,7 @@ impl AccountTool {
             AccountTool::Create(tool) => tool.execute_serialized().await,
             AccountTool::Fund(tool) => tool.execute_serialized().await,
             AccountTool::List(tool) => tool.execute_serialized().await,
+            AccountTool::RotateKey(tool) => tool.execute_serialized().await,
             AccountTool::Transfer(tool) => tool.execute_serialized().await,
         }
     }
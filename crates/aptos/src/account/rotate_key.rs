@@ -0,0 +1,164 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{
+        account_address_from_public_key, CliCommand, CliConfig, CliError, CliTypedResult,
+        EncodingOptions, ProfileOptions, TransactionSummary, WriteTransactionOptions,
+    },
+    utils::submit_transaction,
+};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
+use aptos_rest_client::Client;
+use aptos_transaction_builder::aptos_stdlib;
+use aptos_types::{account_address::AccountAddress, transaction::authenticator::AuthenticationKey};
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Command to rotate the authentication key of an account
+///
+/// The account keeps its address, but only the new key can sign its transactions afterwards.
+/// Once the rotation is committed and verified onchain, the profile is updated with the new key.
+#[derive(Debug, Parser)]
+pub struct RotateKey {
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) write_options: WriteTransactionOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    /// Address of the account to rotate the key of
+    ///
+    /// Defaults to the account of the profile
+    #[clap(long, parse(try_from_str=crate::common::types::load_account_arg))]
+    pub(crate) account: Option<AccountAddress>,
+    /// New private key input file name
+    #[clap(long, group = "new_private_key_input", parse(from_os_str))]
+    pub(crate) new_private_key_file: Option<PathBuf>,
+    /// New private key encoded in a type as shown in `encoding`
+    #[clap(long, group = "new_private_key_input")]
+    pub(crate) new_private_key: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<RotateSummary> for RotateKey {
+    fn command_name(&self) -> &'static str {
+        "RotateKey"
+    }
+
+    async fn execute(self) -> CliTypedResult<RotateSummary> {
+        let profile = &self.profile_options.profile;
+        let encoding = self.encoding_options.encoding;
+        let sender_key = self
+            .write_options
+            .private_key_options
+            .extract_private_key(encoding, profile)?;
+        let new_private_key: Ed25519PrivateKey = if let Some(ref file) = self.new_private_key_file {
+            encoding.load_key("--new-private-key-file", file.as_path())?
+        } else if let Some(ref key) = self.new_private_key {
+            encoding.decode_key("--new-private-key", key.as_bytes().to_vec())?
+        } else {
+            return Err(CliError::CommandArgumentError(
+                "One of ['--new-private-key', '--new-private-key-file'] must be used".to_string(),
+            ));
+        };
+        let address = match self.account {
+            Some(address) => address,
+            None => CliConfig::load_profile(profile)?
+                .and_then(|profile| profile.account)
+                .unwrap_or_else(|| account_address_from_public_key(&sender_key.public_key())),
+        };
+
+        let url = self.write_options.rest_options.url(profile)?;
+        let new_auth_key = AuthenticationKey::ed25519(&new_private_key.public_key());
+        if get_authentication_key(&url, address).await? == new_auth_key {
+            return Err(CliError::CommandArgumentError(format!(
+                "Account {} already uses the new key",
+                address
+            )));
+        }
+
+        let transaction = submit_transaction(
+            url.clone(),
+            self.write_options.chain_id(profile).await?,
+            address,
+            sender_key,
+            aptos_stdlib::encode_account_rotate_authentication_key(new_auth_key.to_vec()),
+            self.write_options.max_gas,
+        )
+        .await?;
+        if !transaction.success() {
+            return Err(CliError::ApiError(format!(
+                "Key rotation failed: {}",
+                transaction.vm_status()
+            )));
+        }
+
+        // Only forget the old key once the new one is known to be in use
+        let onchain_auth_key = get_authentication_key(&url, address).await?;
+        if onchain_auth_key != new_auth_key {
+            return Err(CliError::UnexpectedError(format!(
+                "Authentication key of {} is {} instead of the new key's {}",
+                address, onchain_auth_key, new_auth_key
+            )));
+        }
+        let profile_updated = update_profile(profile, address, new_private_key)?;
+
+        Ok(RotateSummary {
+            account: address,
+            authentication_key: new_auth_key,
+            profile_updated,
+            transaction: TransactionSummary::from(transaction),
+        })
+    }
+}
+
+async fn get_authentication_key(
+    url: &reqwest::Url,
+    address: AccountAddress,
+) -> CliTypedResult<AuthenticationKey> {
+    Client::new(url.clone())
+        .get_account(address)
+        .await
+        .map(|response| response.into_inner().authentication_key)
+        .map_err(|err| CliError::ApiError(err.to_string()))
+}
+
+/// Replaces the keys of the profile, if it's the profile of the account
+fn update_profile(
+    profile: &str,
+    address: AccountAddress,
+    new_private_key: Ed25519PrivateKey,
+) -> CliTypedResult<bool> {
+    if !CliConfig::config_exists() {
+        return Ok(false);
+    }
+    let mut config = CliConfig::load()?;
+    let profile_config = match config
+        .profiles
+        .as_mut()
+        .and_then(|profiles| profiles.get_mut(profile))
+    {
+        Some(profile_config) if profile_config.account.unwrap_or(address) == address => {
+            profile_config
+        }
+        _ => return Ok(false),
+    };
+    // The address can't be derived from the key anymore
+    profile_config.account = Some(address);
+    profile_config.public_key = Some(new_private_key.public_key());
+    profile_config.private_key = Some(new_private_key);
+    config.save()?;
+    Ok(true)
+}
+
+/// Summary of a key rotation
+#[derive(Clone, Debug, Serialize)]
+pub struct RotateSummary {
+    pub account: AccountAddress,
+    pub authentication_key: AuthenticationKey,
+    pub profile_updated: bool,
+    pub transaction: TransactionSummary,
+}
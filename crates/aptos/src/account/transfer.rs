@@ -54,6 +54,8 @@ impl CliCommand<TransferSummary> for TransferCoins {
             self.write_options
                 .chain_id(&self.profile_options.profile)
                 .await?,
+            self.write_options
+                .sender_address(&self.profile_options.profile, &sender_key)?,
             sender_key,
             aptos_stdlib::encode_test_coin_transfer(self.account, self.amount),
             self.write_options.max_gas,
@@ -5,8 +5,9 @@ use crate::{
     common::{
         init::{DEFAULT_FAUCET_URL, DEFAULT_REST_URL},
         utils::{
-            check_if_file_exists, read_from_file, to_common_result, to_common_success_result,
-            write_to_file, write_to_file_with_opts, write_to_user_only_file,
+            check_if_file_exists, get_sequence_number, read_from_file, to_common_result,
+            to_common_success_result, to_pretty_result, write_to_file, write_to_file_with_opts,
+            write_to_user_only_file, DEFAULT_GAS_UNIT_PRICE,
         },
     },
     genesis::git::from_yaml,
@@ -18,7 +19,10 @@ use aptos_crypto::{
 use aptos_keygen::KeyGen;
 use aptos_logger::debug;
 use aptos_rest_client::{aptos_api_types::WriteSetChange, Client, Transaction};
-use aptos_types::{chain_id::ChainId, transaction::authenticator::AuthenticationKey};
+use aptos_types::{
+    chain_id::ChainId,
+    transaction::{authenticator::AuthenticationKey, RawTransaction, TransactionPayload},
+};
 use async_trait::async_trait;
 use clap::{ArgEnum, Parser};
 use hex::FromHexError;
@@ -32,10 +36,15 @@ use std::{
     fs::OpenOptions,
     path::{Path, PathBuf},
     str::FromStr,
-    time::Instant,
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
 
+/// Maximum gas of transactions built to be signed separately
+pub const DEFAULT_MAX_GAS: u64 = 1_000_000;
+/// Expiration of transactions built to be signed separately, leaving time to sign them offline
+pub const DEFAULT_EXPIRATION_SECS: u64 = 3600;
+
 /// A common result to be returned to users
 pub type CliResult = Result<String, String>;
 
@@ -61,6 +70,8 @@ pub enum CliError {
     IO(String, #[source] std::io::Error),
     #[error("Move compilation failed: {0}")]
     MoveCompilationError(String),
+    #[error("Move Prover failed: {0}")]
+    MoveProverError(String),
     #[error("Move unit tests failed: {0}")]
     MoveTestError(String),
     #[error("Unable to parse '{0}': error: {1}")]
@@ -82,6 +93,7 @@ impl CliError {
             CliError::ConfigNotFoundError(_) => "ConfigNotFoundError",
             CliError::IO(_, _) => "IO",
             CliError::MoveCompilationError(_) => "MoveCompilationError",
+            CliError::MoveProverError(_) => "MoveProverError",
             CliError::MoveTestError(_) => "MoveTestError",
             CliError::UnableToParse(_, _) => "UnableToParse",
             CliError::UnableToReadFile(_, _) => "UnableToReadFile",
@@ -147,6 +159,7 @@ pub struct CliConfig {
 
 const CONFIG_FILE: &str = "config.yaml";
 const LEGACY_CONFIG_FILE: &str = "config.yml";
+const TEMP_CONFIG_FILE: &str = "config.yaml.tmp";
 const CONFIG_FOLDER: &str = ".aptos";
 
 /// An individual profile
@@ -239,12 +252,16 @@ impl CliConfig {
             debug!("{} folder already initialized", aptos_folder.display());
         }
 
-        // Save over previous config file
+        // Save over previous config file, through a temporary file so that the config is
+        // never left partially written
         let config_file = aptos_folder.join(CONFIG_FILE);
+        let temp_config_file = aptos_folder.join(TEMP_CONFIG_FILE);
         let config_bytes = serde_yaml::to_string(&self).map_err(|err| {
             CliError::UnexpectedError(format!("Failed to serialize config {}", err))
         })?;
-        write_to_user_only_file(&config_file, CONFIG_FILE, config_bytes.as_bytes())?;
+        write_to_user_only_file(&temp_config_file, CONFIG_FILE, config_bytes.as_bytes())?;
+        std::fs::rename(&temp_config_file, &config_file)
+            .map_err(|err| CliError::IO(CONFIG_FILE.to_string(), err))?;
 
         // As a cleanup, delete the old if it exists
         let legacy_config_file = aptos_folder.join(LEGACY_CONFIG_FILE);
@@ -451,6 +468,51 @@ impl PromptOptions {
     }
 }
 
+/// Formats of command outputs
+#[derive(ArgEnum, Clone, Copy, Debug)]
+pub enum OutputFormat {
+    /// The common JSON output of commands
+    Json,
+    /// Human readable text
+    Pretty,
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let str = match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Pretty => "pretty",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "pretty" => Ok(OutputFormat::Pretty),
+            _ => Err("Invalid output format. Valid values are json, pretty"),
+        }
+    }
+}
+
+/// An insertable option for choosing the output format of a command.
+#[derive(Debug, Default, Parser)]
+pub struct OutputOptions {
+    /// Output format as `json` or `pretty`
+    #[clap(long, default_value_t = OutputFormat::Json)]
+    pub output: OutputFormat,
+}
+
+impl Default for OutputFormat {
+    fn default() -> Self {
+        OutputFormat::Json
+    }
+}
+
 /// An insertable option for use with encodings.
 #[derive(Debug, Default, Parser)]
 pub struct EncodingOptions {
@@ -529,6 +591,11 @@ impl PrivateKeyInputOptions {
         }
     }
 
+    /// Whether no private key is given on the command line
+    pub fn is_empty(&self) -> bool {
+        self.private_key_file.is_none() && self.private_key.is_none()
+    }
+
     /// Extract private key from CLI args
     pub fn extract_private_key_cli(
         &self,
@@ -642,42 +709,137 @@ impl RestOptions {
     }
 }
 
-const DEFAULT_MAX_GAS: u64 = 1000;
-
 /// Options specific to submitting a private key to the Rest endpoint
-#[derive(Debug, Parser)]
+#[derive(Debug, Default, Parser)]
 pub struct WriteTransactionOptions {
     #[clap(flatten)]
     pub private_key_options: PrivateKeyInputOptions,
     #[clap(flatten)]
     pub rest_options: RestOptions,
-    /// Maximum gas to be used to publish the package
+    /// Maximum gas to be used to send the transaction
     ///
-    /// Defaults to 1000 gas units
-    #[clap(long, default_value_t = DEFAULT_MAX_GAS)]
-    pub max_gas: u64,
-}
-
-impl Default for WriteTransactionOptions {
-    fn default() -> Self {
-        Self {
-            private_key_options: Default::default(),
-            rest_options: Default::default(),
-            max_gas: DEFAULT_MAX_GAS,
-        }
-    }
+    /// Defaults to an estimate from executing the transaction on the node, in which case the
+    /// gas unit price is estimated as well
+    #[clap(long)]
+    pub max_gas: Option<u64>,
 }
 
 impl WriteTransactionOptions {
     /// Retrieve the chain id from onchain via the Rest API
     pub async fn chain_id(&self, profile: &str) -> CliTypedResult<ChainId> {
-        let client = Client::new(self.rest_options.url(profile)?);
-        let state = client
-            .get_ledger_information()
-            .await
-            .map_err(|err| CliError::ApiError(err.to_string()))?
-            .into_inner();
-        Ok(ChainId::new(state.chain_id))
+        fetch_chain_id(&self.rest_options, profile).await
+    }
+
+    /// Retrieve the address of the account sending with the `sender_key`
+    ///
+    /// A key given on the command line is for the address derived from it, while a key from the
+    /// profile is for the account of the profile, which differs once its key is rotated
+    pub fn sender_address(
+        &self,
+        profile: &str,
+        sender_key: &Ed25519PrivateKey,
+    ) -> CliTypedResult<AccountAddress> {
+        let profile_account = if self.private_key_options.is_empty() {
+            CliConfig::load_profile(profile)?.and_then(|profile| profile.account)
+        } else {
+            None
+        };
+        Ok(profile_account
+            .unwrap_or_else(|| account_address_from_public_key(&sender_key.public_key())))
+    }
+}
+
+/// Retrieve the chain id from onchain via the Rest API
+async fn fetch_chain_id(rest_options: &RestOptions, profile: &str) -> CliTypedResult<ChainId> {
+    let client = Client::new(rest_options.url(profile)?);
+    let state = client
+        .get_ledger_information()
+        .await
+        .map_err(|err| CliError::ApiError(err.to_string()))?
+        .into_inner();
+    Ok(ChainId::new(state.chain_id))
+}
+
+/// Options for building a transaction to be signed separately from its submission
+///
+/// Values which aren't given are fetched from the Rest endpoint, so a transaction can be
+/// built without network access when all of them are given.
+#[derive(Debug, Parser)]
+pub struct RawTransactionOptions {
+    /// Address of the sender of the transaction
+    ///
+    /// Defaults to the account of the profile
+    #[clap(long, parse(try_from_str = load_account_arg))]
+    pub sender: Option<AccountAddress>,
+    /// Sequence number of the transaction
+    ///
+    /// Defaults to the current sequence number of the sender onchain
+    #[clap(long)]
+    pub sequence_number: Option<u64>,
+    /// Chain id of the network the transaction is for
+    ///
+    /// Defaults to the chain id of the Rest endpoint
+    #[clap(long)]
+    pub chain_id: Option<ChainId>,
+    /// Maximum gas to be used to send the transaction
+    #[clap(long, default_value_t = DEFAULT_MAX_GAS)]
+    pub max_gas: u64,
+    /// Price to be paid per gas unit
+    #[clap(long, default_value_t = DEFAULT_GAS_UNIT_PRICE)]
+    pub gas_unit_price: u64,
+    /// Number of seconds from now after which the transaction expires
+    ///
+    /// This has to leave enough time to sign the transaction and submit it
+    #[clap(long, default_value_t = DEFAULT_EXPIRATION_SECS)]
+    pub expiration_secs: u64,
+    #[clap(flatten)]
+    pub rest_options: RestOptions,
+}
+
+impl RawTransactionOptions {
+    /// Builds a [`RawTransaction`] sending the `payload`
+    pub async fn build(
+        &self,
+        profile: &str,
+        payload: TransactionPayload,
+    ) -> CliTypedResult<RawTransaction> {
+        let sender = match self.sender {
+            Some(sender) => sender,
+            None => CliConfig::load_profile(profile)?
+                .and_then(|profile| profile.account)
+                .ok_or_else(|| {
+                    CliError::CommandArgumentError(
+                        "'--sender' or '--profile' after using aptos init must be provided"
+                            .to_string(),
+                    )
+                })?,
+        };
+        let sequence_number = match self.sequence_number {
+            Some(sequence_number) => sequence_number,
+            None => {
+                let client = Client::new(self.rest_options.url(profile)?);
+                get_sequence_number(&client, sender).await?
+            }
+        };
+        let chain_id = match self.chain_id {
+            Some(chain_id) => chain_id,
+            None => fetch_chain_id(&self.rest_options, profile).await?,
+        };
+        let expiration_timestamp_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            .as_secs()
+            + self.expiration_secs;
+
+        Ok(RawTransaction::new(
+            sender,
+            sequence_number,
+            payload,
+            self.max_gas,
+            self.gas_unit_price,
+            expiration_timestamp_secs,
+            chain_id,
+        ))
     }
 }
 
@@ -736,9 +898,18 @@ pub fn load_account_arg(str: &str) -> Result<AccountAddress, CliError> {
         })
     } else if let Ok(account_address) = AccountAddress::from_str(str) {
         Ok(account_address)
-    } else if let Some(Some(private_key)) = CliConfig::load_profile(str)?.map(|p| p.private_key) {
-        let public_key = private_key.public_key();
-        Ok(account_address_from_public_key(&public_key))
+    } else if let Some(profile) = CliConfig::load_profile(str)? {
+        // The account of the profile is only derived from its key until the key is rotated
+        if let Some(account) = profile.account {
+            Ok(account)
+        } else if let Some(private_key) = profile.private_key {
+            Ok(account_address_from_public_key(&private_key.public_key()))
+        } else {
+            Err(CliError::CommandArgumentError(format!(
+                "Profile {} has no account",
+                str
+            )))
+        }
     } else {
         Err(CliError::CommandArgumentError(
             "'--account-address' or '--profile' after using aptos init must be provided"
@@ -763,6 +934,24 @@ pub trait CliCommand<T: Serialize + Send>: Sized + Send {
         to_common_result(command_name, start_time, self.execute().await).await
     }
 
+    /// Executes the command, and either serializes it to the common JSON output type or prints
+    /// it as human readable text
+    async fn execute_formatted(self, format: OutputFormat) -> CliResult
+    where
+        T: Display,
+    {
+        let command_name = self.command_name();
+        let start_time = Instant::now();
+        match format {
+            OutputFormat::Json => {
+                to_common_result(command_name, start_time, self.execute().await).await
+            }
+            OutputFormat::Pretty => {
+                to_pretty_result(command_name, start_time, self.execute().await).await
+            }
+        }
+    }
+
     /// Executes the command, and throws away Ok(result) for the string Success
     async fn execute_serialized_success(self) -> CliResult {
         let command_name = self.command_name();
@@ -774,11 +963,11 @@ pub trait CliCommand<T: Serialize + Send>: Sized + Send {
 /// A shortened transaction output
 #[derive(Clone, Debug, Default, Serialize)]
 pub struct TransactionSummary {
-    changes: Vec<ChangeSummary>,
-    gas_used: Option<u64>,
-    success: bool,
-    version: Option<u64>,
-    vm_status: String,
+    pub changes: Vec<ChangeSummary>,
+    pub gas_used: Option<u64>,
+    pub success: bool,
+    pub version: Option<u64>,
+    pub vm_status: String,
 }
 
 impl From<Transaction> for TransactionSummary {
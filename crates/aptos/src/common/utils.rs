@@ -8,10 +8,7 @@ use crate::{
 use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey};
 use aptos_rest_client::{Client, Transaction};
 use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
-use aptos_types::{
-    chain_id::ChainId,
-    transaction::{authenticator::AuthenticationKey, TransactionPayload},
-};
+use aptos_types::{chain_id::ChainId, transaction::TransactionPayload};
 use itertools::Itertools;
 use move_deps::move_core_types::account_address::AccountAddress;
 use reqwest::Url;
@@ -20,6 +17,7 @@ use shadow_rs::shadow;
 use std::{
     collections::BTreeMap,
     env,
+    fmt::Display,
     fs::OpenOptions,
     io::Write,
     os::unix::fs::OpenOptionsExt,
@@ -83,6 +81,22 @@ pub async fn to_common_result<T: Serialize>(
     }
 }
 
+/// For printing outputs as human readable text, errors are still in JSON
+pub async fn to_pretty_result<T: Display>(
+    command: &str,
+    start_time: Instant,
+    result: CliTypedResult<T>,
+) -> CliResult {
+    match result {
+        Ok(inner) => {
+            let string = inner.to_string();
+            to_common_success_result(command, start_time, Ok(())).await?;
+            Ok(string)
+        }
+        Err(err) => to_common_result::<()>(command, start_time, Err(err)).await,
+    }
+}
+
 /// A result wrapper for displaying either a correct execution result or an error.
 ///
 /// The purpose of this is to have a pretty easy to recognize JSON output format e.g.
@@ -232,27 +246,42 @@ where
     Ok(map)
 }
 
-/// Submits a [`TransactionPayload`] as signed by the `sender_key`
+/// Gas unit price used when the max gas amount is given explicitly
+pub const DEFAULT_GAS_UNIT_PRICE: u64 = 1;
+
+/// Submits a [`TransactionPayload`] from the `sender_address` as signed by the `sender_key`
+///
+/// If `max_gas` is not provided, the max gas amount and gas unit price are estimated by the node
 pub async fn submit_transaction(
     url: Url,
     chain_id: ChainId,
+    sender_address: AccountAddress,
     sender_key: Ed25519PrivateKey,
     payload: TransactionPayload,
-    max_gas: u64,
+    max_gas: Option<u64>,
 ) -> CliTypedResult<Transaction> {
     let client = Client::new(url);
 
-    // Get sender address
-    let sender_address = AuthenticationKey::ed25519(&sender_key.public_key()).derived_address();
-    let sender_address = AccountAddress::new(*sender_address);
-
     // Get sequence number for account
     let sequence_number = get_sequence_number(&client, sender_address).await?;
 
+    // Estimate gas if the max gas isn't provided
+    let (max_gas_amount, gas_unit_price) = match max_gas {
+        Some(max_gas) => (max_gas, DEFAULT_GAS_UNIT_PRICE),
+        None => {
+            let estimation = client
+                .estimate_gas(sender_address, &sender_key.public_key(), payload.clone())
+                .await
+                .map_err(|err| CliError::ApiError(err.to_string()))?
+                .into_inner();
+            (estimation.max_gas_amount.0, estimation.gas_unit_price.0)
+        }
+    };
+
     // Sign and submit transaction
     let transaction_factory = TransactionFactory::new(chain_id)
-        .with_gas_unit_price(1)
-        .with_max_gas_amount(max_gas);
+        .with_gas_unit_price(gas_unit_price)
+        .with_max_gas_amount(max_gas_amount);
     let sender_account = &mut LocalAccount::new(sender_address, sender_key, sequence_number);
     let transaction =
         sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
@@ -0,0 +1,151 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{
+    load_account_arg, CliCommand, CliError, CliResult, CliTypedResult, OutputOptions,
+    ProfileOptions, RestOptions,
+};
+use aptos_rest_client::{Client, Event};
+use aptos_types::{account_address::AccountAddress, event::EventKey};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use futures::{StreamExt, TryStreamExt};
+use move_deps::move_core_types::{language_storage::StructTag, parser::parse_struct_tag};
+use serde::Serialize;
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+/// Tool for inspecting events
+///
+#[derive(Subcommand)]
+pub enum EventTool {
+    List(ListEvents),
+}
+
+impl EventTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            EventTool::List(tool) => {
+                let format = tool.output_options.output;
+                tool.execute_formatted(format).await
+            }
+        }
+    }
+}
+
+/// Command to list the events of an event stream
+///
+/// The event stream is given either by its `key`, or by the `account` holding the `handle`
+/// resource and the `field-name` of its event handle.  The pages of events are fetched until
+/// the `limit` is reached.
+#[derive(Debug, Parser)]
+pub struct ListEvents {
+    /// Key of the event stream as a hex string
+    #[clap(long, group = "event_stream", parse(try_from_str = parse_event_key))]
+    pub(crate) key: Option<EventKey>,
+    /// Struct tag of the resource holding the event handle
+    ///
+    /// Example: `0x1::Coin::CoinStore<0x1::TestCoin::TestCoin>`
+    #[clap(
+        long,
+        group = "event_stream",
+        requires_all = &["account", "field_name"],
+        parse(try_from_str = parse_struct_tag)
+    )]
+    pub(crate) handle: Option<StructTag>,
+    /// Address of the account holding the `handle` resource
+    #[clap(long, parse(try_from_str = load_account_arg))]
+    pub(crate) account: Option<AccountAddress>,
+    /// Name of the event handle field in the `handle` resource
+    ///
+    /// Example: `deposit_events`
+    #[clap(long)]
+    pub(crate) field_name: Option<String>,
+    /// Maximum number of events to list, all of them if not given
+    #[clap(long)]
+    pub(crate) limit: Option<usize>,
+    /// List the events from the newest instead of from the oldest
+    #[clap(long)]
+    pub(crate) reverse: bool,
+    /// Number of events fetched per request
+    #[clap(long)]
+    pub(crate) page_size: Option<u16>,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) output_options: OutputOptions,
+}
+
+#[async_trait]
+impl CliCommand<EventList> for ListEvents {
+    fn command_name(&self) -> &'static str {
+        "ListEvents"
+    }
+
+    async fn execute(self) -> CliTypedResult<EventList> {
+        let client = Client::new(self.rest_options.url(&self.profile_options.profile)?);
+        let limit = self.limit.unwrap_or(usize::MAX);
+        let events: anyhow::Result<Vec<Event>> = match (
+            self.key,
+            &self.handle,
+            self.account,
+            &self.field_name,
+        ) {
+            (Some(key), _, _, _) => {
+                client
+                    .paginate_events(key, self.reverse, self.page_size)
+                    .take(limit)
+                    .try_collect()
+                    .await
+            }
+            (None, Some(handle), Some(account), Some(field_name)) => {
+                client
+                    .paginate_events_by_event_handle(
+                        account,
+                        handle,
+                        field_name,
+                        self.reverse,
+                        self.page_size,
+                    )
+                    .take(limit)
+                    .try_collect()
+                    .await
+            }
+            _ => return Err(CliError::CommandArgumentError(
+                "Either '--key', or '--handle' with '--account' and '--field-name' must be used"
+                    .to_string(),
+            )),
+        };
+        events
+            .map(EventList)
+            .map_err(|err| CliError::ApiError(err.to_string()))
+    }
+}
+
+fn parse_event_key(str: &str) -> CliTypedResult<EventKey> {
+    EventKey::from_str(str.strip_prefix("0x").unwrap_or(str))
+        .map_err(|err| CliError::UnableToParse("event key", err.to_string()))
+}
+
+/// Events as returned by the Rest API, printable as human readable text
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct EventList(pub Vec<Event>);
+
+impl Display for EventList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, event) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "Event {} #{}", event.key, event.sequence_number)?;
+            writeln!(f, "  Type: {}", event.typ)?;
+            writeln!(f, "  Data: {}", event.data)?;
+        }
+        Ok(())
+    }
+}
@@ -6,10 +6,14 @@
 pub mod account;
 pub mod common;
 pub mod config;
+pub mod event;
 pub mod genesis;
 pub mod move_tool;
+pub mod multisig;
+pub mod node;
 pub mod op;
 pub mod test;
+pub mod transaction;
 
 use crate::common::types::{CliCommand, CliResult};
 use clap::Parser;
@@ -24,12 +28,20 @@ pub enum Tool {
     #[clap(subcommand)]
     Config(config::ConfigTool),
     #[clap(subcommand)]
+    Event(event::EventTool),
+    #[clap(subcommand)]
     Genesis(genesis::GenesisTool),
     Init(common::init::InitTool),
     #[clap(subcommand)]
     Key(op::key::KeyTool),
     #[clap(subcommand)]
     Move(move_tool::MoveTool),
+    #[clap(subcommand)]
+    Multisig(multisig::MultisigTool),
+    #[clap(subcommand)]
+    Node(node::NodeTool),
+    #[clap(subcommand)]
+    Transaction(transaction::TransactionTool),
 }
 
 impl Tool {
@@ -37,11 +49,15 @@ impl Tool {
         match self {
             Tool::Account(tool) => tool.execute().await,
             Tool::Config(tool) => tool.execute().await,
+            Tool::Event(tool) => tool.execute().await,
             // TODO: Replace entirely with config init
             Tool::Genesis(tool) => tool.execute().await,
             Tool::Init(tool) => tool.execute_serialized_success().await,
             Tool::Key(tool) => tool.execute().await,
             Tool::Move(tool) => tool.execute().await,
+            Tool::Multisig(tool) => tool.execute().await,
+            Tool::Node(tool) => tool.execute().await,
+            Tool::Transaction(tool) => tool.execute().await,
         }
     }
 }
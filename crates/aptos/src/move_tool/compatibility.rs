@@ -0,0 +1,294 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks that a module bundle can be published over the modules already onchain
+
+use crate::common::types::{CliError, CliTypedResult};
+use aptos_rest_client::aptos_api_types::MoveModuleBytecode;
+use aptos_types::transaction::ModuleBundle;
+use move_deps::{
+    move_binary_format::{
+        compatibility::Compatibility, file_format::Visibility, normalized::Module, CompiledModule,
+    },
+    move_core_types::{account_address::AccountAddress, language_storage::ModuleId},
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
+
+/// Incompatible changes of a module, which would break its dependents or its published data
+#[derive(Clone, Debug, Serialize)]
+pub struct ModuleIncompatibility {
+    pub module: String,
+    /// Whether the structs and public functions other modules link to are unchanged
+    pub linking_compatible: bool,
+    /// Whether the layout of the structs which can be stored onchain is unchanged
+    pub layout_compatible: bool,
+    /// Structs which were removed, or whose fields, abilities or type parameters changed
+    pub changed_structs: Vec<String>,
+    /// Public functions which were removed, or whose signature changed
+    pub changed_functions: Vec<String>,
+}
+
+impl fmt::Display for ModuleIncompatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:", self.module)?;
+        if !self.changed_structs.is_empty() {
+            write!(
+                f,
+                " struct layout changed for [{}]",
+                self.changed_structs.join(", ")
+            )?;
+        }
+        if !self.changed_functions.is_empty() {
+            write!(
+                f,
+                " function signature changed for [{}]",
+                self.changed_functions.join(", ")
+            )?;
+        }
+        if self.changed_structs.is_empty() && self.changed_functions.is_empty() {
+            write!(f, " linking or layout changed")?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks every module of the bundle against the published module of the same name
+///
+/// Modules which aren't published yet are always compatible.
+pub fn check_compatibility(
+    published: &[MoveModuleBytecode],
+    bundle: &ModuleBundle,
+) -> CliTypedResult<Vec<ModuleIncompatibility>> {
+    let mut old_modules = BTreeMap::new();
+    for bytecode in published {
+        let module = deserialize_module("published module", bytecode.bytecode.inner())?;
+        old_modules.insert(module.self_id(), Module::new(&module));
+    }
+
+    let mut incompatibilities = Vec::new();
+    for code in bundle.iter() {
+        let module = deserialize_module("compiled module", code.code())?;
+        let module_id = module.self_id();
+        if let Some(old) = old_modules.get(&module_id) {
+            let new = Module::new(&module);
+            let compatibility = Compatibility::check(old, &new);
+            if !compatibility.is_fully_compatible() {
+                incompatibilities.push(describe_incompatibility(
+                    &module_id,
+                    old,
+                    &new,
+                    compatibility,
+                ));
+            }
+        }
+    }
+    Ok(incompatibilities)
+}
+
+/// Fails with the incompatible changes, unless incompatible modules are allowed e.g. with
+/// `--force` or `--dry-run`
+pub fn ensure_compatible(
+    address: AccountAddress,
+    incompatibilities: &[ModuleIncompatibility],
+    allow_incompatible: bool,
+) -> CliTypedResult<()> {
+    if incompatibilities.is_empty() || allow_incompatible {
+        return Ok(());
+    }
+    let changes: Vec<_> = incompatibilities
+        .iter()
+        .map(ModuleIncompatibility::to_string)
+        .collect();
+    Err(CliError::CommandArgumentError(format!(
+        "Package is incompatible with the modules published at {}: {}. Use --force to publish it anyway",
+        address,
+        changes.join("; ")
+    )))
+}
+
+fn deserialize_module(name: &'static str, bytes: &[u8]) -> CliTypedResult<CompiledModule> {
+    CompiledModule::deserialize(bytes).map_err(|err| CliError::UnableToParse(name, err.to_string()))
+}
+
+fn describe_incompatibility(
+    module_id: &ModuleId,
+    old: &Module,
+    new: &Module,
+    compatibility: Compatibility,
+) -> ModuleIncompatibility {
+    let changed_structs = old
+        .structs
+        .iter()
+        .filter(|(name, old_struct)| new.structs.get(*name) != Some(*old_struct))
+        .map(|(name, _)| name.to_string())
+        .collect();
+    // Only public functions can be linked to from other accounts' modules
+    let changed_functions = old
+        .exposed_functions
+        .iter()
+        .filter(|(_, old_function)| old_function.visibility == Visibility::Public)
+        .filter(
+            |(name, old_function)| match new.exposed_functions.get(*name) {
+                Some(new_function) => {
+                    new_function.visibility != Visibility::Public
+                        || new_function.type_parameters != old_function.type_parameters
+                        || new_function.parameters != old_function.parameters
+                        || new_function.return_ != old_function.return_
+                }
+                None => true,
+            },
+        )
+        .map(|(name, _)| name.to_string())
+        .collect();
+
+    ModuleIncompatibility {
+        module: format!(
+            "{}::{}",
+            module_id.address().to_hex_literal(),
+            module_id.name()
+        ),
+        linking_compatible: compatibility.struct_and_function_linking,
+        layout_compatible: compatibility.struct_layout,
+        changed_structs,
+        changed_functions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::transaction::Module as ModuleBytes;
+    use move_deps::move_ir_compiler::Compiler;
+
+    const OLD_MODULE: &str = "
+        module 0x1.M {
+            struct S has store { x: u64 }
+
+            public f(x: u64): u64 {
+            label b0:
+                return copy(x);
+            }
+
+            public g() {
+            label b0:
+                return;
+            }
+        }
+    ";
+
+    fn compile(code: &str) -> Vec<u8> {
+        Compiler { deps: vec![] }
+            .into_module_blob(code)
+            .expect("Module compilation failed")
+    }
+
+    fn check(old: &str, new: &str) -> Vec<ModuleIncompatibility> {
+        let published = vec![MoveModuleBytecode::new(compile(old))];
+        let bundle = ModuleBundle::from(ModuleBytes::new(compile(new)));
+        check_compatibility(&published, &bundle).unwrap()
+    }
+
+    #[test]
+    fn test_compatible_upgrade() {
+        // Adding structs and functions is compatible
+        let new = "
+            module 0x1.M {
+                struct S has store { x: u64 }
+                struct T has store { y: bool }
+
+                public f(x: u64): u64 {
+                label b0:
+                    return copy(x) + 1;
+                }
+
+                public g() {
+                label b0:
+                    return;
+                }
+
+                public h() {
+                label b0:
+                    return;
+                }
+            }
+        ";
+        assert!(check(OLD_MODULE, new).is_empty());
+    }
+
+    #[test]
+    fn test_unpublished_module_is_compatible() {
+        let bundle = ModuleBundle::from(ModuleBytes::new(compile(OLD_MODULE)));
+        assert!(check_compatibility(&[], &bundle).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_changed_struct_layout() {
+        let new = "
+            module 0x1.M {
+                struct S has store { x: u64, y: u64 }
+
+                public f(x: u64): u64 {
+                label b0:
+                    return copy(x);
+                }
+
+                public g() {
+                label b0:
+                    return;
+                }
+            }
+        ";
+        let incompatibilities = check(OLD_MODULE, new);
+        assert_eq!(incompatibilities.len(), 1);
+        let incompatibility = &incompatibilities[0];
+        assert_eq!(incompatibility.module, "0x1::M");
+        assert!(!incompatibility.layout_compatible);
+        assert_eq!(incompatibility.changed_structs, vec!["S".to_string()]);
+        assert!(incompatibility.changed_functions.is_empty());
+    }
+
+    #[test]
+    fn test_removed_public_function() {
+        let new = "
+            module 0x1.M {
+                struct S has store { x: u64 }
+
+                public f(x: u64): u64 {
+                label b0:
+                    return copy(x);
+                }
+            }
+        ";
+        let incompatibilities = check(OLD_MODULE, new);
+        assert_eq!(incompatibilities.len(), 1);
+        let incompatibility = &incompatibilities[0];
+        assert!(!incompatibility.linking_compatible);
+        assert!(incompatibility.changed_structs.is_empty());
+        assert_eq!(incompatibility.changed_functions, vec!["g".to_string()]);
+        assert_eq!(
+            incompatibility.to_string(),
+            "0x1::M: function signature changed for [g]"
+        );
+    }
+
+    #[test]
+    fn test_ensure_compatible() {
+        let new = "
+            module 0x1.M {
+                struct S has store { x: u64 }
+            }
+        ";
+        let incompatibilities = check(OLD_MODULE, new);
+        assert_eq!(incompatibilities[0].changed_functions.len(), 2);
+
+        // Compatible packages are always published
+        assert!(ensure_compatible(AccountAddress::ONE, &[], false).is_ok());
+        // Incompatible packages are only published with `--force` or `--dry-run`
+        assert!(matches!(
+            ensure_compatible(AccountAddress::ONE, &incompatibilities, false),
+            Err(CliError::CommandArgumentError(_))
+        ));
+        assert!(ensure_compatible(AccountAddress::ONE, &incompatibilities, true).is_ok());
+    }
+}
@@ -2,6 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod aptos_debug_natives;
+mod compatibility;
+#[cfg(test)]
+mod tests;
 
 use crate::{
     common::{
@@ -14,27 +17,34 @@ use crate::{
     },
     CliCommand, CliResult,
 };
-use aptos_rest_client::aptos_api_types::MoveType;
+use aptos_rest_client::{aptos_api_types::MoveType, Client};
 use aptos_types::transaction::{ModuleBundle, ScriptFunction, TransactionPayload};
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
 use move_deps::{
+    move_binary_format::access::ModuleAccess,
     move_cli,
     move_cli::package::cli::UnitTestResult,
     move_command_line_common::env::get_bytecode_version_from_env,
+    move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule},
     move_core_types::{
         account_address::AccountAddress,
         identifier::Identifier,
         language_storage::{ModuleId, TypeTag},
     },
+    move_coverage::{
+        coverage_map::CoverageMap, source_coverage::SourceCoverageBuilder,
+        summary::summarize_inst_cov,
+    },
     move_package::{
         compilation::compiled_package::CompiledPackage,
         source_package::layout::SourcePackageLayout, BuildConfig,
     },
     move_unit_test::UnitTestingConfig,
 };
+use serde::Serialize;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     convert::TryFrom,
     fs::create_dir_all,
     io::Write,
@@ -42,12 +52,19 @@ use std::{
     str::FromStr,
 };
 
+pub use compatibility::ModuleIncompatibility;
+use compatibility::{check_compatibility, ensure_compatible};
+
+/// The file the Move unit test runner saves the coverage to, in the package directory
+const COVERAGE_MAP_FILE: &str = ".coverage_map.mvcov";
+
 /// CLI tool for performing Move tasks
 ///
 #[derive(Subcommand)]
 pub enum MoveTool {
     Compile(CompilePackage),
     Init(InitPackage),
+    Prove(ProvePackage),
     Publish(PublishPackage),
     Run(RunFunction),
     Test(TestPackage),
@@ -58,6 +75,7 @@ impl MoveTool {
         match self {
             MoveTool::Compile(tool) => tool.execute_serialized().await,
             MoveTool::Init(tool) => tool.execute_serialized_success().await,
+            MoveTool::Prove(tool) => tool.execute_serialized().await,
             MoveTool::Publish(tool) => tool.execute_serialized().await,
             MoveTool::Run(tool) => tool.execute_serialized().await,
             MoveTool::Test(tool) => tool.execute_serialized().await,
@@ -179,6 +197,10 @@ impl CliCommand<Vec<String>> for CompilePackage {
 }
 
 /// Run Move unit tests against a package path
+///
+/// With `--coverage`, the instruction coverage of each module of the package is printed after the
+/// tests, and the source lines of the modules given with `--coverage-source` are printed with the
+/// uncovered ones highlighted.
 #[derive(Parser)]
 pub struct TestPackage {
     #[clap(flatten)]
@@ -187,6 +209,14 @@ pub struct TestPackage {
     /// A filter string to determine which unit tests to run
     #[clap(long)]
     pub filter: Option<String>,
+
+    /// Collect the coverage of the unit tests, and print a summary per module
+    #[clap(long)]
+    pub coverage: bool,
+
+    /// Names of the modules to print a source line coverage report of
+    #[clap(long, requires = "coverage", multiple_values = true)]
+    pub coverage_source: Vec<String>,
 }
 
 #[async_trait]
@@ -204,16 +234,26 @@ impl CliCommand<&'static str> for TestPackage {
         };
         let result = move_cli::package::cli::run_move_unit_tests(
             self.move_options.package_dir.as_path(),
-            config,
+            config.clone(),
             UnitTestingConfig {
                 filter: self.filter,
                 ..UnitTestingConfig::default_with_bound(Some(100_000))
             },
             aptos_debug_natives::aptos_debug_natives(),
-            false,
+            self.coverage,
         )
         .map_err(|err| CliError::MoveTestError(err.to_string()))?;
 
+        // The reports are written to stderr, as stdout is for the result of the command
+        if self.coverage {
+            print_coverage(
+                config,
+                self.move_options.package_dir.as_path(),
+                &self.coverage_source,
+                &mut std::io::stderr(),
+            )?;
+        }
+
         // TODO: commit back up to the move repo
         match result {
             UnitTestResult::Success => Ok("Success"),
@@ -222,6 +262,146 @@ impl CliCommand<&'static str> for TestPackage {
     }
 }
 
+/// Prints the coverage collected by the unit tests of the package
+fn print_coverage<W: Write>(
+    config: BuildConfig,
+    package_dir: &Path,
+    source_modules: &[String],
+    out: &mut W,
+) -> CliTypedResult<()> {
+    let coverage_map =
+        CoverageMap::from_binary_file(package_dir.join(COVERAGE_MAP_FILE)).map_err(|err| {
+            CliError::UnableToReadFile(COVERAGE_MAP_FILE.to_string(), err.to_string())
+        })?;
+    // The coverage is of the test mode bytecode, so it has to be compiled the same way
+    let package = compile_move(config, package_dir)?;
+    let map_io_err = |err| CliError::IO("coverage report".to_string(), err);
+
+    let exec_map = coverage_map.to_unified_exec_map();
+    let (mut total, mut covered) = (0, 0);
+    for unit in package.root_compiled_units.iter() {
+        if let CompiledUnit::Module(NamedCompiledModule { module, .. }) = &unit.unit {
+            let (module_total, module_covered) = summarize_inst_cov(module, &exec_map)
+                .summarize_human(out, true)
+                .map_err(map_io_err)?;
+            total += module_total;
+            covered += module_covered;
+        }
+    }
+    if total > 0 {
+        writeln!(
+            out,
+            "% Move Coverage: {:.2}",
+            (covered as f64 / total as f64) * 100f64
+        )
+        .map_err(map_io_err)?;
+    }
+
+    for module_name in source_modules {
+        let unit = package
+            .get_module_by_name_from_root(module_name)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+        if let CompiledUnit::Module(NamedCompiledModule {
+            module, source_map, ..
+        }) = &unit.unit
+        {
+            SourceCoverageBuilder::new(module, &coverage_map, source_map)
+                .compute_source_coverage(&unit.source_path)
+                .output_source_coverage(out)
+                .map_err(map_io_err)?;
+        }
+    }
+    Ok(())
+}
+
+/// Proves the specifications of a Move package with the Move Prover
+///
+/// The prover and its solvers (Boogie and Z3) are found through the `BOOGIE_EXE` and `Z3_EXE`
+/// environment variables, as for the Move CLI.
+#[derive(Parser)]
+pub struct ProvePackage {
+    #[clap(flatten)]
+    move_options: MovePackageDir,
+
+    /// A filter string to determine which modules to verify
+    #[clap(long)]
+    pub filter: Option<String>,
+
+    /// Verify the test mode version of the package, including its test only code
+    #[clap(long)]
+    pub for_test: bool,
+
+    /// Options passed to the Move Prover, after a `--`
+    ///
+    /// Example: `aptos move prove -- --verbose=info`
+    #[clap(last = true)]
+    pub prover_options: Vec<String>,
+}
+
+#[async_trait]
+impl CliCommand<&'static str> for ProvePackage {
+    fn command_name(&self) -> &'static str {
+        "ProvePackage"
+    }
+
+    async fn execute(self) -> CliTypedResult<&'static str> {
+        let config = BuildConfig {
+            additional_named_addresses: self.move_options.named_addresses(),
+            test_mode: self.for_test,
+            install_dir: self.move_options.output_dir.clone(),
+            ..Default::default()
+        };
+        // The prover is blocking, and may run for a long time
+        let package_dir = self.move_options.package_dir.clone();
+        let filter = self.filter;
+        let for_test = self.for_test;
+        let prover_options = self.prover_options;
+        tokio::task::spawn_blocking(move || {
+            check_natives(&compile_move(config.clone(), package_dir.as_path())?)?;
+            move_cli::package::prover::run_move_prover(
+                config,
+                package_dir.as_path(),
+                &filter,
+                for_test,
+                &prover_options,
+            )
+            .map_err(|err| CliError::MoveProverError(err.to_string()))
+        })
+        .await
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))??;
+        Ok("Success")
+    }
+}
+
+/// Checks that the native functions of the package are the ones of [`aptos_debug_natives`], so
+/// the package is verified against the natives it executes with
+fn check_natives(package: &CompiledPackage) -> CliTypedResult<()> {
+    let natives: BTreeSet<_> = aptos_debug_natives::aptos_debug_natives()
+        .into_iter()
+        .map(|(address, module, function, _)| (address, module, function))
+        .collect();
+
+    let mut missing = Vec::new();
+    for module in package.root_modules_map().iter_modules() {
+        for function in module.function_defs().iter().filter(|def| def.is_native()) {
+            let name = module.identifier_at(module.function_handle_at(function.function).name);
+            let native = (*module.address(), module.name().to_owned(), name.to_owned());
+            if !natives.contains(&native) {
+                missing.push(format!("{}::{}", module.self_id(), name));
+            }
+        }
+    }
+
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::MoveProverError(format!(
+            "Native functions without an Aptos implementation: {}",
+            missing.join(", ")
+        )))
+    }
+}
+
 /// Compiles a Move package dir, and returns the compiled modules.
 fn compile_move(build_config: BuildConfig, package_dir: &Path) -> CliTypedResult<CompiledPackage> {
     // TODO: Add caching
@@ -230,7 +410,38 @@ fn compile_move(build_config: BuildConfig, package_dir: &Path) -> CliTypedResult
         .map_err(|err| CliError::MoveCompilationError(err.to_string()))
 }
 
+/// Compiles a Move package dir, and returns the payload publishing its modules.
+pub(crate) fn package_payload(move_options: &MovePackageDir) -> CliTypedResult<TransactionPayload> {
+    package_module_bundle(move_options).map(TransactionPayload::ModuleBundle)
+}
+
+/// Compiles the package into the bundle of its modules
+fn package_module_bundle(move_options: &MovePackageDir) -> CliTypedResult<ModuleBundle> {
+    let build_config = BuildConfig {
+        additional_named_addresses: move_options.named_addresses(),
+        generate_abis: false,
+        generate_docs: true,
+        install_dir: move_options.output_dir.clone(),
+        ..Default::default()
+    };
+    let package = compile_move(build_config, move_options.package_dir.as_path())?;
+    let compiled_units: Vec<Vec<u8>> = package
+        .root_compiled_units
+        .iter()
+        .map(|unit_with_source| {
+            unit_with_source
+                .unit
+                .serialize(get_bytecode_version_from_env())
+        })
+        .collect();
+    Ok(ModuleBundle::new(compiled_units))
+}
+
 /// Publishes the modules in a Move package
+///
+/// Before publishing, the modules are checked against the ones already published at the sender's
+/// address.  Incompatible struct layout or function signature changes are reported, and the
+/// package isn't published unless `--force` is given.
 #[derive(Parser)]
 pub struct PublishPackage {
     #[clap(flatten)]
@@ -241,35 +452,95 @@ pub struct PublishPackage {
     write_options: WriteTransactionOptions,
     #[clap(flatten)]
     profile_options: ProfileOptions,
+    /// Publish the package even if it is incompatible with the published modules
+    #[clap(long)]
+    force: bool,
+    /// Only compile the package and check its compatibility, without publishing it
+    #[clap(long)]
+    dry_run: bool,
 }
 
 #[async_trait]
-impl CliCommand<TransactionSummary> for PublishPackage {
+impl CliCommand<PublishSummary> for PublishPackage {
     fn command_name(&self) -> &'static str {
         "PublishPackage"
     }
 
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
-        let build_config = BuildConfig {
-            additional_named_addresses: self.move_options.named_addresses(),
-            generate_abis: false,
-            generate_docs: true,
-            install_dir: self.move_options.output_dir.clone(),
-            ..Default::default()
-        };
-        let package = compile_move(build_config, self.move_options.package_dir.as_path())?;
-        let compiled_units: Vec<Vec<u8>> = package
-            .root_compiled_units
-            .iter()
-            .map(|unit_with_source| {
-                unit_with_source
-                    .unit
-                    .serialize(get_bytecode_version_from_env())
-            })
-            .collect();
-        let compiled_payload = TransactionPayload::ModuleBundle(ModuleBundle::new(compiled_units));
+    async fn execute(self) -> CliTypedResult<PublishSummary> {
+        let profile = &self.profile_options.profile;
+        let bundle = package_module_bundle(&self.move_options)?;
+
+        let sender_key = self
+            .write_options
+            .private_key_options
+            .extract_private_key(self.encoding_options.encoding, profile)?;
+        let sender_address = self.write_options.sender_address(profile, &sender_key)?;
+        let url = self.write_options.rest_options.url(profile)?;
+
+        let published_modules = Client::new(url.clone())
+            .get_account_modules(sender_address)
+            .await
+            .map_err(|err| CliError::ApiError(err.to_string()))?
+            .into_inner();
+        let incompatibilities = check_compatibility(&published_modules, &bundle)?;
+        ensure_compatible(
+            sender_address,
+            &incompatibilities,
+            self.force || self.dry_run,
+        )?;
+        if self.dry_run {
+            return Ok(PublishSummary {
+                incompatibilities,
+                transaction: None,
+            });
+        }
+
+        let transaction = submit_transaction(
+            url,
+            self.write_options.chain_id(profile).await?,
+            sender_address,
+            sender_key,
+            TransactionPayload::ModuleBundle(bundle),
+            self.write_options.max_gas,
+        )
+        .await?;
+        Ok(PublishSummary {
+            incompatibilities,
+            transaction: Some(TransactionSummary::from(transaction)),
+        })
+    }
+}
+
+/// Summary of a package publication
+#[derive(Clone, Debug, Serialize)]
+pub struct PublishSummary {
+    /// Incompatible changes with the published modules, only non-empty with `--force` or `--dry-run`
+    pub incompatibilities: Vec<ModuleIncompatibility>,
+    /// The publishing transaction, absent with `--dry-run`
+    pub transaction: Option<TransactionSummary>,
+}
+
+/// Run a Move function
+#[derive(Parser)]
+pub struct RunFunction {
+    #[clap(flatten)]
+    encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    write_options: WriteTransactionOptions,
+    #[clap(flatten)]
+    profile_options: ProfileOptions,
+    #[clap(flatten)]
+    function_args: ScriptFunctionArgs,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for RunFunction {
+    fn command_name(&self) -> &'static str {
+        "RunFunction"
+    }
 
-        // Now that it's compiled, lets send it
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let payload = self.function_args.payload()?;
         let sender_key = self.write_options.private_key_options.extract_private_key(
             self.encoding_options.encoding,
             &self.profile_options.profile,
@@ -282,8 +553,10 @@ impl CliCommand<TransactionSummary> for PublishPackage {
             self.write_options
                 .chain_id(&self.profile_options.profile)
                 .await?,
+            self.write_options
+                .sender_address(&self.profile_options.profile, &sender_key)?,
             sender_key,
-            compiled_payload,
+            payload,
             self.write_options.max_gas,
         )
         .await
@@ -291,15 +564,9 @@ impl CliCommand<TransactionSummary> for PublishPackage {
     }
 }
 
-/// Run a Move function
+/// Arguments of a call to a Move script function
 #[derive(Parser)]
-pub struct RunFunction {
-    #[clap(flatten)]
-    encoding_options: EncodingOptions,
-    #[clap(flatten)]
-    write_options: WriteTransactionOptions,
-    #[clap(flatten)]
-    profile_options: ProfileOptions,
+pub struct ScriptFunctionArgs {
     /// Function name as `<ADDRESS>::<MODULE_ID>::<FUNCTION_NAME>`
     ///
     /// Example: `0x842ed41fad9640a2ad08fdd7d3e4f7f505319aac7d67e1c0dd6a7cce8732c7e3::Message::set_message`
@@ -317,13 +584,9 @@ pub struct RunFunction {
     type_args: Vec<MoveType>,
 }
 
-#[async_trait]
-impl CliCommand<TransactionSummary> for RunFunction {
-    fn command_name(&self) -> &'static str {
-        "RunFunction"
-    }
-
-    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+impl ScriptFunctionArgs {
+    /// Builds the payload calling the function
+    pub fn payload(&self) -> CliTypedResult<TransactionPayload> {
         let args: Vec<Vec<u8>> = self
             .args
             .iter()
@@ -338,29 +601,12 @@ impl CliCommand<TransactionSummary> for RunFunction {
             type_args.push(type_tag)
         }
 
-        let script_function = ScriptFunction::new(
+        Ok(TransactionPayload::ScriptFunction(ScriptFunction::new(
             self.function_id.module_id.clone(),
             self.function_id.function_id.clone(),
             type_args,
             args,
-        );
-
-        submit_transaction(
-            self.write_options
-                .rest_options
-                .url(&self.profile_options.profile)?,
-            self.write_options
-                .chain_id(&self.profile_options.profile)
-                .await?,
-            self.write_options.private_key_options.extract_private_key(
-                self.encoding_options.encoding,
-                &self.profile_options.profile,
-            )?,
-            TransactionPayload::ScriptFunction(script_function),
-            self.write_options.max_gas,
-        )
-        .await
-        .map(TransactionSummary::from)
+        )))
     }
 }
 
@@ -0,0 +1,92 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::types::CliError,
+    move_tool::{
+        aptos_debug_natives::aptos_debug_natives, check_natives, compile_move, print_coverage,
+    },
+};
+use aptos_temppath::TempPath;
+use move_deps::{
+    move_cli::package::cli::{run_move_unit_tests, UnitTestResult},
+    move_package::BuildConfig,
+    move_unit_test::UnitTestingConfig,
+};
+use std::path::Path;
+
+const MANIFEST: &str = "
+[package]
+name = \"Coverage\"
+version = \"0.0.0\"
+";
+
+const MODULE: &str = "
+module 0xCAFE::M {
+    public fun max(a: u64, b: u64): u64 {
+        if (a > b) a else b
+    }
+
+    #[test]
+    fun test_max() {
+        assert!(max(2, 1) == 2, 0);
+    }
+}
+";
+
+fn create_package(dir: &Path, module: &str) {
+    std::fs::create_dir_all(dir.join("sources")).unwrap();
+    std::fs::write(dir.join("Move.toml"), MANIFEST).unwrap();
+    std::fs::write(dir.join("sources").join("M.move"), module).unwrap();
+}
+
+fn test_config() -> BuildConfig {
+    BuildConfig {
+        test_mode: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_print_coverage() {
+    let dir = TempPath::new();
+    create_package(dir.path(), MODULE);
+
+    let result = run_move_unit_tests(
+        dir.path(),
+        test_config(),
+        UnitTestingConfig::default_with_bound(Some(100_000)),
+        aptos_debug_natives(),
+        true,
+    )
+    .unwrap();
+    assert!(matches!(result, UnitTestResult::Success));
+
+    let mut out = Vec::new();
+    print_coverage(test_config(), dir.path(), &["M".to_string()], &mut out).unwrap();
+    let report = String::from_utf8(out).unwrap();
+    // Only the `a > b` branch of `max` is tested
+    assert!(report.contains("M"));
+    assert!(report.contains("% Move Coverage:"));
+    assert!(!report.contains("% Move Coverage: 0.00"));
+    assert!(!report.contains("% Move Coverage: 100.00"));
+}
+
+#[test]
+fn test_check_natives() {
+    let dir = TempPath::new();
+    create_package(dir.path(), MODULE);
+    let package = compile_move(test_config(), dir.path()).unwrap();
+    check_natives(&package).unwrap();
+
+    let dir = TempPath::new();
+    create_package(
+        dir.path(),
+        "module 0xCAFE::M { native public fun unknown(): u64; }",
+    );
+    let package = compile_move(test_config(), dir.path()).unwrap();
+    assert!(matches!(
+        check_natives(&package),
+        Err(CliError::MoveProverError(_))
+    ));
+}
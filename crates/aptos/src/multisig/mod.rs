@@ -0,0 +1,249 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::types::{
+        CliCommand, CliError, CliResult, CliTypedResult, EncodingOptions, EncodingType,
+        PrivateKeyInputOptions, ProfileOptions, SaveFile,
+    },
+    transaction::{read_bcs_file, SignedTransactionSummary},
+};
+use aptos_crypto::{ed25519::Ed25519PublicKey, multi_ed25519::MultiEd25519PublicKey};
+use aptos_sdk::types::{MultiSigAccount, PartialSignature};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{authenticator::AuthenticationKey, RawTransaction},
+};
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Tool for K-of-N multisig accounts
+///
+/// Transactions of a multisig account are built with `aptos transaction build-*`, signed by
+/// each key holder with `sign`, combined with `aggregate` and then submitted with
+/// `aptos transaction submit`.
+#[derive(Subcommand)]
+pub enum MultisigTool {
+    Aggregate(AggregateSignatures),
+    Create(CreateMultisig),
+    Sign(PartialSignTransaction),
+}
+
+impl MultisigTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            MultisigTool::Aggregate(tool) => tool.execute_serialized().await,
+            MultisigTool::Create(tool) => tool.execute_serialized().await,
+            MultisigTool::Sign(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// Command to create a K-of-N multisig public key, and derive its account address
+///
+/// The multisig public key is saved to the `output_file` for use in the other multisig commands.
+/// The order of the public keys matters, as it changes the derived address.
+#[derive(Debug, Parser)]
+pub struct CreateMultisig {
+    /// Public key files of the N keys of the account
+    #[clap(
+        long,
+        group = "public_keys_input",
+        multiple_values = true,
+        parse(from_os_str)
+    )]
+    pub(crate) public_key_files: Vec<PathBuf>,
+    /// Public keys of the N keys of the account, encoded in a type as shown in `encoding`
+    #[clap(long, group = "public_keys_input", multiple_values = true)]
+    pub(crate) public_keys: Vec<String>,
+    /// Number K of signatures required to sign transactions of the account
+    #[clap(long)]
+    pub(crate) threshold: u8,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<MultisigSummary> for CreateMultisig {
+    fn command_name(&self) -> &'static str {
+        "CreateMultisig"
+    }
+
+    async fn execute(self) -> CliTypedResult<MultisigSummary> {
+        self.save_file.check_file()?;
+        let encoding = self.encoding_options.encoding;
+        let public_keys = if !self.public_key_files.is_empty() {
+            self.public_key_files
+                .iter()
+                .map(|file| encoding.load_key("--public-key-files", file.as_path()))
+                .collect::<CliTypedResult<Vec<Ed25519PublicKey>>>()?
+        } else if !self.public_keys.is_empty() {
+            self.public_keys
+                .iter()
+                .map(|key| encoding.decode_key("--public-keys", key.as_bytes().to_vec()))
+                .collect::<CliTypedResult<Vec<Ed25519PublicKey>>>()?
+        } else {
+            return Err(CliError::CommandArgumentError(
+                "One of ['--public-keys', '--public-key-files'] must be used".to_string(),
+            ));
+        };
+
+        let public_key = MultiEd25519PublicKey::new(public_keys, self.threshold).map_err(|_| {
+            CliError::CommandArgumentError(
+                "Threshold must be between 1 and the number of public keys, which is at most 32"
+                    .to_string(),
+            )
+        })?;
+        self.save_file.save_to_file(
+            "Multisig public key",
+            &encoding.encode_key("Multisig public key", &public_key)?,
+        )?;
+
+        Ok(MultisigSummary::from(&public_key))
+    }
+}
+
+/// Command to sign an unsigned transaction file with one of the keys of a multisig account
+///
+/// The partial signature is saved BCS encoded to the `output_file`, to be aggregated with the
+/// others.  The signing doesn't need network access, so it can be done on an offline machine.
+#[derive(Debug, Parser)]
+pub struct PartialSignTransaction {
+    /// Path to the multisig public key, as created with `aptos multisig create`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) multisig_public_key_file: PathBuf,
+    /// Path to the BCS encoded unsigned transaction
+    #[clap(long, parse(from_os_str))]
+    pub(crate) transaction_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<PartialSignature> for PartialSignTransaction {
+    fn command_name(&self) -> &'static str {
+        "PartialSignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<PartialSignature> {
+        self.save_file.check_file()?;
+        let account = load_multisig_account(
+            self.encoding_options.encoding,
+            &self.multisig_public_key_file,
+        )?;
+        let raw_txn: RawTransaction = read_bcs_file("RawTransaction", &self.transaction_file)?;
+        // Otherwise the key holder could be tricked into co-signing for another account
+        if raw_txn.sender() != account.address() {
+            return Err(CliError::CommandArgumentError(format!(
+                "Transaction sender {} isn't the multisig account {}",
+                raw_txn.sender(),
+                account.address()
+            )));
+        }
+        let private_key = self.private_key_options.extract_private_key(
+            self.encoding_options.encoding,
+            &self.profile_options.profile,
+        )?;
+
+        let signature = account
+            .partial_sign(&raw_txn, &private_key)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+        let bytes =
+            bcs::to_bytes(&signature).map_err(|err| CliError::BCS("PartialSignature", err))?;
+        self.save_file.save_to_file("Partial signature", &bytes)?;
+
+        Ok(signature)
+    }
+}
+
+/// Command to aggregate partial signatures into a signed transaction file
+///
+/// The signed transaction is saved BCS encoded to the `output_file`, and can be submitted with
+/// `aptos transaction submit`.
+#[derive(Debug, Parser)]
+pub struct AggregateSignatures {
+    /// Path to the multisig public key, as created with `aptos multisig create`
+    #[clap(long, parse(from_os_str))]
+    pub(crate) multisig_public_key_file: PathBuf,
+    /// Path to the BCS encoded unsigned transaction
+    #[clap(long, parse(from_os_str))]
+    pub(crate) transaction_file: PathBuf,
+    /// Paths to the partial signatures, as created with `aptos multisig sign`
+    #[clap(long, required = true, multiple_values = true, parse(from_os_str))]
+    pub(crate) signature_files: Vec<PathBuf>,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<SignedTransactionSummary> for AggregateSignatures {
+    fn command_name(&self) -> &'static str {
+        "AggregateSignatures"
+    }
+
+    async fn execute(self) -> CliTypedResult<SignedTransactionSummary> {
+        self.save_file.check_file()?;
+        let account = load_multisig_account(
+            self.encoding_options.encoding,
+            &self.multisig_public_key_file,
+        )?;
+        let raw_txn: RawTransaction = read_bcs_file("RawTransaction", &self.transaction_file)?;
+        let signatures = self
+            .signature_files
+            .iter()
+            .map(|file| read_bcs_file("PartialSignature", file))
+            .collect::<CliTypedResult<Vec<PartialSignature>>>()?;
+
+        let signed_txn = account
+            .aggregate_signatures(raw_txn, signatures)
+            .map_err(|err| CliError::CommandArgumentError(err.to_string()))?;
+        let bytes =
+            bcs::to_bytes(&signed_txn).map_err(|err| CliError::BCS("SignedTransaction", err))?;
+        self.save_file.save_to_file("Signed transaction", &bytes)?;
+
+        Ok(SignedTransactionSummary::from(signed_txn))
+    }
+}
+
+fn load_multisig_account(
+    encoding: EncodingType,
+    multisig_public_key_file: &Path,
+) -> CliTypedResult<MultiSigAccount> {
+    let public_key: MultiEd25519PublicKey =
+        encoding.load_key("--multisig-public-key-file", multisig_public_key_file)?;
+    // Only the keys are needed for signing, the transaction has the sender and sequence number
+    Ok(MultiSigAccount::from_public_key(public_key, 0))
+}
+
+/// Summary of a multisig account
+#[derive(Clone, Debug, Serialize)]
+pub struct MultisigSummary {
+    pub account_address: AccountAddress,
+    pub authentication_key: AuthenticationKey,
+    pub threshold: u8,
+    pub public_keys: Vec<Ed25519PublicKey>,
+}
+
+impl From<&MultiEd25519PublicKey> for MultisigSummary {
+    fn from(public_key: &MultiEd25519PublicKey) -> Self {
+        let authentication_key = AuthenticationKey::multi_ed25519(public_key);
+        MultisigSummary {
+            account_address: authentication_key.derived_address(),
+            authentication_key,
+            threshold: *public_key.threshold(),
+            public_keys: public_key.public_keys().clone(),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#[cfg(test)]
+mod tests;
+
+use crate::common::{
+    types::{CliCommand, CliError, CliResult, CliTypedResult, EncodingType, PromptOptions},
+    utils::prompt_yes_with_override,
+};
+use aptos_config::config::{NodeConfig, DEFAULT_PORT};
+use aptos_crypto::ed25519::Ed25519PrivateKey;
+use aptos_faucet::Service;
+use aptos_sdk::types::LocalAccount;
+use aptos_types::{account_config::aptos_root_address, chain_id::ChainId};
+use async_trait::async_trait;
+use clap::Parser;
+use hex::FromHex;
+use rand::{rngs::StdRng, SeedableRng};
+use reqwest::Url;
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+const DEFAULT_FAUCET_PORT: u16 = 8081;
+const NODE_STARTUP_ATTEMPTS: usize = 120;
+
+/// Tool for operations related to nodes
+///
+#[derive(Parser)]
+pub enum NodeTool {
+    RunLocalTestnet(RunLocalTestnet),
+}
+
+impl NodeTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            NodeTool::RunLocalTestnet(tool) => tool.execute_serialized_success().await,
+        }
+    }
+}
+
+/// Run a local testnet
+///
+/// The testnet is a single validator node running its own genesis, with the REST API and a
+/// faucet for funding accounts.  The chain is kept in the test directory, and is resumed by the
+/// next run unless `--force-restart` is given.
+#[derive(Parser)]
+pub struct RunLocalTestnet {
+    /// Directory of the configs, keys and data of the testnet
+    #[clap(long, parse(from_os_str), default_value = ".aptos/testnet")]
+    test_dir: PathBuf,
+    /// Port of the REST API of the node
+    #[clap(long, default_value_t = DEFAULT_PORT)]
+    api_port: u16,
+    /// Port of the faucet
+    #[clap(long, default_value_t = DEFAULT_FAUCET_PORT)]
+    faucet_port: u16,
+    /// Run the node without a faucet
+    #[clap(long)]
+    no_faucet: bool,
+    /// Seed of the keys generated at genesis, as 32 hex encoded bytes
+    #[clap(long, parse(try_from_str = FromHex::from_hex))]
+    seed: Option<[u8; 32]>,
+    /// Delete the existing chain in the test directory and start a new one from genesis
+    #[clap(long)]
+    force_restart: bool,
+    #[clap(flatten)]
+    prompt_options: PromptOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for RunLocalTestnet {
+    fn command_name(&self) -> &'static str {
+        "RunLocalTestnet"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        if self.force_restart && self.test_dir.exists() {
+            prompt_yes_with_override(
+                &format!(
+                    "Are you sure you want to delete the existing chain in {}?",
+                    self.test_dir.display()
+                ),
+                self.prompt_options,
+            )?;
+            std::fs::remove_dir_all(&self.test_dir)
+                .map_err(|err| CliError::IO(self.test_dir.display().to_string(), err))?;
+        }
+
+        let rng = self
+            .seed
+            .map(StdRng::from_seed)
+            .unwrap_or_else(StdRng::from_entropy);
+        let test_dir = self.test_dir.clone();
+        let api_port = self.api_port;
+        let config =
+            tokio::task::spawn_blocking(move || load_or_create_config(&test_dir, api_port, rng))
+                .await
+                .map_err(|err| {
+                    CliError::UnexpectedError(format!("Failed to create the testnet: {}", err))
+                })?;
+
+        // The node runtimes are owned by their handle, which has to outlive the command
+        let node_config = config.clone();
+        let node = thread::spawn(move || {
+            let _node_handle = aptos_node::setup_environment(&node_config, None);
+            loop {
+                thread::park();
+            }
+        });
+
+        let rest_url = Url::parse(&format!(
+            "http://{}:{}",
+            Ipv4Addr::LOCALHOST,
+            config.api.address.port()
+        ))
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        wait_for_node(&rest_url).await?;
+
+        eprintln!("Local testnet is running, press ctrl-c to exit");
+        eprintln!("\tTest dir: {}", self.test_dir.display());
+        eprintln!("\tREST API endpoint: {}", rest_url);
+        eprintln!("\tChainId: {}", ChainId::test());
+
+        if self.no_faucet {
+            return wait_for_exit(node).await;
+        }
+
+        let mint_key_path = self.test_dir.join(aptos_node::TEST_ROOT_KEY_FILE);
+        let mint_key =
+            EncodingType::BCS.load_key::<Ed25519PrivateKey>("mint key", &mint_key_path)?;
+        let service = Arc::new(Service::new(
+            rest_url.to_string(),
+            ChainId::test(),
+            LocalAccount::new(aptos_root_address(), mint_key, 0),
+            None,
+        ));
+        let faucet_address = SocketAddr::from((Ipv4Addr::LOCALHOST, self.faucet_port));
+        eprintln!("\tFaucet endpoint: http://{}", faucet_address);
+        eprintln!("\tMint key path: {}", mint_key_path.display());
+
+        tokio::select! {
+            _ = warp::serve(aptos_faucet::routes(service)).run(faucet_address) => {
+                Err(CliError::UnexpectedError("Faucet stopped".to_string()))
+            }
+            result = wait_for_exit(node) => result,
+        }
+    }
+}
+
+/// Loads the config of the chain in the test directory, creating a new chain from genesis if
+/// there is none.  The API port is applied to resumed chains as well.
+fn load_or_create_config(test_dir: &Path, api_port: u16, rng: StdRng) -> NodeConfig {
+    let mut template = NodeConfig::default_for_validator();
+    template.api.address.set_port(api_port);
+
+    let mut config = aptos_node::create_single_node_test_config(
+        test_dir,
+        template,
+        false,
+        false,
+        cached_framework_packages::module_blobs().to_vec(),
+        rng,
+    );
+    config.api.address.set_port(api_port);
+    config
+}
+
+/// Waits for the REST API of the node to serve requests
+async fn wait_for_node(rest_url: &Url) -> CliTypedResult<()> {
+    let client = aptos_rest_client::Client::new(rest_url.clone());
+    for _ in 0..NODE_STARTUP_ATTEMPTS {
+        if client.get_ledger_information().await.is_ok() {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    Err(CliError::UnexpectedError(format!(
+        "Node didn't start serving the REST API at {}",
+        rest_url
+    )))
+}
+
+/// The node only stops by panicking, as it otherwise runs until the process is killed
+async fn wait_for_exit(node: thread::JoinHandle<()>) -> CliTypedResult<()> {
+    let _ = tokio::task::spawn_blocking(move || node.join()).await;
+    Err(CliError::UnexpectedError(
+        "Node stopped unexpectedly".to_string(),
+    ))
+}
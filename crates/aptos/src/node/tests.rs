@@ -0,0 +1,23 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::node::load_or_create_config;
+use aptos_temppath::TempPath;
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn test_load_or_create_config() {
+    let dir = TempPath::new();
+    dir.create_as_dir().unwrap();
+
+    let config = load_or_create_config(dir.path(), 9000, StdRng::from_seed([0; 32]));
+    assert_eq!(config.api.address.port(), 9000);
+    assert!(dir.path().join(aptos_node::TEST_ROOT_KEY_FILE).exists());
+
+    // Resuming the chain keeps its genesis, but still uses the requested API port
+    let node_config_path = dir.path().join("0").join("node.yaml");
+    let genesis_config = std::fs::read(&node_config_path).unwrap();
+    let config = load_or_create_config(dir.path(), 9001, StdRng::from_seed([1; 32]));
+    assert_eq!(config.api.address.port(), 9001);
+    assert_eq!(std::fs::read(&node_config_path).unwrap(), genesis_config);
+}
@@ -6,23 +6,36 @@ use crate::{
         create::{CreateAccount, DEFAULT_FUNDED_COINS},
         fund::FundAccount,
         list::{ListAccount, ListQuery},
+        rotate_key::{RotateKey, RotateSummary},
         transfer::{TransferCoins, TransferSummary},
     },
     common::{
         init::InitTool,
         types::{
-            CliConfig, CliTypedResult, EncodingOptions, PrivateKeyInputOptions, ProfileOptions,
-            PromptOptions, RestOptions, RngArgs, WriteTransactionOptions,
+            CliConfig, CliTypedResult, EncodingOptions, EncodingType, FaucetOptions,
+            PrivateKeyInputOptions, ProfileOptions, PromptOptions, RawTransactionOptions,
+            RestOptions, RngArgs, SaveFile, TransactionSummary, WriteTransactionOptions,
+            DEFAULT_EXPIRATION_SECS, DEFAULT_MAX_GAS,
         },
+        utils::DEFAULT_GAS_UNIT_PRICE,
+    },
+    event::{EventList, ListEvents},
+    multisig::{AggregateSignatures, CreateMultisig, MultisigSummary, PartialSignTransaction},
+    transaction::{
+        BuildTransfer, ListTransactions, RawTransactionSummary, ShowTransaction, SignTransaction,
+        SignedTransactionSummary, SubmitTransaction, TransactionId, TransactionList,
+        TransactionView,
     },
     CliCommand,
 };
 use aptos_crypto::ed25519::Ed25519PrivateKey;
 use aptos_keygen::KeyGen;
 use aptos_sdk::move_types::account_address::AccountAddress;
+use aptos_sdk::types::PartialSignature;
+use move_deps::move_core_types::parser::parse_struct_tag;
 use reqwest::Url;
 use serde_json::Value;
-use std::{str::FromStr, time::Duration};
+use std::{path::Path, str::FromStr, time::Duration};
 use tokio::time::{sleep, Instant};
 
 /// A framework for testing the CLI
@@ -63,7 +76,7 @@ impl CliTestFramework {
             write_options: WriteTransactionOptions {
                 private_key_options: PrivateKeyInputOptions::from_private_key(mint_key)?,
                 rest_options: RestOptions::new(Some(self.endpoint.clone())),
-                max_gas: 1000,
+                max_gas: Some(1000),
             },
             profile_options: profile(index),
             account: Self::account_id(index),
@@ -100,6 +113,18 @@ impl CliTestFramework {
         .await
     }
 
+    /// Creates and funds an account not backed by a profile
+    pub async fn fund_address(&self, address: AccountAddress) -> CliTypedResult<String> {
+        FundAccount {
+            profile_options: Default::default(),
+            account: address,
+            faucet_options: FaucetOptions::new(Some(self.faucet_endpoint.clone())),
+            num_coins: DEFAULT_FUNDED_COINS,
+        }
+        .execute()
+        .await
+    }
+
     pub async fn list_account(&self, index: usize, query: ListQuery) -> CliTypedResult<Vec<Value>> {
         ListAccount {
             rest_options: Default::default(),
@@ -130,6 +155,210 @@ impl CliTestFramework {
         .await
     }
 
+    /// Builds an unsigned coin transfer to the `output_file`, without signing it
+    pub async fn build_transfer(
+        &self,
+        sender: AccountAddress,
+        receiver_index: usize,
+        amount: u64,
+        output_file: &Path,
+    ) -> CliTypedResult<RawTransactionSummary> {
+        BuildTransfer {
+            raw_txn_options: RawTransactionOptions {
+                sender: Some(sender),
+                sequence_number: None,
+                chain_id: None,
+                max_gas: DEFAULT_MAX_GAS,
+                gas_unit_price: DEFAULT_GAS_UNIT_PRICE,
+                expiration_secs: DEFAULT_EXPIRATION_SECS,
+                rest_options: RestOptions::new(Some(self.endpoint.clone())),
+            },
+            profile_options: Default::default(),
+            save_file: save_file(output_file),
+            account: Self::account_id(receiver_index),
+            amount,
+        }
+        .execute()
+        .await
+    }
+
+    /// Signs the unsigned transaction in `transaction_file` with the key of the profile
+    pub async fn sign_transaction(
+        &self,
+        index: usize,
+        transaction_file: &Path,
+        output_file: &Path,
+    ) -> CliTypedResult<SignedTransactionSummary> {
+        SignTransaction {
+            transaction_file: transaction_file.to_path_buf(),
+            private_key_options: Default::default(),
+            encoding_options: Default::default(),
+            profile_options: profile(index),
+            save_file: save_file(output_file),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn submit_transaction(
+        &self,
+        transaction_file: &Path,
+    ) -> CliTypedResult<TransactionSummary> {
+        SubmitTransaction {
+            transaction_file: transaction_file.to_path_buf(),
+            rest_options: RestOptions::new(Some(self.endpoint.clone())),
+            profile_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn show_transaction(&self, version: u64) -> CliTypedResult<TransactionView> {
+        ShowTransaction {
+            transaction: TransactionId::Version(version),
+            rest_options: RestOptions::new(Some(self.endpoint.clone())),
+            profile_options: Default::default(),
+            output_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn list_transactions(
+        &self,
+        index: usize,
+        limit: Option<usize>,
+        reverse: bool,
+    ) -> CliTypedResult<TransactionList> {
+        ListTransactions {
+            account: Some(Self::account_id(index)),
+            limit,
+            reverse,
+            page_size: None,
+            rest_options: RestOptions::new(Some(self.endpoint.clone())),
+            profile_options: Default::default(),
+            output_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    /// Lists the events of the `field_name` handle of the coin store of the account
+    pub async fn list_coin_events(
+        &self,
+        index: usize,
+        field_name: &str,
+        limit: Option<usize>,
+    ) -> CliTypedResult<EventList> {
+        ListEvents {
+            key: None,
+            handle: Some(
+                parse_struct_tag("0x1::Coin::CoinStore<0x1::TestCoin::TestCoin>").unwrap(),
+            ),
+            account: Some(Self::account_id(index)),
+            field_name: Some(field_name.to_string()),
+            limit,
+            reverse: false,
+            page_size: None,
+            rest_options: RestOptions::new(Some(self.endpoint.clone())),
+            profile_options: Default::default(),
+            output_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
+    /// Creates a multisig public key from the keys of the profiles, in order
+    pub async fn create_multisig(
+        &self,
+        indices: &[usize],
+        threshold: u8,
+        output_file: &Path,
+    ) -> CliTypedResult<MultisigSummary> {
+        let public_keys = indices
+            .iter()
+            .map(|index| {
+                let public_key = CliConfig::load_profile(&index.to_string())?
+                    .and_then(|profile| profile.public_key)
+                    .expect("Expected to have public key");
+                Ok(String::from_utf8(
+                    EncodingType::Hex.encode_key("public key", &public_key)?,
+                )?)
+            })
+            .collect::<CliTypedResult<Vec<String>>>()?;
+
+        CreateMultisig {
+            public_key_files: Vec::new(),
+            public_keys,
+            threshold,
+            encoding_options: Default::default(),
+            save_file: save_file(output_file),
+        }
+        .execute()
+        .await
+    }
+
+    /// Signs the unsigned transaction with the key of the profile, as one of the multisig keys
+    pub async fn partial_sign_transaction(
+        &self,
+        index: usize,
+        multisig_public_key_file: &Path,
+        transaction_file: &Path,
+        output_file: &Path,
+    ) -> CliTypedResult<PartialSignature> {
+        PartialSignTransaction {
+            multisig_public_key_file: multisig_public_key_file.to_path_buf(),
+            transaction_file: transaction_file.to_path_buf(),
+            private_key_options: Default::default(),
+            encoding_options: Default::default(),
+            profile_options: profile(index),
+            save_file: save_file(output_file),
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn aggregate_signatures(
+        &self,
+        multisig_public_key_file: &Path,
+        transaction_file: &Path,
+        signature_files: &[&Path],
+        output_file: &Path,
+    ) -> CliTypedResult<SignedTransactionSummary> {
+        AggregateSignatures {
+            multisig_public_key_file: multisig_public_key_file.to_path_buf(),
+            transaction_file: transaction_file.to_path_buf(),
+            signature_files: signature_files
+                .iter()
+                .map(|file| file.to_path_buf())
+                .collect(),
+            encoding_options: Default::default(),
+            save_file: save_file(output_file),
+        }
+        .execute()
+        .await
+    }
+
+    /// Rotates the key of the account of the profile to `new_private_key`
+    pub async fn rotate_key(
+        &self,
+        index: usize,
+        new_private_key: &Ed25519PrivateKey,
+    ) -> CliTypedResult<RotateSummary> {
+        RotateKey {
+            encoding_options: Default::default(),
+            write_options: Default::default(),
+            profile_options: profile(index),
+            account: None,
+            new_private_key_file: None,
+            new_private_key: Some(String::from_utf8(
+                EncodingType::Hex.encode_key("new private key", new_private_key)?,
+            )?),
+        }
+        .execute()
+        .await
+    }
+
     pub async fn init(&self, index: usize, private_key: &Ed25519PrivateKey) -> CliTypedResult<()> {
         InitTool {
             rest_url: Some(self.endpoint.clone()),
@@ -220,3 +449,10 @@ fn private_key_options(private_key: &Ed25519PrivateKey) -> PrivateKeyInputOption
     PrivateKeyInputOptions::from_private_key(private_key)
         .expect("Must serialize private key to hex")
 }
+
+fn save_file(output_file: &Path) -> SaveFile {
+    SaveFile {
+        output_file: output_file.to_path_buf(),
+        prompt_options: PromptOptions::yes(),
+    }
+}
@@ -0,0 +1,524 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{
+            load_account_arg, CliCommand, CliConfig, CliError, CliResult, CliTypedResult,
+            EncodingOptions, MovePackageDir, OutputOptions, PrivateKeyInputOptions, ProfileOptions,
+            RawTransactionOptions, RestOptions, SaveFile, TransactionSummary,
+        },
+        utils::read_from_file,
+    },
+    move_tool::{package_payload, ScriptFunctionArgs},
+};
+use aptos_crypto::{HashValue, PrivateKey};
+use aptos_rest_client::{
+    aptos_api_types::{TransactionPayload as ApiTransactionPayload, UserTransactionRequest},
+    Client, Transaction,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    transaction::{RawTransaction, SignedTransaction, TransactionPayload},
+};
+use async_trait::async_trait;
+use cached_framework_packages::aptos_stdlib;
+use clap::{Parser, Subcommand};
+use futures::{StreamExt, TryStreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Tool for inspecting transactions, and for building, signing and submitting transactions in
+/// separate steps
+///
+/// Transactions are built into BCS encoded `RawTransaction` files, which can be signed
+/// without network access into BCS encoded `SignedTransaction` files to be submitted.
+#[derive(Subcommand)]
+pub enum TransactionTool {
+    BuildPublish(BuildPublish),
+    BuildRunFunction(BuildRunFunction),
+    BuildTransfer(BuildTransfer),
+    List(ListTransactions),
+    Show(ShowTransaction),
+    Sign(SignTransaction),
+    Submit(SubmitTransaction),
+}
+
+impl TransactionTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            TransactionTool::BuildPublish(tool) => tool.execute_serialized().await,
+            TransactionTool::BuildRunFunction(tool) => tool.execute_serialized().await,
+            TransactionTool::BuildTransfer(tool) => tool.execute_serialized().await,
+            TransactionTool::List(tool) => {
+                let format = tool.output_options.output;
+                tool.execute_formatted(format).await
+            }
+            TransactionTool::Show(tool) => {
+                let format = tool.output_options.output;
+                tool.execute_formatted(format).await
+            }
+            TransactionTool::Sign(tool) => tool.execute_serialized().await,
+            TransactionTool::Submit(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// Command to show a committed or pending transaction
+///
+#[derive(Debug, Parser)]
+pub struct ShowTransaction {
+    /// Hash of the transaction as a `0x` prefixed hex string, or version of the transaction
+    #[clap(parse(try_from_str))]
+    pub(crate) transaction: TransactionId,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) output_options: OutputOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionView> for ShowTransaction {
+    fn command_name(&self) -> &'static str {
+        "ShowTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionView> {
+        let client = Client::new(self.rest_options.url(&self.profile_options.profile)?);
+        let response = match self.transaction {
+            TransactionId::Hash(hash) => client.get_transaction(hash).await,
+            TransactionId::Version(version) => client.get_transaction_by_version(version).await,
+        };
+        response
+            .map(|response| TransactionView(response.into_inner()))
+            .map_err(|err| CliError::ApiError(err.to_string()))
+    }
+}
+
+/// Command to list the transactions sent by an account
+///
+/// The pages of transactions are fetched until the `limit` is reached.
+#[derive(Debug, Parser)]
+pub struct ListTransactions {
+    /// Address of the sender of the transactions
+    ///
+    /// Defaults to the account of the profile
+    #[clap(long, parse(try_from_str = load_account_arg))]
+    pub(crate) account: Option<AccountAddress>,
+    /// Maximum number of transactions to list, all of them if not given
+    #[clap(long)]
+    pub(crate) limit: Option<usize>,
+    /// List the transactions from the newest instead of from the oldest
+    #[clap(long)]
+    pub(crate) reverse: bool,
+    /// Number of transactions fetched per request
+    #[clap(long)]
+    pub(crate) page_size: Option<u16>,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) output_options: OutputOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionList> for ListTransactions {
+    fn command_name(&self) -> &'static str {
+        "ListTransactions"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionList> {
+        let profile = &self.profile_options.profile;
+        let account = match self.account {
+            Some(account) => account,
+            None => CliConfig::load_profile(profile)?
+                .and_then(|profile| profile.account)
+                .ok_or_else(|| {
+                    CliError::CommandArgumentError(
+                        "Please provide an account using --account or run aptos init".to_string(),
+                    )
+                })?,
+        };
+
+        let client = Client::new(self.rest_options.url(profile)?);
+        client
+            .paginate_account_transactions(account, self.reverse, self.page_size)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .try_collect()
+            .await
+            .map(TransactionList)
+            .map_err(|err| CliError::ApiError(err.to_string()))
+    }
+}
+
+/// A transaction given by its hash or by its version
+#[derive(Clone, Copy, Debug)]
+pub enum TransactionId {
+    Hash(HashValue),
+    Version(u64),
+}
+
+impl FromStr for TransactionId {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(hash) = s.strip_prefix("0x") {
+            HashValue::from_str(hash)
+                .map(TransactionId::Hash)
+                .map_err(|err| CliError::UnableToParse("transaction hash", err.to_string()))
+        } else {
+            u64::from_str(s)
+                .map(TransactionId::Version)
+                .map_err(|err| CliError::UnableToParse("transaction version", err.to_string()))
+        }
+    }
+}
+
+/// A transaction as returned by the Rest API, printable as human readable text
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct TransactionView(pub Transaction);
+
+impl Display for TransactionView {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let txn = &self.0;
+        match txn {
+            Transaction::PendingTransaction(pending) => {
+                writeln!(f, "Transaction {}", pending.hash)?;
+                writeln!(f, "  Type:            {}", txn.type_str())?;
+                return fmt_request(f, &pending.request);
+            }
+            Transaction::UserTransaction(user) => writeln!(f, "Transaction {}", user.info.hash)?,
+            Transaction::GenesisTransaction(genesis) => {
+                writeln!(f, "Transaction {}", genesis.info.hash)?
+            }
+            Transaction::BlockMetadataTransaction(block) => {
+                writeln!(f, "Transaction {}", block.info.hash)?
+            }
+            Transaction::StateCheckpointTransaction(checkpoint) => {
+                writeln!(f, "Transaction {}", checkpoint.info.hash)?
+            }
+        }
+        writeln!(f, "  Type:            {}", txn.type_str())?;
+        if let Some(version) = txn.version() {
+            writeln!(f, "  Version:         {}", version)?;
+        }
+        writeln!(f, "  Success:         {}", txn.success())?;
+        writeln!(f, "  VM status:       {}", txn.vm_status())?;
+        writeln!(f, "  Timestamp usecs: {}", txn.timestamp())?;
+        if let Transaction::UserTransaction(user) = txn {
+            writeln!(f, "  Gas used:        {}", user.info.gas_used)?;
+            fmt_request(f, &user.request)?;
+            writeln!(f, "  Events:          {}", user.events.len())?;
+        }
+        Ok(())
+    }
+}
+
+fn fmt_request(f: &mut Formatter<'_>, request: &UserTransactionRequest) -> std::fmt::Result {
+    writeln!(f, "  Sender:          {}", request.sender)?;
+    writeln!(f, "  Sequence number: {}", request.sequence_number)?;
+    writeln!(
+        f,
+        "  Max gas:         {} at {} per unit",
+        request.max_gas_amount, request.gas_unit_price
+    )?;
+    match &request.payload {
+        ApiTransactionPayload::ScriptFunctionPayload(payload) => {
+            write!(f, "  Function:        {}", payload.function)?;
+            if !payload.type_arguments.is_empty() {
+                let type_arguments: Vec<String> = payload
+                    .type_arguments
+                    .iter()
+                    .map(|type_argument| type_argument.to_string())
+                    .collect();
+                write!(f, "<{}>", type_arguments.join(", "))?;
+            }
+            writeln!(f)?;
+            let arguments: Vec<String> = payload
+                .arguments
+                .iter()
+                .map(|argument| argument.to_string())
+                .collect();
+            writeln!(f, "  Arguments:       [{}]", arguments.join(", "))
+        }
+        ApiTransactionPayload::ScriptPayload(_) => writeln!(f, "  Payload:         script"),
+        ApiTransactionPayload::ModuleBundlePayload(payload) => {
+            writeln!(f, "  Payload:         {} modules", payload.modules.len())
+        }
+        ApiTransactionPayload::WriteSetPayload(_) => writeln!(f, "  Payload:         write set"),
+    }
+}
+
+/// Transactions as returned by the Rest API, printable as human readable text
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct TransactionList(pub Vec<Transaction>);
+
+impl Display for TransactionList {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, txn) in self.0.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", TransactionView(txn.clone()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Command to build an unsigned transaction transferring coins between accounts
+///
+#[derive(Debug, Parser)]
+pub struct BuildTransfer {
+    #[clap(flatten)]
+    pub(crate) raw_txn_options: RawTransactionOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+    /// Address of account you want to send coins to
+    #[clap(long, parse(try_from_str = load_account_arg))]
+    pub(crate) account: AccountAddress,
+    /// Amount of coins to transfer
+    #[clap(long)]
+    pub(crate) amount: u64,
+}
+
+#[async_trait]
+impl CliCommand<RawTransactionSummary> for BuildTransfer {
+    fn command_name(&self) -> &'static str {
+        "BuildTransfer"
+    }
+
+    async fn execute(self) -> CliTypedResult<RawTransactionSummary> {
+        build_transaction(
+            &self.raw_txn_options,
+            &self.profile_options,
+            &self.save_file,
+            aptos_stdlib::encode_test_coin_transfer(self.account, self.amount),
+        )
+        .await
+    }
+}
+
+/// Command to build an unsigned transaction running a Move function
+///
+#[derive(Parser)]
+pub struct BuildRunFunction {
+    #[clap(flatten)]
+    raw_txn_options: RawTransactionOptions,
+    #[clap(flatten)]
+    profile_options: ProfileOptions,
+    #[clap(flatten)]
+    save_file: SaveFile,
+    #[clap(flatten)]
+    function_args: ScriptFunctionArgs,
+}
+
+#[async_trait]
+impl CliCommand<RawTransactionSummary> for BuildRunFunction {
+    fn command_name(&self) -> &'static str {
+        "BuildRunFunction"
+    }
+
+    async fn execute(self) -> CliTypedResult<RawTransactionSummary> {
+        build_transaction(
+            &self.raw_txn_options,
+            &self.profile_options,
+            &self.save_file,
+            self.function_args.payload()?,
+        )
+        .await
+    }
+}
+
+/// Command to build an unsigned transaction publishing the modules in a Move package
+///
+#[derive(Parser)]
+pub struct BuildPublish {
+    #[clap(flatten)]
+    raw_txn_options: RawTransactionOptions,
+    #[clap(flatten)]
+    profile_options: ProfileOptions,
+    #[clap(flatten)]
+    save_file: SaveFile,
+    #[clap(flatten)]
+    move_options: MovePackageDir,
+}
+
+#[async_trait]
+impl CliCommand<RawTransactionSummary> for BuildPublish {
+    fn command_name(&self) -> &'static str {
+        "BuildPublish"
+    }
+
+    async fn execute(self) -> CliTypedResult<RawTransactionSummary> {
+        build_transaction(
+            &self.raw_txn_options,
+            &self.profile_options,
+            &self.save_file,
+            package_payload(&self.move_options)?,
+        )
+        .await
+    }
+}
+
+/// Builds the transaction and saves it BCS encoded to the output file
+async fn build_transaction(
+    raw_txn_options: &RawTransactionOptions,
+    profile_options: &ProfileOptions,
+    save_file: &SaveFile,
+    payload: TransactionPayload,
+) -> CliTypedResult<RawTransactionSummary> {
+    save_file.check_file()?;
+    let raw_txn = raw_txn_options
+        .build(&profile_options.profile, payload)
+        .await?;
+    let bytes = bcs::to_bytes(&raw_txn).map_err(|err| CliError::BCS("RawTransaction", err))?;
+    save_file.save_to_file("Unsigned transaction", &bytes)?;
+
+    Ok(RawTransactionSummary::from(raw_txn))
+}
+
+/// Command to sign an unsigned transaction file
+///
+/// The signing doesn't need network access, so it can be done on an offline machine.
+#[derive(Debug, Parser)]
+pub struct SignTransaction {
+    /// Path to the BCS encoded unsigned transaction
+    #[clap(long, parse(from_os_str))]
+    pub(crate) transaction_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+#[async_trait]
+impl CliCommand<SignedTransactionSummary> for SignTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<SignedTransactionSummary> {
+        self.save_file.check_file()?;
+        let raw_txn: RawTransaction = read_bcs_file("RawTransaction", &self.transaction_file)?;
+        let private_key = self.private_key_options.extract_private_key(
+            self.encoding_options.encoding,
+            &self.profile_options.profile,
+        )?;
+
+        let signed_txn = raw_txn
+            .sign(&private_key, private_key.public_key())
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?
+            .into_inner();
+        let bytes =
+            bcs::to_bytes(&signed_txn).map_err(|err| CliError::BCS("SignedTransaction", err))?;
+        self.save_file.save_to_file("Signed transaction", &bytes)?;
+
+        Ok(SignedTransactionSummary::from(signed_txn))
+    }
+}
+
+/// Command to submit a signed transaction file, and wait for it to be committed
+///
+#[derive(Debug, Parser)]
+pub struct SubmitTransaction {
+    /// Path to the BCS encoded signed transaction
+    #[clap(long, parse(from_os_str))]
+    pub(crate) transaction_file: PathBuf,
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let signed_txn: SignedTransaction =
+            read_bcs_file("SignedTransaction", &self.transaction_file)?;
+        let client = Client::new(self.rest_options.url(&self.profile_options.profile)?);
+        client
+            .submit_and_wait(&signed_txn)
+            .await
+            .map(|response| TransactionSummary::from(response.into_inner()))
+            .map_err(|err| CliError::ApiError(err.to_string()))
+    }
+}
+
+/// Reads a BCS encoded value from a file
+pub(crate) fn read_bcs_file<T: DeserializeOwned>(
+    name: &'static str,
+    path: &Path,
+) -> CliTypedResult<T> {
+    let bytes = read_from_file(path)?;
+    bcs::from_bytes(&bytes).map_err(|err| CliError::BCS(name, err))
+}
+
+/// Summary of a built unsigned transaction
+#[derive(Clone, Debug, Serialize)]
+pub struct RawTransactionSummary {
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub chain_id: ChainId,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub expiration_timestamp_secs: u64,
+}
+
+impl From<RawTransaction> for RawTransactionSummary {
+    fn from(raw_txn: RawTransaction) -> Self {
+        RawTransactionSummary {
+            sender: raw_txn.sender(),
+            sequence_number: raw_txn.sequence_number(),
+            chain_id: raw_txn.chain_id(),
+            max_gas_amount: raw_txn.max_gas_amount(),
+            gas_unit_price: raw_txn.gas_unit_price(),
+            expiration_timestamp_secs: raw_txn.expiration_timestamp_secs(),
+        }
+    }
+}
+
+/// Summary of a signed transaction, to be checked before submitting it
+#[derive(Clone, Debug, Serialize)]
+pub struct SignedTransactionSummary {
+    pub hash: HashValue,
+    pub sender: AccountAddress,
+    pub sequence_number: u64,
+    pub chain_id: ChainId,
+    pub max_gas_amount: u64,
+    pub gas_unit_price: u64,
+    pub expiration_timestamp_secs: u64,
+}
+
+impl From<SignedTransaction> for SignedTransactionSummary {
+    fn from(signed_txn: SignedTransaction) -> Self {
+        SignedTransactionSummary {
+            sender: signed_txn.sender(),
+            sequence_number: signed_txn.sequence_number(),
+            chain_id: signed_txn.chain_id(),
+            max_gas_amount: signed_txn.max_gas_amount(),
+            gas_unit_price: signed_txn.gas_unit_price(),
+            expiration_timestamp_secs: signed_txn.expiration_timestamp_secs(),
+            hash: signed_txn.committed_hash(),
+        }
+    }
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::Result;
-use reqwest::{blocking, Url};
+use aptos_crypto::HashValue;
+use aptos_mempool::TransactionTrace;
+use reqwest::{blocking, StatusCode, Url};
 use std::collections::HashMap;
 
 pub mod node_debug_service;
@@ -78,6 +80,21 @@ impl NodeDebugClient {
             })
             .collect()
     }
+
+    /// Retrieves the lifecycle trace of a transaction in mempool, `None` if mempool doesn't know it.
+    pub fn get_transaction_trace(&self, hash: HashValue) -> Result<Option<TransactionTrace>> {
+        let mut url = self.url.clone();
+        url.set_path(&format!("mempool/trace/{}", hash.to_hex()));
+        let response = self.client.get(url).send()?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            anyhow::bail!("Error querying transaction trace: {}", response.status());
+        }
+        Ok(Some(response.json()?))
+    }
 }
 
 /// Implement default utility client for AsyncNodeDebugInterface
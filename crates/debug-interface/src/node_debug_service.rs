@@ -4,12 +4,15 @@
 //! Debug interface to access information in a specific node.
 
 use aptos_config::config::NodeConfig;
+use aptos_crypto::HashValue;
 use aptos_logger::{info, Filter, Logger};
+use aptos_mempool::{MempoolClientRequest, MempoolClientSender};
 use aptos_metrics::metric_server;
+use futures::{channel::oneshot, SinkExt};
 use serde::{Deserialize, Serialize};
-use std::{net::SocketAddr, sync::Arc};
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
 use tokio::runtime::{Builder, Runtime};
-use warp::Filter as _;
+use warp::{http::StatusCode, Filter as _, Reply};
 
 #[derive(Debug)]
 pub struct NodeDebugService {
@@ -23,7 +26,12 @@ struct NodeInfo {
 }
 
 impl NodeDebugService {
-    pub fn new(address: SocketAddr, logger: Option<Arc<Logger>>, node_config: &NodeConfig) -> Self {
+    pub fn new(
+        address: SocketAddr,
+        logger: Option<Arc<Logger>>,
+        node_config: &NodeConfig,
+        mempool_client_sender: MempoolClientSender,
+    ) -> Self {
         let runtime = Builder::new_multi_thread()
             .thread_name("nodedebug")
             .enable_all()
@@ -77,7 +85,11 @@ impl NodeDebugService {
         };
         let node_info_route = warp::path("node-info").map(move || warp::reply::json(&node_info));
 
-        let routes = log.or(warp::get().and(metrics.or(node_info_route)));
+        // Get /mempool/trace/<hash> (the lifecycle of a transaction in mempool)
+        let mempool_trace = warp::path!("mempool" / "trace" / String)
+            .and_then(move |hash| get_transaction_trace(mempool_client_sender.clone(), hash));
+
+        let routes = log.or(warp::get().and(metrics.or(node_info_route).or(mempool_trace)));
 
         runtime
             .handle()
@@ -90,3 +102,41 @@ impl NodeDebugService {
         &self.runtime
     }
 }
+
+async fn get_transaction_trace(
+    mut mempool_client_sender: MempoolClientSender,
+    hash: String,
+) -> Result<warp::reply::Response, Infallible> {
+    let hash = match HashValue::from_hex(hash.trim_start_matches("0x")) {
+        Ok(hash) => hash,
+        Err(e) => {
+            return Ok(warp::reply::with_status(
+                format!("Invalid transaction hash: {}", e),
+                StatusCode::BAD_REQUEST,
+            )
+            .into_response())
+        }
+    };
+
+    let (callback, trace) = oneshot::channel();
+    let trace = match mempool_client_sender
+        .send(MempoolClientRequest::GetTransactionTrace(hash, callback))
+        .await
+    {
+        Ok(()) => trace.await.ok(),
+        Err(_) => None,
+    };
+    Ok(match trace {
+        Some(Some(trace)) => warp::reply::json(&trace).into_response(),
+        Some(None) => warp::reply::with_status(
+            "Transaction not found in mempool".to_string(),
+            StatusCode::NOT_FOUND,
+        )
+        .into_response(),
+        None => warp::reply::with_status(
+            "Mempool is unavailable".to_string(),
+            StatusCode::SERVICE_UNAVAILABLE,
+        )
+        .into_response(),
+    })
+}
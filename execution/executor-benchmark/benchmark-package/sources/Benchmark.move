@@ -0,0 +1,81 @@
+/// Entry functions for the executor benchmark workloads which aren't covered by the framework.
+module Benchmark::Benchmark {
+    use Std::Signer;
+    use AptosFramework::Table::{Self, Table};
+
+    /// Table items owned by an account, keyed by their insertion order
+    struct Entries has key {
+        entries: Table<u64, u64>,
+        next: u64,
+    }
+
+    /// Resource instantiated for each marker type, so that an account can hold many of them
+    struct Resource<phantom T> has key {
+        value: u64,
+    }
+
+    struct R0 {}
+    struct R1 {}
+    struct R2 {}
+    struct R3 {}
+    struct R4 {}
+    struct R5 {}
+    struct R6 {}
+    struct R7 {}
+    struct R8 {}
+    struct R9 {}
+    struct R10 {}
+    struct R11 {}
+    struct R12 {}
+    struct R13 {}
+    struct R14 {}
+    struct R15 {}
+
+    /// Adds `count` new items to the table of the account.
+    public(script) fun insert_entries(account: signer, count: u64) acquires Entries {
+        let account_addr = Signer::address_of(&account);
+        if (!exists<Entries>(account_addr)) {
+            move_to(&account, Entries {
+                entries: Table::new(),
+                next: 0,
+            });
+        };
+        let entries = borrow_global_mut<Entries>(account_addr);
+        let i = 0;
+        while (i < count) {
+            let key = entries.next;
+            Table::add(&mut entries.entries, key, i);
+            entries.next = key + 1;
+            i = i + 1;
+        };
+    }
+
+    /// Writes `value` to the first `count` resources of the account, at most 16.
+    public(script) fun write_resources(account: signer, count: u64, value: u64) acquires Resource {
+        if (count > 0) write<R0>(&account, value);
+        if (count > 1) write<R1>(&account, value);
+        if (count > 2) write<R2>(&account, value);
+        if (count > 3) write<R3>(&account, value);
+        if (count > 4) write<R4>(&account, value);
+        if (count > 5) write<R5>(&account, value);
+        if (count > 6) write<R6>(&account, value);
+        if (count > 7) write<R7>(&account, value);
+        if (count > 8) write<R8>(&account, value);
+        if (count > 9) write<R9>(&account, value);
+        if (count > 10) write<R10>(&account, value);
+        if (count > 11) write<R11>(&account, value);
+        if (count > 12) write<R12>(&account, value);
+        if (count > 13) write<R13>(&account, value);
+        if (count > 14) write<R14>(&account, value);
+        if (count > 15) write<R15>(&account, value);
+    }
+
+    fun write<T>(account: &signer, value: u64) acquires Resource {
+        let account_addr = Signer::address_of(account);
+        if (exists<Resource<T>>(account_addr)) {
+            borrow_global_mut<Resource<T>>(account_addr).value = value;
+        } else {
+            move_to(account, Resource<T> { value });
+        }
+    }
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_sdk::{move_types::account_address::AccountAddress, types::LocalAccount};
-use rand::{rngs::StdRng, SeedableRng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::{collections::VecDeque, sync::mpsc};
 
 type Seed = [u8; 32];
@@ -89,4 +89,39 @@ impl AccountCache {
 
         (sender, receiver)
     }
+
+    /// Picks a random account among the first `n` accounts of the cache.
+    pub fn get_random_among_first(&mut self, n: usize) -> &mut LocalAccount {
+        let index = self.rng.gen_range(0, n.min(self.accounts.len()));
+
+        &mut self.accounts[index]
+    }
+
+    /// Picks a random transfer whose receiver is one of the first `num_hotspots` accounts with
+    /// probability `conflict_ratio`, and any account otherwise.
+    pub fn get_random_hotspot_transfer(
+        &mut self,
+        num_hotspots: usize,
+        conflict_ratio: f64,
+    ) -> (&mut LocalAccount, AccountAddress) {
+        assert!(num_hotspots > 0, "At least one hotspot account is needed");
+        assert!(
+            self.accounts.len() > 1,
+            "Transfers need at least two accounts, got {}",
+            self.accounts.len()
+        );
+        if !self.rng.gen_bool(conflict_ratio) {
+            return self.get_random_transfer();
+        }
+        let receiver_idx = self.rng.gen_range(0, num_hotspots.min(self.accounts.len()));
+        let mut sender_idx = self.rng.gen_range(0, self.accounts.len() - 1);
+        if sender_idx >= receiver_idx {
+            sender_idx += 1;
+        }
+
+        let receiver = self.accounts[receiver_idx].address();
+        let sender = &mut self.accounts[sender_idx];
+
+        (sender, receiver)
+    }
 }
@@ -7,15 +7,19 @@ pub mod state_committer;
 pub mod transaction_committer;
 pub mod transaction_executor;
 pub mod transaction_generator;
+pub mod workload;
 
 use crate::{
-    transaction_committer::TransactionCommitter, transaction_executor::TransactionExecutor,
+    transaction_committer::TransactionCommitter,
+    transaction_executor::TransactionExecutor,
     transaction_generator::TransactionGenerator,
+    workload::{compile_benchmark_package, Workload, WorkloadOptions, WorkloadReport},
 };
 use aptos_config::config::{
     NodeConfig, RocksdbConfig, StoragePrunerConfig, NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_logger::prelude::*;
+use aptos_types::account_config::aptos_root_address;
 
 use crate::state_committer::StateCommitter;
 use aptos_vm::AptosVM;
@@ -54,21 +58,7 @@ pub fn run_benchmark(
     verify_sequence_numbers: bool,
     pruner_config: StoragePrunerConfig,
 ) {
-    // Create rocksdb checkpoint.
-    if checkpoint_dir.as_ref().exists() {
-        fs::remove_dir_all(checkpoint_dir.as_ref()).unwrap_or(());
-    }
-    std::fs::create_dir_all(checkpoint_dir.as_ref()).unwrap();
-
-    AptosDB::open(
-        &source_dir,
-        true,                        /* readonly */
-        NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
-        RocksdbConfig::default(),
-    )
-    .expect("db open failure.")
-    .create_checkpoint(checkpoint_dir.as_ref())
-    .expect("db checkpoint creation fails.");
+    create_checkpoint(&source_dir, &checkpoint_dir);
 
     let (mut config, _genesis_key) = aptos_genesis::test_utils::test_config();
     config.storage.dir = checkpoint_dir.as_ref().to_path_buf();
@@ -152,8 +142,93 @@ pub fn run_benchmark(
     }
 }
 
+/// Runs the workloads one after the other on a checkpoint of the DB.
+///
+/// Every block is executed both sequentially and in parallel before being committed, and the
+/// execution results are reported per workload.
+pub fn run_workloads(
+    workloads: &[Workload],
+    options: &WorkloadOptions,
+    block_size: usize,
+    num_blocks: usize,
+    source_dir: impl AsRef<Path>,
+    checkpoint_dir: impl AsRef<Path>,
+    pruner_config: StoragePrunerConfig,
+) -> Vec<WorkloadReport> {
+    create_checkpoint(&source_dir, &checkpoint_dir);
+
+    // The genesis key is deterministic, so it is the one of the source DB's root account.
+    let (mut config, genesis_key) = aptos_genesis::test_utils::test_config();
+    config.storage.dir = checkpoint_dir.as_ref().to_path_buf();
+    config.storage.storage_pruner_config = pruner_config;
+
+    let (db, executor) = init_db_and_executor(&config);
+    let start_version = db.reader.get_latest_version().unwrap();
+    let parent_block_id = executor.committed_block_id();
+    // Without a commit sender, the blocks are committed as soon as they are executed, so the
+    // next block is measured on top of the latest state checkpoint.
+    let mut executor =
+        TransactionExecutor::new(Arc::new(executor), parent_block_id, start_version, None);
+    let mut generator = TransactionGenerator::new_for_workloads(
+        db.reader.clone(),
+        source_dir,
+        start_version,
+        genesis_key,
+    );
+    let concurrency_level = AptosVM::get_concurrency_level();
+    let mut benchmark_package = workloads
+        .iter()
+        .any(Workload::needs_benchmark_package)
+        .then(|| compile_benchmark_package(aptos_root_address()));
+
+    let mut reports = vec![];
+    for workload in workloads {
+        info!("Setting up workload {}", workload);
+        let package = if workload.needs_benchmark_package() {
+            // The package is only published once.
+            benchmark_package.take()
+        } else {
+            None
+        };
+        for block in
+            generator.gen_workload_setup_transactions(*workload, options, block_size, package)
+        {
+            executor.execute_block(block);
+        }
+
+        info!("Running workload {}", workload);
+        let mut report = WorkloadReport::new(*workload);
+        for block in generator.gen_workload_transactions(*workload, options, block_size, num_blocks)
+        {
+            report.measure_block(&db.reader, &block, concurrency_level);
+            executor.execute_block(block);
+        }
+        reports.push(report);
+    }
+    reports
+}
+
+fn create_checkpoint(source_dir: impl AsRef<Path>, checkpoint_dir: impl AsRef<Path>) {
+    // Create rocksdb checkpoint.
+    if checkpoint_dir.as_ref().exists() {
+        fs::remove_dir_all(checkpoint_dir.as_ref()).unwrap_or(());
+    }
+    std::fs::create_dir_all(checkpoint_dir.as_ref()).unwrap();
+
+    AptosDB::open(
+        &source_dir,
+        true,                        /* readonly */
+        NO_OP_STORAGE_PRUNER_CONFIG, /* pruner */
+        RocksdbConfig::default(),
+    )
+    .expect("db open failure.")
+    .create_checkpoint(checkpoint_dir.as_ref())
+    .expect("db checkpoint creation fails.");
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::workload::{Workload, WorkloadOptions};
     use aptos_config::config::NO_OP_STORAGE_PRUNER_CONFIG;
     use aptos_temppath::TempPath;
 
@@ -180,4 +255,51 @@ mod tests {
             NO_OP_STORAGE_PRUNER_CONFIG,
         );
     }
+
+    #[test]
+    fn test_workloads() {
+        let storage_dir = TempPath::new();
+        let checkpoint_dir = TempPath::new();
+
+        crate::db_generator::run(
+            25,      /* num_accounts */
+            1000000, /* init_account_balance */
+            5,       /* block_size */
+            storage_dir.as_ref(),
+            NO_OP_STORAGE_PRUNER_CONFIG, /* prune_window */
+            false,
+        );
+
+        let workloads = [
+            Workload::Transfer,
+            Workload::Hotspot,
+            Workload::TokenMint,
+            Workload::TableInsert,
+            Workload::ResourceWrites,
+        ];
+        let options = WorkloadOptions {
+            num_hotspots: 2,
+            conflict_ratio: 0.5,
+            num_token_creators: 7,
+            table_inserts_per_txn: 3,
+            resources_per_txn: 4,
+        };
+        let reports = super::run_workloads(
+            &workloads,
+            &options,
+            5, /* block_size */
+            3, /* num_blocks */
+            storage_dir.as_ref(),
+            checkpoint_dir,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+        );
+
+        assert_eq!(reports.len(), workloads.len());
+        for (report, workload) in reports.iter().zip(workloads.iter()) {
+            assert_eq!(report.workload, *workload);
+            // 3 blocks of 5 transactions and a state checkpoint
+            assert_eq!(report.num_txns, 18);
+            assert_eq!(report.num_failed_txns, 0, "{}", report);
+        }
+    }
 }
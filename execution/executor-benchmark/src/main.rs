@@ -4,6 +4,7 @@
 use aptos_config::config::StoragePrunerConfig;
 use aptos_secure_push_metrics::MetricsPusher;
 use aptos_vm::AptosVM;
+use executor_benchmark::workload::{Workload, WorkloadOptions};
 use std::path::PathBuf;
 use structopt::StructOpt;
 
@@ -100,6 +101,31 @@ enum Command {
         #[structopt(long, parse(from_os_str))]
         data_dir: PathBuf,
 
+        #[structopt(long, parse(from_os_str))]
+        checkpoint_dir: PathBuf,
+    },
+    RunWorkloads {
+        #[structopt(
+            long,
+            default_value = "100",
+            about = "number of blocks to run per workload"
+        )]
+        blocks: usize,
+
+        #[structopt(
+            long,
+            use_delimiter = true,
+            default_value = "transfer,hotspot,token-mint,table-insert,resource-writes",
+            about = "workloads to run, in order"
+        )]
+        workloads: Vec<Workload>,
+
+        #[structopt(flatten)]
+        workload_opt: WorkloadOptions,
+
+        #[structopt(long, parse(from_os_str))]
+        data_dir: PathBuf,
+
         #[structopt(long, parse(from_os_str))]
         checkpoint_dir: PathBuf,
     },
@@ -145,5 +171,27 @@ fn main() {
                 opt.pruner_opt.pruner_config(),
             );
         }
+        Command::RunWorkloads {
+            blocks,
+            workloads,
+            workload_opt,
+            data_dir,
+            checkpoint_dir,
+        } => {
+            aptos_logger::Logger::new().init();
+            let reports = executor_benchmark::run_workloads(
+                &workloads,
+                &workload_opt,
+                opt.block_size,
+                blocks,
+                data_dir,
+                checkpoint_dir,
+                opt.pruner_opt.pruner_config(),
+            );
+            println!("============= WORKLOAD RESULTS =============");
+            for report in reports {
+                println!("{}", report);
+            }
+        }
     }
 }
@@ -1,15 +1,25 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::account_generator::{AccountCache, AccountGenerator};
+use crate::{
+    account_generator::{AccountCache, AccountGenerator},
+    workload::{Workload, WorkloadOptions, BENCHMARK_MODULE_NAME},
+};
 use aptos_crypto::ed25519::Ed25519PrivateKey;
-use aptos_sdk::{transaction_builder::TransactionFactory, types::LocalAccount};
+use aptos_sdk::{
+    move_types::{identifier::Identifier, language_storage::ModuleId},
+    transaction_builder::TransactionFactory,
+    types::LocalAccount,
+};
 use aptos_state_view::account_with_state_view::AsAccountWithStateView;
+use aptos_transaction_builder::aptos_stdlib;
 use aptos_types::{
     account_config::aptos_root_address,
     account_view::AccountView,
     chain_id::ChainId,
-    transaction::{Transaction, Version},
+    transaction::{
+        ModuleBundle, ScriptFunction, SignedTransaction, Transaction, TransactionPayload, Version,
+    },
 };
 use chrono::Local;
 use indicatif::{ProgressBar, ProgressStyle};
@@ -27,6 +37,8 @@ use storage_interface::{state_view::LatestDbStateCheckpointView, DbReader};
 
 const META_FILENAME: &str = "metadata.toml";
 const MAX_ACCOUNTS_INVOLVED_IN_P2P: usize = 1_000_000;
+const WORKLOAD_MAX_GAS_AMOUNT: u64 = 100_000;
+const TOKEN_COLLECTION_NAME: &str = "benchmark";
 
 fn get_progress_bar(num_accounts: usize) -> ProgressBar {
     let bar = ProgressBar::new(num_accounts as u64);
@@ -76,8 +88,17 @@ pub struct TransactionGenerator {
     /// Transaction Factory
     transaction_factory: TransactionFactory,
 
+    /// Transaction Factory for the workloads which may need more gas than a transfer
+    workload_transaction_factory: TransactionFactory,
+
     /// root account is used across creating and minting.
     root_account: Option<LocalAccount>,
+
+    /// Number of token creators whose collection has been created.
+    num_token_creators: usize,
+
+    /// Number of tokens minted, used to name the next token.
+    num_tokens: u64,
 }
 
 impl TransactionGenerator {
@@ -106,6 +127,9 @@ impl TransactionGenerator {
             version: 0,
             block_sender,
             transaction_factory: Self::create_transaction_factory(),
+            workload_transaction_factory: Self::create_workload_transaction_factory(),
+            num_token_creators: 0,
+            num_tokens: 0,
         }
     }
 
@@ -138,6 +162,36 @@ impl TransactionGenerator {
         block_sender: mpsc::SyncSender<Vec<Transaction>>,
         db_dir: P,
         version: Version,
+    ) -> Self {
+        Self::new_with_existing_db_impl(Some(block_sender), db_dir, version, None)
+    }
+
+    /// Creates a generator returning the blocks of the workloads, with the root account to
+    /// publish the benchmark package.
+    pub fn new_for_workloads<P: AsRef<Path>>(
+        db: Arc<dyn DbReader>,
+        db_dir: P,
+        version: Version,
+        genesis_key: Ed25519PrivateKey,
+    ) -> Self {
+        let root_address = aptos_root_address();
+        let sequence_number = db
+            .latest_state_checkpoint_view()
+            .unwrap()
+            .as_account_with_state_view(&root_address)
+            .get_account_resource()
+            .unwrap()
+            .expect("Root account must exist.")
+            .sequence_number();
+        let root_account = LocalAccount::new(root_address, genesis_key, sequence_number);
+        Self::new_with_existing_db_impl(None, db_dir, version, Some(root_account))
+    }
+
+    fn new_with_existing_db_impl<P: AsRef<Path>>(
+        block_sender: Option<mpsc::SyncSender<Vec<Transaction>>>,
+        db_dir: P,
+        version: Version,
+        root_account: Option<LocalAccount>,
     ) -> Self {
         let path = db_dir.as_ref().join(META_FILENAME);
         let mut file = File::open(&path).unwrap();
@@ -151,12 +205,15 @@ impl TransactionGenerator {
 
         Self {
             seed_accounts_cache: None,
-            root_account: None,
+            root_account,
             accounts_cache,
             num_accounts,
             version,
-            block_sender: Some(block_sender),
+            block_sender,
             transaction_factory: Self::create_transaction_factory(),
+            workload_transaction_factory: Self::create_workload_transaction_factory(),
+            num_token_creators: 0,
+            num_tokens: 0,
         }
     }
 
@@ -167,6 +224,10 @@ impl TransactionGenerator {
             .with_max_gas_amount(1000)
     }
 
+    fn create_workload_transaction_factory() -> TransactionFactory {
+        Self::create_transaction_factory().with_max_gas_amount(WORKLOAD_MAX_GAS_AMOUNT)
+    }
+
     // Write metadata
     pub fn write_meta<P: AsRef<Path>>(self, path: &P) {
         let metadata = TestCase::P2p(P2pTestCase {
@@ -309,19 +370,173 @@ impl TransactionGenerator {
         &mut self,
         block_size: usize,
         num_blocks: usize,
+    ) -> Vec<Vec<Transaction>> {
+        self.gen_blocks(block_size, num_blocks, |generator| {
+            let (sender, receiver) = generator
+                .accounts_cache
+                .as_mut()
+                .unwrap()
+                .get_random_transfer();
+            sender
+                .sign_with_transaction_builder(generator.transaction_factory.transfer(receiver, 1))
+        })
+    }
+
+    /// Generates the transactions which have to be committed before the blocks of the workload.
+    pub fn gen_workload_setup_transactions(
+        &mut self,
+        workload: Workload,
+        options: &WorkloadOptions,
+        block_size: usize,
+        benchmark_package: Option<ModuleBundle>,
+    ) -> Vec<Vec<Transaction>> {
+        match workload {
+            Workload::TokenMint => self.create_token_collections(
+                block_size,
+                options
+                    .num_token_creators
+                    .min(self.accounts_cache.as_ref().unwrap().len()),
+            ),
+            Workload::TableInsert | Workload::ResourceWrites => benchmark_package
+                .map(|package| self.publish_benchmark_package(package))
+                .unwrap_or_default(),
+            Workload::Transfer | Workload::Hotspot => vec![],
+        }
+    }
+
+    /// Generates the blocks of the workload.
+    pub fn gen_workload_transactions(
+        &mut self,
+        workload: Workload,
+        options: &WorkloadOptions,
+        block_size: usize,
+        num_blocks: usize,
+    ) -> Vec<Vec<Transaction>> {
+        match workload {
+            Workload::Transfer => self.gen_transfer_transactions(block_size, num_blocks),
+            Workload::Hotspot => self.gen_blocks(block_size, num_blocks, |generator| {
+                let (sender, receiver) = generator
+                    .accounts_cache
+                    .as_mut()
+                    .unwrap()
+                    .get_random_hotspot_transfer(options.num_hotspots, options.conflict_ratio);
+                sender.sign_with_transaction_builder(
+                    generator.transaction_factory.transfer(receiver, 1),
+                )
+            }),
+            Workload::TokenMint => {
+                assert!(
+                    self.num_token_creators > 0,
+                    "Token collections must be created first."
+                );
+                self.gen_blocks(block_size, num_blocks, |generator| {
+                    let name = format!("token-{}", generator.num_tokens).into_bytes();
+                    generator.num_tokens += 1;
+                    let payload = aptos_stdlib::encode_token_create_unlimited_token_script(
+                        TOKEN_COLLECTION_NAME.as_bytes().to_vec(),
+                        name,
+                        b"description".to_vec(),
+                        false, /* monitor_supply */
+                        1,     /* initial_balance */
+                        b"uri".to_vec(),
+                        0, /* royalty_points_per_million */
+                    );
+                    generator
+                        .accounts_cache
+                        .as_mut()
+                        .unwrap()
+                        .get_random_among_first(generator.num_token_creators)
+                        .sign_with_transaction_builder(
+                            generator.workload_transaction_factory.payload(payload),
+                        )
+                })
+            }
+            Workload::TableInsert => self.gen_blocks(block_size, num_blocks, |generator| {
+                let payload = benchmark_script_function(
+                    "insert_entries",
+                    vec![bcs::to_bytes(&options.table_inserts_per_txn).unwrap()],
+                );
+                generator.sign_by_random_account(payload)
+            }),
+            Workload::ResourceWrites => self.gen_blocks(block_size, num_blocks, |generator| {
+                // Write a new value in every block, so that all the resources change.
+                let value = generator.version;
+                let payload = benchmark_script_function(
+                    "write_resources",
+                    vec![
+                        bcs::to_bytes(&options.resources_per_txn).unwrap(),
+                        bcs::to_bytes(&value).unwrap(),
+                    ],
+                );
+                generator.sign_by_random_account(payload)
+            }),
+        }
+    }
+
+    /// Generates transactions creating the token collection of the first `num_creators`
+    /// accounts of the cache.
+    pub fn create_token_collections(
+        &mut self,
+        block_size: usize,
+        num_creators: usize,
+    ) -> Vec<Vec<Transaction>> {
+        let mut txn_block = vec![];
+        while self.num_token_creators < num_creators {
+            let size = (num_creators - self.num_token_creators).min(block_size);
+            txn_block.extend(self.gen_blocks(size, 1, |generator| {
+                let payload = aptos_stdlib::encode_token_create_unlimited_collection_script(
+                    TOKEN_COLLECTION_NAME.as_bytes().to_vec(),
+                    b"description".to_vec(),
+                    b"uri".to_vec(),
+                );
+                let creator = &mut generator.accounts_cache.as_mut().unwrap().accounts
+                    [generator.num_token_creators];
+                generator.num_token_creators += 1;
+                creator.sign_with_transaction_builder(
+                    generator.workload_transaction_factory.payload(payload),
+                )
+            }));
+        }
+        txn_block
+    }
+
+    /// Generates the transaction publishing the benchmark package with the root account.
+    pub fn publish_benchmark_package(&mut self, package: ModuleBundle) -> Vec<Vec<Transaction>> {
+        self.gen_blocks(1, 1, |generator| {
+            generator
+                .root_account
+                .as_mut()
+                .expect("Root account is needed to publish the benchmark package.")
+                .sign_with_transaction_builder(
+                    generator
+                        .workload_transaction_factory
+                        .payload(TransactionPayload::ModuleBundle(package.clone())),
+                )
+        })
+    }
+
+    fn sign_by_random_account(&mut self, payload: TransactionPayload) -> SignedTransaction {
+        self.accounts_cache
+            .as_mut()
+            .unwrap()
+            .get_random()
+            .sign_with_transaction_builder(self.workload_transaction_factory.payload(payload))
+    }
+
+    /// Generates blocks of `block_size` transactions followed by a state checkpoint, sending them
+    /// to the block sender if there is one, and returning them otherwise.
+    fn gen_blocks(
+        &mut self,
+        block_size: usize,
+        num_blocks: usize,
+        mut gen_transaction: impl FnMut(&mut Self) -> SignedTransaction,
     ) -> Vec<Vec<Transaction>> {
         let mut txn_block = vec![];
 
         for _ in 0..num_blocks {
             let transactions: Vec<_> = (0..block_size)
                 .into_iter()
-                .map(|_| {
-                    let (sender, receiver) =
-                        self.accounts_cache.as_mut().unwrap().get_random_transfer();
-                    sender.sign_with_transaction_builder(
-                        self.transaction_factory.transfer(receiver, 1),
-                    )
-                })
+                .map(|_| gen_transaction(self))
                 .map(Transaction::UserTransaction)
                 .chain(once(Transaction::StateCheckpoint))
                 .collect();
@@ -378,3 +593,15 @@ impl TransactionGenerator {
         self.block_sender.take().unwrap();
     }
 }
+
+fn benchmark_script_function(function: &str, args: Vec<Vec<u8>>) -> TransactionPayload {
+    TransactionPayload::ScriptFunction(ScriptFunction::new(
+        ModuleId::new(
+            aptos_root_address(),
+            Identifier::new(BENCHMARK_MODULE_NAME).unwrap(),
+        ),
+        Identifier::new(function).unwrap(),
+        vec![],
+        args,
+    ))
+}
@@ -0,0 +1,275 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, Result};
+use aptos_parallel_executor::counters::{
+    PARALLEL_EXECUTION_ABORTS, PARALLEL_EXECUTION_DEPENDENCY_WAITS,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{ExecutionStatus, ModuleBundle, Transaction, TransactionStatus},
+};
+use aptos_vm::{parallel_executor::ParallelAptosVM, AptosVM};
+use move_deps::{
+    move_command_line_common::env::get_bytecode_version_from_env, move_package::BuildConfig,
+};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use storage_interface::{state_view::LatestDbStateCheckpointView, DbReader};
+use structopt::StructOpt;
+
+const BENCHMARK_PACKAGE_DIR: &str = "benchmark-package";
+pub(crate) const BENCHMARK_MODULE_NAME: &str = "Benchmark";
+/// Number of distinct resources the benchmark module can write for an account.
+const MAX_RESOURCES_PER_TXN: u64 = 16;
+
+/// The kinds of transactions a block can be made of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Workload {
+    /// Coin transfers between random pairs of accounts.
+    Transfer,
+    /// Coin transfers, a share of which go to a few hotspot accounts.
+    Hotspot,
+    /// Token minting in the collections of a set of creators.
+    TokenMint,
+    /// Inserts into a `Table` owned by the sender.
+    TableInsert,
+    /// Writes to many resources of the sender.
+    ResourceWrites,
+}
+
+impl Workload {
+    /// Whether the workload calls the modules of the benchmark package.
+    pub(crate) fn needs_benchmark_package(&self) -> bool {
+        matches!(self, Workload::TableInsert | Workload::ResourceWrites)
+    }
+}
+
+impl FromStr for Workload {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "transfer" => Workload::Transfer,
+            "hotspot" => Workload::Hotspot,
+            "token-mint" => Workload::TokenMint,
+            "table-insert" => Workload::TableInsert,
+            "resource-writes" => Workload::ResourceWrites,
+            _ => bail!(
+                "Invalid workload '{}', expected one of transfer, hotspot, token-mint, \
+                 table-insert, resource-writes",
+                s
+            ),
+        })
+    }
+}
+
+impl fmt::Display for Workload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Workload::Transfer => "transfer",
+            Workload::Hotspot => "hotspot",
+            Workload::TokenMint => "token-mint",
+            Workload::TableInsert => "table-insert",
+            Workload::ResourceWrites => "resource-writes",
+        })
+    }
+}
+
+#[derive(Clone, Debug, StructOpt)]
+pub struct WorkloadOptions {
+    #[structopt(
+        long,
+        default_value = "10",
+        parse(try_from_str = parse_num_hotspots),
+        help = "Number of hotspot accounts of the hotspot workload"
+    )]
+    pub num_hotspots: usize,
+
+    #[structopt(
+        long,
+        default_value = "0.5",
+        parse(try_from_str = parse_ratio),
+        help = "Share of the hotspot workload transfers going to a hotspot account"
+    )]
+    pub conflict_ratio: f64,
+
+    #[structopt(
+        long,
+        default_value = "1000",
+        help = "Number of accounts creating tokens in the token-mint workload"
+    )]
+    pub num_token_creators: usize,
+
+    #[structopt(
+        long,
+        default_value = "10",
+        help = "Number of table items inserted per transaction of the table-insert workload"
+    )]
+    pub table_inserts_per_txn: u64,
+
+    #[structopt(
+        long,
+        default_value = "10",
+        parse(try_from_str = parse_resources_per_txn),
+        help = "Number of resources written per transaction of the resource-writes workload"
+    )]
+    pub resources_per_txn: u64,
+}
+
+fn parse_ratio(s: &str) -> Result<f64> {
+    let ratio = f64::from_str(s)?;
+    if !(0.0..=1.0).contains(&ratio) {
+        bail!("Ratio {} should be between 0 and 1", ratio);
+    }
+    Ok(ratio)
+}
+
+fn parse_num_hotspots(s: &str) -> Result<usize> {
+    let num_hotspots = usize::from_str(s)?;
+    if num_hotspots == 0 {
+        bail!("The hotspot workload needs at least one hotspot account");
+    }
+    Ok(num_hotspots)
+}
+
+fn parse_resources_per_txn(s: &str) -> Result<u64> {
+    let resources = u64::from_str(s)?;
+    if resources > MAX_RESOURCES_PER_TXN {
+        bail!(
+            "At most {} resources can be written per transaction",
+            MAX_RESOURCES_PER_TXN
+        );
+    }
+    Ok(resources)
+}
+
+/// Execution results of the blocks of a workload.
+#[derive(Clone, Debug)]
+pub struct WorkloadReport {
+    pub workload: Workload,
+    pub num_txns: usize,
+    /// Transactions which weren't kept with a success status by the sequential execution.
+    pub num_failed_txns: usize,
+    pub sequential_time: Duration,
+    /// `None` when the concurrency level doesn't allow parallel execution.
+    pub parallel_time: Option<Duration>,
+    /// Incarnations aborted by the parallel executor after a validation failure, each of which is
+    /// re-executed.
+    pub aborts: u64,
+    /// Reads of the parallel executor waiting on a dependency on an estimated write.
+    pub dependency_waits: u64,
+}
+
+impl WorkloadReport {
+    pub fn new(workload: Workload) -> Self {
+        Self {
+            workload,
+            num_txns: 0,
+            num_failed_txns: 0,
+            sequential_time: Duration::default(),
+            parallel_time: None,
+            aborts: 0,
+            dependency_waits: 0,
+        }
+    }
+
+    pub fn speedup(&self) -> Option<f64> {
+        self.parallel_time
+            .map(|parallel_time| self.sequential_time.as_secs_f64() / parallel_time.as_secs_f64())
+    }
+
+    /// Executes the block both sequentially and in parallel on top of the latest state
+    /// checkpoint, without committing it.
+    pub(crate) fn measure_block(
+        &mut self,
+        db: &Arc<dyn DbReader>,
+        block: &[Transaction],
+        concurrency_level: usize,
+    ) {
+        let state_view = db.latest_state_checkpoint_view().unwrap();
+
+        let sequential_start = Instant::now();
+        let outputs =
+            AptosVM::execute_block_and_keep_vm_status(block.to_vec(), &state_view).unwrap();
+        self.sequential_time += sequential_start.elapsed();
+        self.num_txns += block.len();
+        self.num_failed_txns += outputs
+            .iter()
+            .filter(|(_, output)| {
+                output.status() != &TransactionStatus::Keep(ExecutionStatus::Success)
+            })
+            .count();
+
+        // The parallel executor can't run on a single thread.
+        if concurrency_level > 1 {
+            let aborts = PARALLEL_EXECUTION_ABORTS.get();
+            let dependency_waits = PARALLEL_EXECUTION_DEPENDENCY_WAITS.get();
+            let parallel_start = Instant::now();
+            ParallelAptosVM::execute_block(block.to_vec(), &state_view, concurrency_level).unwrap();
+            *self.parallel_time.get_or_insert_with(Duration::default) += parallel_start.elapsed();
+            self.aborts += PARALLEL_EXECUTION_ABORTS.get() - aborts;
+            self.dependency_waits += PARALLEL_EXECUTION_DEPENDENCY_WAITS.get() - dependency_waits;
+        }
+    }
+}
+
+impl fmt::Display for WorkloadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<16} txns: {:>8}, failed: {:>6}, sequential TPS: {:>8.0}",
+            self.workload.to_string(),
+            self.num_txns,
+            self.num_failed_txns,
+            self.num_txns as f64 / self.sequential_time.as_secs_f64(),
+        )?;
+        match (self.parallel_time, self.speedup()) {
+            (Some(parallel_time), Some(speedup)) => write!(
+                f,
+                ", parallel TPS: {:>8.0}, speedup: {:>5.2}x, aborts and re-executions: {}, \
+                 dependency waits: {}",
+                self.num_txns as f64 / parallel_time.as_secs_f64(),
+                speedup,
+                self.aborts,
+                self.dependency_waits,
+            ),
+            _ => write!(f, ", parallel execution disabled"),
+        }
+    }
+}
+
+/// Compiles the benchmark package, with its modules published at `address`.
+pub(crate) fn compile_benchmark_package(address: AccountAddress) -> ModuleBundle {
+    let install_dir = tempfile::tempdir().unwrap();
+    let mut named_addresses = BTreeMap::new();
+    named_addresses.insert("Benchmark".to_string(), address);
+    let build_config = BuildConfig {
+        additional_named_addresses: named_addresses,
+        install_dir: Some(install_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let package = build_config
+        .compile_package(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join(BENCHMARK_PACKAGE_DIR),
+            &mut Vec::new(),
+        )
+        .expect("Benchmark package should compile.");
+    ModuleBundle::new(
+        package
+            .root_compiled_units
+            .iter()
+            .map(|unit_with_source| {
+                unit_with_source
+                    .unit
+                    .serialize(get_bytecode_version_from_env())
+            })
+            .collect(),
+    )
+}
@@ -64,6 +64,11 @@ impl PriorityIndex {
         self.data.iter().rev()
     }
 
+    /// Iterates over the transactions starting from the lowest ranked one.
+    pub(crate) fn iter_lowest(&self) -> Iter<OrderedQueueKey> {
+        self.data.iter()
+    }
+
     pub(crate) fn size(&self) -> usize {
         self.data.len()
     }
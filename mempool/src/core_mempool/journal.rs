@@ -0,0 +1,308 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Write-ahead journal of the transactions accepted into and removed from mempool, so that the
+//! pending transactions can be restored after a restart.
+//!
+//! The journal is a sequence of BCS serialized records, each prefixed with its length as a little
+//! endian `u32`.  A record cut short by a crash, or corrupted, ends the journal: it's truncated
+//! there when opened, so that new records aren't appended after unreadable bytes.
+//!
+//! The records are written by a dedicated thread, so that mempool doesn't wait on the disk while
+//! holding its lock.
+
+use crate::{
+    core_mempool::transaction::{MempoolTransaction, TimelineState},
+    counters,
+    logging::{LogEntry, LogSchema},
+};
+use anyhow::{Context, Result};
+use aptos_logger::prelude::*;
+use aptos_types::{account_address::AccountAddress, transaction::SignedTransaction};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{self, File, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+/// Largest record read back from the journal. The gas schedule caps transactions at 256 KiB, a
+/// longer record can only come from a corrupted length prefix.
+const MAX_RECORD_SIZE: usize = 512 * 1024;
+
+type JournaledTransactions = BTreeMap<(AccountAddress, u64), JournaledTransaction>;
+
+#[derive(Deserialize, Serialize)]
+enum JournalRecord {
+    Accepted(JournaledTransaction),
+    /// The transaction was removed from mempool, e.g. committed, rejected, evicted or expired
+    Removed {
+        sender: AccountAddress,
+        sequence_number: u64,
+    },
+}
+
+/// A transaction as it was accepted into mempool
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct JournaledTransaction {
+    pub txn: SignedTransaction,
+    /// System expiration time of the transaction, it's dropped after that time even if it isn't
+    /// expired for its sender
+    pub expiration_time: Duration,
+    pub timeline_state: TimelineState,
+}
+
+impl From<&MempoolTransaction> for JournaledTransaction {
+    fn from(txn: &MempoolTransaction) -> Self {
+        Self {
+            txn: txn.txn.clone(),
+            expiration_time: txn.expiration_time,
+            // The position in the timeline is only meaningful for this run of the node.
+            timeline_state: match txn.timeline_state {
+                TimelineState::Ready(_) => TimelineState::NotReady,
+                state => state,
+            },
+        }
+    }
+}
+
+enum JournalCommand {
+    Append(JournalRecord),
+    Compact,
+}
+
+/// Handle to the journal, whose records are written in the background by the journal thread.
+pub struct Journal {
+    sender: Option<Sender<JournalCommand>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl Journal {
+    /// Opens the journal at `path`, creating it if needed, and returns the transactions which
+    /// were still in mempool according to its records. The journal is compacted in the
+    /// background every `compaction_threshold` appended records.
+    pub fn open(
+        path: &Path,
+        compaction_threshold: usize,
+    ) -> Result<(Self, Vec<JournaledTransaction>)> {
+        let (transactions, valid_len) = match File::open(path) {
+            Ok(file) => read_records(path, file)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => (BTreeMap::new(), 0),
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() > valid_len {
+            warn!(
+                "Truncating mempool journal {:?} after its last valid record, at {} bytes",
+                path, valid_len
+            );
+            file.set_len(valid_len)?;
+        }
+
+        let journaled_transactions = transactions.values().cloned().collect();
+        let mut writer = JournalWriter {
+            path: path.to_path_buf(),
+            file: BufWriter::new(file),
+            transactions,
+            num_appended_records: 0,
+            compaction_threshold,
+        };
+        let (sender, receiver) = mpsc::channel();
+        let writer = thread::Builder::new()
+            .name("mempool-journal".into())
+            .spawn(move || writer.run(receiver))
+            .context("Failed to spawn the mempool journal thread")?;
+        Ok((
+            Self {
+                sender: Some(sender),
+                writer: Some(writer),
+            },
+            journaled_transactions,
+        ))
+    }
+
+    pub fn record_accepted(&self, txn: JournaledTransaction) {
+        self.send(JournalCommand::Append(JournalRecord::Accepted(txn)));
+    }
+
+    pub fn record_removed(&self, sender: AccountAddress, sequence_number: u64) {
+        self.send(JournalCommand::Append(JournalRecord::Removed {
+            sender,
+            sequence_number,
+        }));
+    }
+
+    /// Replaces the records of the journal with the acceptance of the transactions still in
+    /// mempool.
+    pub fn compact(&self) {
+        self.send(JournalCommand::Compact);
+    }
+
+    fn send(&self, command: JournalCommand) {
+        if let Some(sender) = self.sender.as_ref() {
+            // The journal thread only exits once the journal is dropped.
+            let _ = sender.send(command);
+        }
+    }
+}
+
+impl Drop for Journal {
+    /// Writes the pending records before returning, so that the journal can be opened again.
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(writer) = self.writer.take() {
+            if writer.join().is_err() {
+                error!(
+                    LogSchema::new(LogEntry::Journal),
+                    "The mempool journal thread panicked"
+                );
+            }
+        }
+    }
+}
+
+struct JournalWriter {
+    path: PathBuf,
+    file: BufWriter<File>,
+    // Transactions accepted and not removed since, which a compaction keeps.
+    transactions: JournaledTransactions,
+    // Number of records appended since the journal was last compacted.
+    num_appended_records: usize,
+    compaction_threshold: usize,
+}
+
+impl JournalWriter {
+    fn run(&mut self, receiver: Receiver<JournalCommand>) {
+        while let Ok(command) = receiver.recv() {
+            self.handle(command);
+            // Flush once the queued commands are handled, so that bursts of records are written
+            // together.
+            while let Ok(command) = receiver.try_recv() {
+                self.handle(command);
+            }
+            log_error(self.file.flush().map_err(Into::into));
+        }
+    }
+
+    fn handle(&mut self, command: JournalCommand) {
+        let result = match command {
+            JournalCommand::Append(record) => self.append(record),
+            JournalCommand::Compact => self.compact(),
+        };
+        log_error(result);
+    }
+
+    fn append(&mut self, record: JournalRecord) -> Result<()> {
+        write_record(&mut self.file, &record)?;
+        apply_record(&mut self.transactions, record);
+        self.num_appended_records += 1;
+        if self.num_appended_records >= self.compaction_threshold {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    /// The new journal is written next to the current one and then renamed over it, so that a
+    /// crash in the middle of the compaction doesn't lose the journal.
+    fn compact(&mut self) -> Result<()> {
+        self.file.flush()?;
+        let compacted_path = self.path.with_extension("compacted");
+        {
+            let mut writer = BufWriter::new(File::create(&compacted_path)?);
+            for txn in self.transactions.values() {
+                write_record(&mut writer, &JournalRecord::Accepted(txn.clone()))?;
+            }
+            writer.into_inner()?.sync_all()?;
+        }
+        fs::rename(&compacted_path, &self.path)?;
+        self.file = BufWriter::new(OpenOptions::new().append(true).open(&self.path)?);
+        self.num_appended_records = 0;
+        Ok(())
+    }
+}
+
+/// Failures are only logged, mempool keeps working without persistence.
+fn log_error(result: Result<()>) {
+    if let Err(err) = result {
+        counters::CORE_MEMPOOL_JOURNAL_ERRORS.inc();
+        error!(LogSchema::new(LogEntry::Journal).error(&err));
+    }
+}
+
+fn write_record(writer: &mut impl Write, record: &JournalRecord) -> Result<()> {
+    let bytes = bcs::to_bytes(record)?;
+    let mut buffer = Vec::with_capacity(4 + bytes.len());
+    buffer.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(&bytes);
+    writer.write_all(&buffer)?;
+    Ok(())
+}
+
+fn apply_record(transactions: &mut JournaledTransactions, record: JournalRecord) {
+    match record {
+        JournalRecord::Accepted(txn) => {
+            transactions.insert((txn.txn.sender(), txn.txn.sequence_number()), txn);
+        }
+        JournalRecord::Removed {
+            sender,
+            sequence_number,
+        } => {
+            transactions.remove(&(sender, sequence_number));
+        }
+    }
+}
+
+/// Applies the records of the journal, returning the transactions accepted and not removed since,
+/// and the length of the journal up to the end of its last valid record.
+fn read_records(path: &Path, file: File) -> Result<(JournaledTransactions, u64)> {
+    let mut reader = BufReader::new(file);
+    let mut transactions = BTreeMap::new();
+    let mut valid_len = 0;
+    loop {
+        let mut length = [0u8; 4];
+        match reader.read_exact(&mut length) {
+            Ok(()) => (),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        let length = u32::from_le_bytes(length) as usize;
+        if length > MAX_RECORD_SIZE {
+            error!(
+                "Ignoring the records of mempool journal {:?} after one of {} bytes",
+                path, length
+            );
+            break;
+        }
+        let mut bytes = vec![0u8; length];
+        match reader.read_exact(&mut bytes) {
+            Ok(()) => (),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => {
+                warn!(
+                    "Ignoring the incomplete last record of mempool journal {:?}",
+                    path
+                );
+                break;
+            }
+            Err(err) => return Err(err.into()),
+        }
+        match bcs::from_bytes(&bytes) {
+            Ok(record) => apply_record(&mut transactions, record),
+            Err(err) => {
+                error!(
+                    "Ignoring the records of mempool journal {:?} after a corrupted one: {}",
+                    path, err
+                );
+                break;
+            }
+        }
+        valid_len += (4 + length) as u64;
+    }
+    Ok((transactions, valid_len))
+}
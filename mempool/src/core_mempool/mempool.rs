@@ -6,14 +6,19 @@
 use crate::{
     core_mempool::{
         index::TxnPointer,
-        transaction::{MempoolTransaction, TimelineState},
+        journal::{Journal, JournaledTransaction},
+        transaction::{
+            trace_timestamp_usecs, AckEvent, BroadcastEvent, MempoolTransaction, ReceiveEvent,
+            RepeatedEvent, TimelineState, TransactionTrace,
+        },
         transaction_store::TransactionStore,
         ttl_cache::TtlCache,
     },
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
+    shared_mempool::types::{MempoolStats, MempoolTransactionStatus},
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::{config::NodeConfig, network_id::PeerNetworkId};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_types::{
@@ -24,10 +29,13 @@ use aptos_types::{
 };
 use std::{
     cmp::max,
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     time::{Duration, SystemTime},
 };
 
+/// How long the trace of a transaction is kept after it was committed or rejected.
+const COMPLETED_TRACE_TIMEOUT_SECS: u64 = 600;
+
 pub struct Mempool {
     // Stores the metadata of all transactions in mempool (of all states).
     transactions: TransactionStore,
@@ -37,18 +45,45 @@ pub struct Mempool {
     // This is used to measure e2e latency of transactions in the system, as well as the time it
     // takes to pick it up by consensus.
     pub(crate) metrics_cache: TtlCache<(AccountAddress, u64), SystemTime>,
+    // Traces of the transactions committed or rejected recently, by hash.
+    completed_traces: TtlCache<HashValue, TransactionTrace>,
+    trace_transactions: bool,
     pub system_transaction_timeout: Duration,
+
+    journal: Option<Journal>,
+    // Transactions read back from the journal on startup, until they are re-submitted.
+    journaled_transactions: Vec<JournaledTransaction>,
+    max_sender_batch_share_percent: Option<u64>,
 }
 
 impl Mempool {
     pub fn new(config: &NodeConfig) -> Self {
+        let (journal, journaled_transactions) = match config.mempool.journal_path() {
+            Some(path) => {
+                let (journal, transactions) =
+                    Journal::open(&path, config.mempool.journal_compaction_threshold)
+                        .unwrap_or_else(|err| {
+                            panic!("Failed to open mempool journal {:?}: {}", path, err)
+                        });
+                (Some(journal), transactions)
+            }
+            None => (None, vec![]),
+        };
         Mempool {
             transactions: TransactionStore::new(&config.mempool),
             sequence_number_cache: TtlCache::new(config.mempool.capacity, Duration::from_secs(100)),
             metrics_cache: TtlCache::new(config.mempool.capacity, Duration::from_secs(100)),
+            completed_traces: TtlCache::new(
+                config.mempool.capacity,
+                Duration::from_secs(COMPLETED_TRACE_TIMEOUT_SECS),
+            ),
+            trace_transactions: config.mempool.trace_transactions,
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
+            journal,
+            journaled_transactions,
+            max_sender_batch_share_percent: config.mempool.max_sender_batch_share_percent,
         }
     }
 
@@ -70,6 +105,17 @@ impl Mempool {
         };
         self.log_latency(*sender, sequence_number, metric_label);
         self.metrics_cache.remove(&(*sender, sequence_number));
+        if self.trace_transactions {
+            if let Some((hash, mut trace)) = self.transactions.take_trace(sender, sequence_number) {
+                let timestamp_usecs = Some(trace_timestamp_usecs());
+                if is_rejected {
+                    trace.rejected_usecs = timestamp_usecs;
+                } else {
+                    trace.committed_usecs = timestamp_usecs;
+                }
+                self.completed_traces.insert(hash, trace);
+            }
+        }
 
         let current_seq_number = self
             .sequence_number_cache
@@ -80,6 +126,7 @@ impl Mempool {
             if sequence_number >= current_seq_number {
                 self.transactions
                     .reject_transaction(sender, sequence_number);
+                self.journal_removed_txns();
             }
         } else {
             let new_seq_number = max(current_seq_number, sequence_number + 1);
@@ -106,9 +153,44 @@ impl Mempool {
             self.sequence_number_cache
                 .insert(*sender, new_seq_number.min_seq());
             self.transactions.commit_transaction(sender, new_seq_number);
+            self.journal_removed_txns();
+        }
+    }
+
+    /// Journals the removal of the transactions dropped from the store since the last call,
+    /// whatever the reason: commit, rejection, replacement, eviction or expiration.
+    fn journal_removed_txns(&mut self) {
+        let removed_txns = self.transactions.take_removed_txns();
+        if let Some(journal) = self.journal.as_ref() {
+            for (sender, sequence_number) in removed_txns {
+                journal.record_removed(sender, sequence_number);
+            }
+        }
+    }
+
+    /// Journals the removal of transactions which never made it back into mempool after being
+    /// restored from the journal.
+    pub(crate) fn journal_dropped_txns(&self, txns: impl IntoIterator<Item = TxnPointer>) {
+        if let Some(journal) = self.journal.as_ref() {
+            for (sender, sequence_number) in txns {
+                journal.record_removed(sender, sequence_number);
+            }
+        }
+    }
+
+    /// Rewrites the journal with only the transactions currently in mempool.
+    pub(crate) fn compact_journal(&self) {
+        if let Some(journal) = self.journal.as_ref() {
+            journal.compact();
         }
     }
 
+    /// Returns the transactions restored from the journal on startup, which still have to be
+    /// validated and added back.
+    pub(crate) fn take_journaled_transactions(&mut self) -> Vec<JournaledTransaction> {
+        std::mem::take(&mut self.journaled_transactions)
+    }
+
     fn log_latency(&self, account: AccountAddress, sequence_number: u64, metric: &str) {
         if let Some(&creation_time) = self.metrics_cache.get(&(account, sequence_number)) {
             if let Ok(time_delta) = SystemTime::now().duration_since(creation_time) {
@@ -123,6 +205,103 @@ impl Mempool {
         self.transactions.get_by_hash(hash)
     }
 
+    /// Fetches the trace of a transaction in mempool or recently committed or rejected.
+    pub(crate) fn get_trace(&self, hash: HashValue) -> Option<TransactionTrace> {
+        if !self.trace_transactions {
+            return None;
+        }
+        self.transactions
+            .get_trace_by_hash(hash)
+            .or_else(|| self.completed_traces.get(&hash).cloned())
+    }
+
+    /// Records the reception and validation of a transaction, unless it was already received.
+    pub(crate) fn trace_received(
+        &mut self,
+        txn: &SignedTransaction,
+        received: ReceiveEvent,
+        validated_usecs: u64,
+    ) {
+        if !self.trace_transactions {
+            return;
+        }
+        self.transactions
+            .update_trace(&txn.sender(), txn.sequence_number(), |trace| {
+                if trace.received.is_none() {
+                    trace.received = Some(received);
+                    trace.validated_usecs = Some(validated_usecs);
+                }
+            });
+    }
+
+    /// Records the broadcast to `peer` of the transactions in the timeline from `start_id`
+    /// (exclusive) to `end_id` (inclusive).
+    pub(crate) fn trace_broadcast(
+        &mut self,
+        start_id: u64,
+        end_id: u64,
+        peer: PeerNetworkId,
+        backoff: bool,
+    ) {
+        if !self.trace_transactions {
+            return;
+        }
+        let timestamp_usecs = trace_timestamp_usecs();
+        self.transactions
+            .update_timeline_range_traces(start_id, end_id, |trace| {
+                RepeatedEvent::record(
+                    &mut trace.broadcasts,
+                    BroadcastEvent {
+                        timestamp_usecs,
+                        peer,
+                        backoff,
+                    },
+                )
+            });
+    }
+
+    /// Records the ACK from `peer` of a broadcast of the transactions in the timeline from
+    /// `start_id` (exclusive) to `end_id` (inclusive).
+    pub(crate) fn trace_ack(
+        &mut self,
+        start_id: u64,
+        end_id: u64,
+        peer: PeerNetworkId,
+        retry: bool,
+        backoff: bool,
+    ) {
+        if !self.trace_transactions {
+            return;
+        }
+        let timestamp_usecs = trace_timestamp_usecs();
+        self.transactions
+            .update_timeline_range_traces(start_id, end_id, |trace| {
+                RepeatedEvent::record(
+                    &mut trace.acks,
+                    AckEvent {
+                        timestamp_usecs,
+                        peer,
+                        retry,
+                        backoff,
+                    },
+                )
+            });
+    }
+
+    /// Records that the transactions were pulled in a batch for consensus.
+    pub(crate) fn trace_pulled_by_consensus(&mut self, txns: &[SignedTransaction]) {
+        if !self.trace_transactions {
+            return;
+        }
+        let timestamp_usecs = trace_timestamp_usecs();
+        for txn in txns {
+            self.transactions
+                .update_trace(&txn.sender(), txn.sequence_number(), |trace| {
+                    RepeatedEvent::record(&mut trace.pulled_by_consensus_usecs, timestamp_usecs)
+                });
+        }
+    }
+
     /// Used to add a transaction to the Mempool.
     /// Performs basic validation: checks account's sequence number.
     pub(crate) fn add_txn(
@@ -173,8 +352,20 @@ impl Mempool {
             timeline_state,
             sequence_number,
         );
+        let journaled_txn = self
+            .journal
+            .is_some()
+            .then(|| JournaledTransaction::from(&txn_info));
 
-        self.transactions.insert(txn_info)
+        let status = self.transactions.insert(txn_info);
+        // The removals come first, a replaced transaction has the same sender and sequence number.
+        self.journal_removed_txns();
+        if let (Some(journal), Some(journaled_txn)) = (self.journal.as_ref(), journaled_txn) {
+            if status.code == MempoolStatusCode::Accepted {
+                journal.record_accepted(journaled_txn);
+            }
+        }
+        status
     }
 
     /// Fetches next block of transactions for consensus.
@@ -197,6 +388,12 @@ impl Mempool {
         let mut skipped = HashSet::new();
         let seen_size = seen.len();
         let mut txn_walked = 0usize;
+        // In fairness mode, a sender can only fill a share of the batch, so that a single one
+        // can't starve the others.
+        let max_txns_per_sender = self
+            .max_sender_batch_share_percent
+            .map(|percent| max(batch_size.saturating_mul(percent) / 100, 1));
+        let mut txns_per_sender = HashMap::new();
         // iterate over the queue of transactions based on gas price
         'main: for txn in self.transactions.iter_queue() {
             txn_walked += 1;
@@ -214,6 +411,9 @@ impl Mempool {
                 || account_sequence_number == Some(&tx_seq)
                 || matches!(account_seqtype, AccountSequenceInfo::CRSN { .. })
             {
+                if !take_sender_slot(&mut txns_per_sender, max_txns_per_sender, txn.address) {
+                    continue;
+                }
                 let ptr = TxnPointer::from(txn);
                 seen.insert(ptr);
                 result.push(ptr);
@@ -224,7 +424,9 @@ impl Mempool {
                 // check if we can now include some transactions
                 // that were skipped before for given account
                 let mut skipped_txn = (txn.address, tx_seq + 1);
-                while skipped.contains(&skipped_txn) {
+                while skipped.contains(&skipped_txn)
+                    && take_sender_slot(&mut txns_per_sender, max_txns_per_sender, txn.address)
+                {
                     seen.insert(skipped_txn);
                     result.push(skipped_txn);
                     if (result.len() as u64) == batch_size {
@@ -271,7 +473,9 @@ impl Mempool {
     pub(crate) fn gc(&mut self) {
         let now = SystemTime::now();
         self.transactions.gc_by_system_ttl(&self.metrics_cache);
+        self.journal_removed_txns();
         self.metrics_cache.gc(now);
+        self.completed_traces.gc(now);
         self.sequence_number_cache.gc(now);
     }
 
@@ -279,6 +483,7 @@ impl Mempool {
     pub(crate) fn gc_by_expiration_time(&mut self, block_time: Duration) {
         self.transactions
             .gc_by_expiration_time(block_time, &self.metrics_cache);
+        self.journal_removed_txns();
     }
 
     /// Read `count` transactions from timeline since `timeline_id`.
@@ -304,4 +509,35 @@ impl Mempool {
     pub fn get_parking_lot_size(&self) -> usize {
         self.transactions.get_parking_lot_size()
     }
+
+    /// Returns the transactions of `account` currently in mempool, ordered by sequence number.
+    pub(crate) fn get_account_transactions(
+        &self,
+        account: &AccountAddress,
+    ) -> Vec<(SignedTransaction, MempoolTransactionStatus)> {
+        self.transactions.get_account_transactions(account)
+    }
+
+    pub(crate) fn get_stats(&self) -> MempoolStats {
+        self.transactions.get_stats()
+    }
+}
+
+/// Counts a transaction of `sender` in the batch, unless the sender already reached its share of
+/// the batch.
+fn take_sender_slot(
+    txns_per_sender: &mut HashMap<AccountAddress, u64>,
+    max_txns_per_sender: Option<u64>,
+    sender: AccountAddress,
+) -> bool {
+    let max_txns = match max_txns_per_sender {
+        Some(max_txns) => max_txns,
+        None => return true,
+    };
+    let txns = txns_per_sender.entry(sender).or_insert(0);
+    if *txns >= max_txns {
+        return false;
+    }
+    *txns += 1;
+    true
 }
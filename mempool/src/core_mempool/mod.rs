@@ -2,11 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod index;
+mod journal;
 mod mempool;
 mod transaction;
 mod transaction_store;
 mod ttl_cache;
 
+pub(crate) use self::transaction::trace_timestamp_usecs;
 #[cfg(test)]
 pub use self::ttl_cache::TtlCache;
-pub use self::{index::TxnPointer, mempool::Mempool as CoreMempool, transaction::TimelineState};
+pub use self::{
+    index::TxnPointer,
+    mempool::Mempool as CoreMempool,
+    transaction::{
+        AckEvent, BroadcastEvent, ReceiveEvent, RepeatedEvent, TimelineState, TransactionTrace,
+    },
+};
@@ -1,6 +1,7 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_config::network_id::PeerNetworkId;
 use aptos_crypto::HashValue;
 use aptos_types::{
     account_address::AccountAddress, account_config::AccountSequenceInfo,
@@ -18,6 +19,7 @@ pub struct MempoolTransaction {
     pub ranking_score: u64,
     pub timeline_state: TimelineState,
     pub sequence_info: SequenceInfo,
+    pub trace: TransactionTrace,
 }
 
 impl MempoolTransaction {
@@ -39,6 +41,7 @@ impl MempoolTransaction {
             gas_amount,
             ranking_score,
             timeline_state,
+            trace: TransactionTrace::default(),
         }
     }
     pub(crate) fn get_sender(&self) -> AccountAddress {
@@ -69,3 +72,75 @@ pub struct SequenceInfo {
     pub transaction_sequence_number: u64,
     pub account_sequence_number_type: AccountSequenceInfo,
 }
+
+/// Timestamps, in microseconds since the Unix epoch, of the events in the life of a transaction in
+/// mempool.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct TransactionTrace {
+    pub received: Option<ReceiveEvent>,
+    /// When the VM validation of the transaction completed
+    pub validated_usecs: Option<u64>,
+    pub broadcasts: Option<RepeatedEvent<BroadcastEvent>>,
+    pub acks: Option<RepeatedEvent<AckEvent>>,
+    /// When the transaction was pulled in a batch for consensus, which can happen several times
+    pub pulled_by_consensus_usecs: Option<RepeatedEvent<u64>>,
+    pub committed_usecs: Option<u64>,
+    pub rejected_usecs: Option<u64>,
+}
+
+/// An event which can happen many times in the life of a transaction, summarized by its first and
+/// last occurrences so that the trace stays bounded.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct RepeatedEvent<T> {
+    pub count: u64,
+    pub first: T,
+    pub last: T,
+}
+
+impl<T: Clone> RepeatedEvent<T> {
+    pub(crate) fn record(events: &mut Option<Self>, event: T) {
+        match events {
+            Some(events) => {
+                events.count += 1;
+                events.last = event;
+            }
+            None => {
+                *events = Some(Self {
+                    count: 1,
+                    first: event.clone(),
+                    last: event,
+                })
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct ReceiveEvent {
+    pub timestamp_usecs: u64,
+    /// The peer which broadcast the transaction, `None` if it was submitted by a client
+    pub peer: Option<PeerNetworkId>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct BroadcastEvent {
+    pub timestamp_usecs: u64,
+    pub peer: PeerNetworkId,
+    /// Whether the broadcast was delayed because the peer asked to back off
+    pub backoff: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct AckEvent {
+    pub timestamp_usecs: u64,
+    pub peer: PeerNetworkId,
+    /// Whether the peer asked for the batch to be broadcast again
+    pub retry: bool,
+    /// Whether the peer asked to back off
+    pub backoff: bool,
+}
+
+/// Current time in microseconds since the Unix epoch, as recorded in `TransactionTrace`.
+pub(crate) fn trace_timestamp_usecs() -> u64 {
+    aptos_infallible::duration_since_epoch().as_micros() as u64
+}
@@ -5,13 +5,14 @@ use crate::{
     core_mempool::{
         index::{
             AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
-            TimelineIndex,
+            TimelineIndex, TxnPointer,
         },
-        transaction::{MempoolTransaction, TimelineState},
+        transaction::{MempoolTransaction, TimelineState, TransactionTrace},
         ttl_cache::TtlCache,
     },
     counters,
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
+    shared_mempool::types::{MempoolStats, MempoolTransactionStatus},
 };
 use aptos_config::config::MempoolConfig;
 use aptos_crypto::HashValue;
@@ -23,7 +24,9 @@ use aptos_types::{
     transaction::SignedTransaction,
 };
 use std::{
+    cmp::max,
     collections::HashMap,
+    convert::TryFrom,
     ops::Bound,
     time::{Duration, SystemTime},
 };
@@ -52,9 +55,15 @@ pub struct TransactionStore {
     // one valid hash.
     hash_index: HashMap<HashValue, (AccountAddress, u64)>,
 
+    // Transactions removed since the last call to `take_removed_txns`, only tracked for the
+    // mempool journal.
+    removed_txns: Option<Vec<TxnPointer>>,
+
     // configuration
     capacity: usize,
     capacity_per_user: usize,
+    replacement_gas_price_bump_percent: u64,
+    evict_lower_ranked_txns: bool,
 }
 
 impl TransactionStore {
@@ -72,10 +81,13 @@ impl TransactionStore {
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
             hash_index: HashMap::new(),
+            removed_txns: config.journal_path.is_some().then(Vec::new),
 
             // configuration
             capacity: config.capacity,
             capacity_per_user: config.capacity_per_user,
+            replacement_gas_price_bump_percent: config.replacement_gas_price_bump_percent,
+            evict_lower_ranked_txns: config.evict_lower_ranked_txns,
         }
     }
 
@@ -114,6 +126,56 @@ impl TransactionStore {
             .cloned()
     }
 
+    pub(crate) fn get_trace_by_hash(&self, hash: HashValue) -> Option<TransactionTrace> {
+        let (address, sequence_number) = self.hash_index.get(&hash)?;
+        self.transactions
+            .get(address)
+            .and_then(|txns| txns.get(sequence_number))
+            .map(|txn| txn.trace.clone())
+    }
+
+    /// Takes the trace of a transaction out of the store, along with the transaction hash.
+    pub(crate) fn take_trace(
+        &mut self,
+        address: &AccountAddress,
+        sequence_number: u64,
+    ) -> Option<(HashValue, TransactionTrace)> {
+        let txn = self
+            .transactions
+            .get_mut(address)
+            .and_then(|txns| txns.get_mut(&sequence_number))?;
+        Some((txn.get_committed_hash(), std::mem::take(&mut txn.trace)))
+    }
+
+    /// Applies `update` to the trace of a transaction, if it's in the store.
+    pub(crate) fn update_trace(
+        &mut self,
+        address: &AccountAddress,
+        sequence_number: u64,
+        update: impl FnOnce(&mut TransactionTrace),
+    ) {
+        if let Some(txn) = self
+            .transactions
+            .get_mut(address)
+            .and_then(|txns| txns.get_mut(&sequence_number))
+        {
+            update(&mut txn.trace);
+        }
+    }
+
+    /// Applies `update` to the traces of the transactions in the timeline from `start_id`
+    /// (exclusive) to `end_id` (inclusive).
+    pub(crate) fn update_timeline_range_traces(
+        &mut self,
+        start_id: u64,
+        end_id: u64,
+        mut update: impl FnMut(&mut TransactionTrace),
+    ) {
+        for (address, sequence_number) in self.timeline_index.timeline_range(start_id, end_id) {
+            self.update_trace(&address, sequence_number, &mut update);
+        }
+    }
+
     /// Insert transaction into TransactionStore. Performs validation checks and updates indexes.
     pub(crate) fn insert(&mut self, txn: MempoolTransaction) -> MempoolStatus {
         let address = txn.get_sender();
@@ -121,35 +183,42 @@ impl TransactionStore {
 
         // check if transaction is already present in Mempool
         // e.g. given request is update
-        // we allow increase in gas price to speed up process.
+        // we allow a transaction to be replaced by one with a high enough gas price, e.g. to
+        // speed it up or to cancel it.
         // ignores the case transaction hash is same for retrying submit transaction.
-        if let Some(txns) = self.transactions.get_mut(&address) {
-            if let Some(current_version) =
-                txns.get_mut(&sequence_number.transaction_sequence_number)
+        let mut is_replacement = false;
+        if let Some(current_version) =
+            self.get_mempool_txn(&address, sequence_number.transaction_sequence_number)
+        {
+            if current_version.txn == txn.txn {
+                return MempoolStatus::new(MempoolStatusCode::Accepted);
+            }
+            let min_gas_price = self.min_replacement_gas_price(current_version.get_gas_price());
+            if txn.get_gas_price() < min_gas_price {
+                return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(format!(
+                    "Transaction already in mempool, replacing it requires a gas unit price of \
+                     at least {}",
+                    min_gas_price
+                ));
+            }
+            if let Some(current_version) = self
+                .transactions
+                .get_mut(&address)
+                .and_then(|txns| txns.remove(&sequence_number.transaction_sequence_number))
             {
-                if current_version.txn == txn.txn {
-                    return MempoolStatus::new(MempoolStatusCode::Accepted);
-                }
-                if current_version.txn.max_gas_amount() == txn.txn.max_gas_amount()
-                    && current_version.txn.payload() == txn.txn.payload()
-                    && current_version.txn.expiration_timestamp_secs()
-                        == txn.txn.expiration_timestamp_secs()
-                    && current_version.get_gas_price() < txn.get_gas_price()
-                {
-                    if let Some(txn) = txns.remove(&txn.sequence_info.transaction_sequence_number) {
-                        self.index_remove(&txn);
-                    }
-                } else {
-                    return MempoolStatus::new(MempoolStatusCode::InvalidUpdate)
-                        .with_message("Transaction already in mempool".to_string());
-                }
+                self.index_remove(&current_version);
+                counters::CORE_MEMPOOL_REPLACED_TXNS.inc();
+                is_replacement = true;
             }
         }
 
-        if self.check_is_full_after_eviction(
-            &txn,
-            sequence_number.account_sequence_number_type.min_seq(),
-        ) {
+        // A replacement takes the space of the transaction it removed.
+        if !is_replacement
+            && self.check_is_full_after_eviction(
+                &txn,
+                sequence_number.account_sequence_number_type.min_seq(),
+            )
+        {
             return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
                 "mempool size: {}, capacity: {}",
                 self.system_ttl_index.size(),
@@ -168,7 +237,7 @@ impl TransactionStore {
 
         if let Some(txns) = self.transactions.get_mut(&address) {
             // capacity check
-            if txns.len() >= self.capacity_per_user {
+            if !is_replacement && txns.len() >= self.capacity_per_user {
                 return MempoolStatus::new(MempoolStatusCode::TooManyTransactions).with_message(
                     format!(
                         "txns length: {} capacity per user: {}",
@@ -195,6 +264,14 @@ impl TransactionStore {
         MempoolStatus::new(MempoolStatusCode::Accepted)
     }
 
+    /// Minimum gas unit price of a transaction replacing one with `gas_price`: it has to be
+    /// higher by at least the configured bump, and strictly higher in any case.
+    fn min_replacement_gas_price(&self, gas_price: u64) -> u64 {
+        let bump = (gas_price as u128 * self.replacement_gas_price_bump_percent as u128 + 99) / 100;
+        let bump = u64::try_from(max(bump, 1)).unwrap_or(u64::MAX);
+        gas_price.saturating_add(bump)
+    }
+
     fn track_indices(&self) {
         counters::core_mempool_index_size(
             counters::SYSTEM_TTL_INDEX_LABEL,
@@ -223,7 +300,8 @@ impl TransactionStore {
     }
 
     /// Checks if Mempool is full.
-    /// If it's full, tries to free some space by evicting transactions from the ParkingLot.
+    /// If it's full, tries to free some space by evicting transactions from the ParkingLot, and
+    /// then, if enabled, ready transactions ranked lower than the new one.
     /// We only evict on attempt to insert a transaction that would be ready for broadcast upon insertion.
     fn check_is_full_after_eviction(
         &mut self,
@@ -246,13 +324,91 @@ impl TransactionStore {
                             txn.sequence_info.transaction_sequence_number
                         ))
                     );
+                    counters::CORE_MEMPOOL_EVICTED_TXNS
+                        .with_label_values(&[counters::EVICTED_PARKED_LABEL])
+                        .inc();
                     self.index_remove(&txn);
                 }
             }
+
+            if self.evict_lower_ranked_txns {
+                while self.system_ttl_index.size() >= self.capacity {
+                    let evicted = match self.lowest_ranked_txn(txn) {
+                        Some((address, sequence_number)) => self.evict_lower_ranked_txn(
+                            &address,
+                            sequence_number,
+                            txn.ranking_score,
+                        ),
+                        None => false,
+                    };
+                    if !evicted {
+                        break;
+                    }
+                }
+            }
         }
         self.system_ttl_index.size() >= self.capacity
     }
 
+    /// Returns the lowest ranked ready transaction, if it's ranked lower than `txn` and from
+    /// another sender.
+    fn lowest_ranked_txn(&self, txn: &MempoolTransaction) -> Option<TxnPointer> {
+        self.priority_index
+            .iter_lowest()
+            .take_while(|key| key.gas_ranking_score < txn.ranking_score)
+            .find(|key| key.address != txn.get_sender())
+            .map(TxnPointer::from)
+    }
+
+    /// Evicts a transaction of the account of a lower ranked ready transaction, if it's ranked
+    /// lower than `ranking_score`. For a sequential account, that's the transaction with the
+    /// highest sequence number, so that the others stay executable.
+    ///
+    /// Returns whether a transaction was evicted.
+    fn evict_lower_ranked_txn(
+        &mut self,
+        address: &AccountAddress,
+        sequence_number: u64,
+        ranking_score: u64,
+    ) -> bool {
+        let txns = match self.transactions.get_mut(address) {
+            Some(txns) => txns,
+            None => return false,
+        };
+        let evicted_sequence_number = match txns
+            .get(&sequence_number)
+            .map(|txn| txn.sequence_info.account_sequence_number_type)
+        {
+            Some(AccountSequenceInfo::CRSN { .. }) => sequence_number,
+            Some(AccountSequenceInfo::Sequential(_)) => match txns.keys().next_back() {
+                Some(last_sequence_number) => *last_sequence_number,
+                None => return false,
+            },
+            None => return false,
+        };
+        let is_lower_ranked = txns
+            .get(&evicted_sequence_number)
+            .map_or(false, |txn| txn.ranking_score < ranking_score);
+        if !is_lower_ranked {
+            return false;
+        }
+
+        if let Some(txn) = txns.remove(&evicted_sequence_number) {
+            debug!(
+                LogSchema::new(LogEntry::MempoolFullEvictedTxn).txns(TxnsLog::new_txn(
+                    txn.get_sender(),
+                    txn.sequence_info.transaction_sequence_number
+                ))
+            );
+            counters::CORE_MEMPOOL_EVICTED_TXNS
+                .with_label_values(&[counters::EVICTED_LOWER_RANKED_LABEL])
+                .inc();
+            self.index_remove(&txn);
+            return true;
+        }
+        false
+    }
+
     /// Check if a transaction would be ready for broadcast in mempool upon insertion (without inserting it).
     /// Two ways this can happen:
     /// 1. txn sequence number == curr_sequence_number
@@ -410,9 +566,23 @@ impl TransactionStore {
         self.timeline_index.remove(txn);
         self.parking_lot_index.remove(txn);
         self.hash_index.remove(&txn.get_committed_hash());
+        if let Some(removed_txns) = self.removed_txns.as_mut() {
+            removed_txns.push((
+                txn.get_sender(),
+                txn.sequence_info.transaction_sequence_number,
+            ));
+        }
         self.track_indices();
     }
 
+    /// Returns the transactions removed since the last call, if removals are tracked.
+    pub(crate) fn take_removed_txns(&mut self) -> Vec<TxnPointer> {
+        self.removed_txns
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
     /// Read `count` transactions from timeline since `timeline_id`.
     /// Returns block of transactions and new last_timeline_id.
     pub(crate) fn read_timeline(
@@ -565,6 +735,42 @@ impl TransactionStore {
         txns_log
     }
 
+    /// Returns the transactions of `account` ordered by sequence number, with whether they're
+    /// ready or parked.
+    pub(crate) fn get_account_transactions(
+        &self,
+        account: &AccountAddress,
+    ) -> Vec<(SignedTransaction, MempoolTransactionStatus)> {
+        self.transactions
+            .get(account)
+            .map(|txns| {
+                txns.iter()
+                    .map(|(seq_num, txn)| {
+                        let status = if self.parking_lot_index.contains(account, seq_num) {
+                            MempoolTransactionStatus::Parked
+                        } else {
+                            MempoolTransactionStatus::Ready
+                        };
+                        (txn.txn.clone(), status)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn get_stats(&self) -> MempoolStats {
+        MempoolStats {
+            capacity: self.capacity,
+            capacity_per_user: self.capacity_per_user,
+            system_ttl_index: self.system_ttl_index.size(),
+            expiration_time_index: self.expiration_time_index.size(),
+            priority_index: self.priority_index.size(),
+            parking_lot_index: self.parking_lot_index.size(),
+            timeline_index: self.timeline_index.size(),
+            transaction_hash_index: self.hash_index.len(),
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn get_parking_lot_size(&self) -> usize {
         self.parking_lot_index.size()
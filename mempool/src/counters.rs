@@ -28,6 +28,10 @@ pub const COMMIT_REJECTED_LABEL: &str = "commit_rejected";
 pub const GC_SYSTEM_TTL_LABEL: &str = "system_ttl";
 pub const GC_CLIENT_EXP_LABEL: &str = "client_expiration";
 
+// Core mempool eviction reason labels
+pub const EVICTED_PARKED_LABEL: &str = "parked";
+pub const EVICTED_LOWER_RANKED_LABEL: &str = "lower_ranked";
+
 // Core mempool GC txn status label
 pub const GC_ACTIVE_TXN_LABEL: &str = "active";
 pub const GC_PARKED_TXN_LABEL: &str = "parked";
@@ -52,6 +56,9 @@ pub const SUCCESS_LABEL: &str = "success";
 // Bounded executor task labels
 pub const CLIENT_EVENT_LABEL: &str = "client_event";
 pub const CLIENT_EVENT_GET_TXN_LABEL: &str = "client_event_get_txn";
+pub const CLIENT_EVENT_GET_ACCOUNT_TXNS_LABEL: &str = "client_event_get_account_txns";
+pub const CLIENT_EVENT_GET_STATS_LABEL: &str = "client_event_get_stats";
+pub const CLIENT_EVENT_GET_TXN_TRACE_LABEL: &str = "client_event_get_txn_trace";
 pub const RECONFIG_EVENT_LABEL: &str = "reconfig";
 pub const PEER_BROADCAST_EVENT_LABEL: &str = "peer_broadcast";
 
@@ -101,6 +108,34 @@ pub static CORE_MEMPOOL_REMOVED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter of txns evicted from a full core mempool to make room for a new txn, by reason
+pub static CORE_MEMPOOL_EVICTED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "core_mempool_evicted_txns_count",
+        "Number of txns evicted from a full core mempool to make room for a new txn",
+        &["reason"]
+    )
+    .unwrap()
+});
+
+/// Counter of txns replaced in core mempool by a txn with a higher gas price
+pub static CORE_MEMPOOL_REPLACED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "core_mempool_replaced_txns_count",
+        "Number of txns replaced in core mempool by a txn with a higher gas price"
+    )
+    .unwrap()
+});
+
+/// Counter of failures to write to the mempool journal
+pub static CORE_MEMPOOL_JOURNAL_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "core_mempool_journal_errors_count",
+        "Number of failed writes to the mempool journal"
+    )
+    .unwrap()
+});
+
 /// Counter tracking latency of txns reaching various stages in committing
 /// (e.g. time from txn entering core mempool to being pulled in consensus block)
 pub static CORE_MEMPOOL_TXN_COMMIT_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
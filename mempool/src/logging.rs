@@ -130,6 +130,9 @@ pub enum LogEntry {
     ReconfigUpdate,
     JsonRpc,
     GetTransaction,
+    GetTransactionTrace,
+    GetAccountTransactions,
+    GetStats,
     GetBlock,
     QuorumStore,
     StateSyncCommit,
@@ -147,6 +150,7 @@ pub enum LogEntry {
     DBError,
     UnexpectedNetworkMsg,
     MempoolSnapshot,
+    Journal,
 }
 
 #[derive(Clone, Copy, Serialize)]
@@ -4,6 +4,7 @@
 //! Interface between Mempool and Network layers.
 
 use crate::{
+    core_mempool::CoreMempool,
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     shared_mempool::{
@@ -264,6 +265,7 @@ impl MempoolNetworkInterface {
 
     pub fn process_broadcast_ack(
         &self,
+        mempool: &Mutex<CoreMempool>,
         peer: PeerNetworkId,
         request_id_bytes: Vec<u8>,
         retry: bool,
@@ -326,6 +328,12 @@ impl MempoolNetworkInterface {
         if backoff {
             sync_state.broadcast_info.backoff_mode = true;
         }
+
+        // Release the sync states before taking the mempool lock.
+        drop(sync_states);
+        mempool
+            .lock()
+            .trace_ack(batch_id.0, batch_id.1, peer, retry, backoff);
     }
 
     pub fn is_backoff_mode(&self, peer: &PeerNetworkId) -> bool {
@@ -515,6 +523,9 @@ impl MempoolNetworkInterface {
         let send_time = SystemTime::now();
         self.send_batch(peer, batch_id, transactions).await?;
         let num_pending_broadcasts = self.update_broadcast_state(peer, batch_id, send_time)?;
+        smp.mempool
+            .lock()
+            .trace_broadcast(batch_id.0, batch_id.1, peer, scheduled_backoff);
         notify_subscribers(SharedMempoolNotification::Broadcast, &smp.subscribers);
 
         // Log all the metrics
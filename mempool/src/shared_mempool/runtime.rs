@@ -6,6 +6,7 @@ use crate::{
     network::{MempoolNetworkEvents, MempoolNetworkSender},
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator, snapshot_job},
+        tasks::process_journaled_transactions,
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
@@ -62,6 +63,7 @@ pub(crate) fn start_shared_mempool<V>(
         config.base.role,
         peer_metadata_storage,
     );
+    process_journaled_transactions(&smp);
 
     executor.spawn(coordinator(
         smp,
@@ -3,13 +3,16 @@
 
 //! Tasks that are executed by coordinators (short-lived compared to coordinators)
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TxnPointer},
+    core_mempool::{
+        trace_timestamp_usecs, CoreMempool, ReceiveEvent, TimelineState, TransactionTrace,
+        TxnPointer,
+    },
     counters,
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastError, MempoolSyncMsg},
     shared_mempool::types::{
-        notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
-        SubmissionStatusBundle,
+        notify_subscribers, MempoolStats, MempoolTransactionStatus, ScheduledBroadcast,
+        SharedMempool, SharedMempoolNotification, SubmissionStatusBundle,
     },
     QuorumStoreRequest, QuorumStoreResponse, SubmissionStatus,
 };
@@ -20,6 +23,7 @@ use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_metrics_core::HistogramTimer;
 use aptos_types::{
+    account_address::AccountAddress,
     mempool_status::{MempoolStatus, MempoolStatusCode},
     on_chain_config::OnChainConfigPayload,
     transaction::SignedTransaction,
@@ -106,7 +110,8 @@ pub(crate) async fn process_client_transaction_submission<V>(
 {
     timer.stop_and_record();
     let _timer = counters::process_txn_submit_latency_timer_client();
-    let statuses = process_incoming_transactions(&smp, vec![transaction], TimelineState::NotReady);
+    let statuses =
+        process_incoming_transactions(&smp, vec![transaction], TimelineState::NotReady, None);
     log_txn_process_results(&statuses, None);
 
     if let Some(status) = statuses.get(0) {
@@ -142,6 +147,68 @@ pub(crate) async fn process_client_get_transaction<V>(
     }
 }
 
+/// Processes get transaction trace by hash request by client.
+pub(crate) async fn process_client_get_transaction_trace<V>(
+    smp: SharedMempool<V>,
+    hash: HashValue,
+    callback: oneshot::Sender<Option<TransactionTrace>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let trace = smp.mempool.lock().get_trace(hash);
+
+    if callback.send(trace).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetTransactionTrace,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get account transactions request by client.
+pub(crate) async fn process_client_get_account_transactions<V>(
+    smp: SharedMempool<V>,
+    account: AccountAddress,
+    callback: oneshot::Sender<Vec<(SignedTransaction, MempoolTransactionStatus)>>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let txns = smp.mempool.lock().get_account_transactions(&account);
+
+    if callback.send(txns).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetAccountTransactions,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
+/// Processes get mempool stats request by client.
+pub(crate) async fn process_client_get_stats<V>(
+    smp: SharedMempool<V>,
+    callback: oneshot::Sender<MempoolStats>,
+    timer: HistogramTimer,
+) where
+    V: TransactionValidation,
+{
+    timer.stop_and_record();
+    let stats = smp.mempool.lock().get_stats();
+
+    if callback.send(stats).is_err() {
+        error!(LogSchema::event_log(
+            LogEntry::GetStats,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes transactions from other nodes.
 pub(crate) async fn process_transaction_broadcast<V>(
     smp: SharedMempool<V>,
@@ -155,7 +222,7 @@ pub(crate) async fn process_transaction_broadcast<V>(
 {
     timer.stop_and_record();
     let _timer = counters::process_txn_submit_latency_timer(peer.network_id());
-    let results = process_incoming_transactions(&smp, transactions, timeline_state);
+    let results = process_incoming_transactions(&smp, transactions, timeline_state, Some(peer));
     log_txn_process_results(&results, Some(peer));
 
     let ack_response = gen_ack_response(request_id, results, &peer);
@@ -227,11 +294,13 @@ pub(crate) fn process_incoming_transactions<V>(
     smp: &SharedMempool<V>,
     transactions: Vec<SignedTransaction>,
     timeline_state: TimelineState,
+    peer: Option<PeerNetworkId>,
 ) -> Vec<SubmissionStatusBundle>
 where
     V: TransactionValidation,
 {
     let mut statuses = vec![];
+    let received_usecs = trace_timestamp_usecs();
 
     let start_storage_read = Instant::now();
     // Track latency: fetching seq number
@@ -290,6 +359,7 @@ where
         .map(|t| smp.validator.read().validate_transaction(t.0.clone()))
         .collect::<Vec<_>>();
     vm_validation_timer.stop_and_record();
+    let validated_usecs = trace_timestamp_usecs();
     {
         let mut mempool = smp.mempool.lock();
         for (idx, (transaction, crsn_or_seqno)) in transactions.into_iter().enumerate() {
@@ -305,6 +375,16 @@ where
                             crsn_or_seqno,
                             timeline_state,
                         );
+                        if mempool_status.code == MempoolStatusCode::Accepted {
+                            mempool.trace_received(
+                                &transaction,
+                                ReceiveEvent {
+                                    timestamp_usecs: received_usecs,
+                                    peer,
+                                },
+                                validated_usecs,
+                            );
+                        }
                         statuses.push((transaction, (mempool_status, None)));
                     }
                     Some(validation_status) => {
@@ -324,6 +404,63 @@ where
     statuses
 }
 
+/// Re-submits the transactions restored from the mempool journal on startup.
+///
+/// They go through the same checks as newly received transactions, which drops the ones
+/// committed or invalidated while the node was down. The journal is then rewritten with the
+/// transactions that made it back into mempool, even if none did, so that it doesn't keep growing
+/// from records of past runs.
+pub(crate) fn process_journaled_transactions<V>(smp: &SharedMempool<V>)
+where
+    V: TransactionValidation,
+{
+    let journaled_transactions = smp.mempool.lock().take_journaled_transactions();
+    let num_journaled = journaled_transactions.len();
+
+    let now = aptos_infallible::duration_since_epoch();
+    let (live_transactions, expired_transactions): (Vec<_>, Vec<_>) = journaled_transactions
+        .into_iter()
+        .partition(|txn| txn.expiration_time > now);
+    let (client_transactions, peer_transactions): (Vec<_>, Vec<_>) = live_transactions
+        .into_iter()
+        .partition(|txn| txn.timeline_state != TimelineState::NonQualified);
+
+    let mut statuses = vec![];
+    for (transactions, timeline_state) in [
+        (client_transactions, TimelineState::NotReady),
+        (peer_transactions, TimelineState::NonQualified),
+    ] {
+        if !transactions.is_empty() {
+            let transactions = transactions.into_iter().map(|txn| txn.txn).collect();
+            statuses.extend(process_incoming_transactions(
+                smp,
+                transactions,
+                timeline_state,
+                None,
+            ));
+        }
+    }
+    let (restored, dropped): (Vec<_>, Vec<_>) = statuses
+        .iter()
+        .partition(|(_, (status, _))| status.code == MempoolStatusCode::Accepted);
+    info!(
+        LogSchema::new(LogEntry::Journal),
+        "Restored {} of the {} transactions of the mempool journal",
+        restored.len(),
+        num_journaled
+    );
+
+    let mempool = smp.mempool.lock();
+    mempool.journal_dropped_txns(
+        expired_transactions
+            .iter()
+            .map(|txn| &txn.txn)
+            .chain(dropped.into_iter().map(|(txn, _)| txn))
+            .map(|txn| (txn.sender(), txn.sequence_number())),
+    );
+    mempool.compact_journal();
+}
+
 fn log_txn_process_results(results: &[SubmissionStatusBundle], sender: Option<PeerNetworkId>) {
     let network = match sender {
         Some(peer) => peer.network_id().to_string(),
@@ -385,6 +522,7 @@ pub(crate) fn process_quorum_store_request<V: TransactionValidation>(
                 mempool.gc_by_expiration_time(curr_time);
                 let batch_size = cmp::max(max_batch_size, 1);
                 txns = mempool.get_batch(batch_size, exclude_transactions);
+                mempool.trace_pulled_by_consensus(&txns);
             }
             counters::mempool_service_transactions(counters::GET_BLOCK_LABEL, txns.len());
 
@@ -3,7 +3,8 @@
 
 //! Objects used by/related to shared mempool
 use crate::{
-    core_mempool::CoreMempool, network::MempoolNetworkInterface,
+    core_mempool::{CoreMempool, TransactionTrace},
+    network::MempoolNetworkInterface,
     shared_mempool::network::MempoolNetworkSender,
 };
 use anyhow::Result;
@@ -14,7 +15,8 @@ use aptos_config::{
 use aptos_crypto::HashValue;
 use aptos_infallible::{Mutex, RwLock};
 use aptos_types::{
-    mempool_status::MempoolStatus, transaction::SignedTransaction, vm_status::DiscardedVMStatus,
+    account_address::AccountAddress, mempool_status::MempoolStatus, transaction::SignedTransaction,
+    vm_status::DiscardedVMStatus,
 };
 use consensus_types::common::TransactionSummary;
 use futures::{
@@ -205,9 +207,39 @@ pub type SubmissionStatus = (MempoolStatus, Option<DiscardedVMStatus>);
 
 pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 
+/// Whether a transaction in mempool can be included in the next block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MempoolTransactionStatus {
+    /// The transaction is in the priority queue and can be pulled by consensus.
+    Ready,
+    /// The transaction is in the parking lot, waiting for a sequence number gap to be filled.
+    Parked,
+}
+
+/// Sizes of the core mempool indexes, along with its configured capacities.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MempoolStats {
+    pub capacity: usize,
+    pub capacity_per_user: usize,
+    pub system_ttl_index: usize,
+    pub expiration_time_index: usize,
+    pub priority_index: usize,
+    pub parking_lot_index: usize,
+    pub timeline_index: usize,
+    pub transaction_hash_index: usize,
+}
+
 pub enum MempoolClientRequest {
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
     GetTransactionByHash(HashValue, oneshot::Sender<Option<SignedTransaction>>),
+    /// Fetches the transactions of an account, ordered by sequence number.
+    GetAccountTransactions(
+        AccountAddress,
+        oneshot::Sender<Vec<(SignedTransaction, MempoolTransactionStatus)>>,
+    ),
+    GetMempoolStats(oneshot::Sender<MempoolStats>),
+    /// Fetches the lifecycle trace of a transaction in mempool or recently committed or rejected.
+    GetTransactionTrace(HashValue, oneshot::Sender<Option<TransactionTrace>>),
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
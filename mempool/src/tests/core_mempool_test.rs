@@ -2,17 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TtlCache},
+    core_mempool::{CoreMempool, ReceiveEvent, TimelineState, TtlCache},
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, exist_in_metrics_cache, setup_mempool,
-        TestTransaction,
+        ConsensusMock, TestTransaction,
     },
+    MempoolTransactionStatus,
+};
+use aptos_config::{
+    config::NodeConfig,
+    network_id::{NetworkId, PeerNetworkId},
 };
-use aptos_config::config::NodeConfig;
 use aptos_crypto::HashValue;
-use aptos_types::{account_config::AccountSequenceInfo, transaction::SignedTransaction};
+use aptos_temppath::TempPath;
+use aptos_types::{account_config::AccountSequenceInfo, transaction::SignedTransaction, PeerId};
 use std::{
     collections::HashSet,
+    fs::OpenOptions,
+    io::Write,
     time::{Duration, SystemTime},
 };
 
@@ -265,7 +272,7 @@ fn test_fail_for_same_gas_amount_and_not_same_expiration_time_crsn() {
 }
 
 #[test]
-fn test_update_invalid_transaction_in_mempool() {
+fn test_replace_transaction_with_different_max_gas_amount() {
     let (mut mempool, mut consensus) = setup_mempool();
     let txns = add_txns_to_mempool(
         &mut mempool,
@@ -275,18 +282,16 @@ fn test_update_invalid_transaction_in_mempool() {
         &TestTransaction::new(0, 0, 5),
         200,
     );
-    let _added_tnx = add_signed_txn(&mut mempool, updated_txn);
+    assert!(add_signed_txn(&mut mempool, updated_txn.clone()).is_ok());
 
-    // Since both gas price and mas gas amount were updated, the ordering should not have changed.
-    // The second transaction with gas price 2 should come first.
+    // The replacement with the higher gas price comes first, the replaced transaction is gone.
+    assert_eq!(consensus.get_block(&mut mempool, 1), vec![updated_txn]);
     assert_eq!(consensus.get_block(&mut mempool, 1), vec![txns[1].clone()]);
-    let next_tnx = consensus.get_block(&mut mempool, 1);
-    assert_eq!(next_tnx, vec![txns[0].clone()]);
-    assert_eq!(next_tnx[0].gas_unit_price(), 1);
+    assert!(consensus.get_block(&mut mempool, 1).is_empty());
 }
 
 #[test]
-fn test_update_invalid_transaction_in_mempool_crsn() {
+fn test_replace_transaction_with_different_max_gas_amount_crsn() {
     let (mut mempool, mut consensus) = setup_mempool();
     let txns = add_txns_to_mempool(
         &mut mempool,
@@ -299,14 +304,12 @@ fn test_update_invalid_transaction_in_mempool_crsn() {
         &TestTransaction::new(0, 0, 5).crsn(0),
         200,
     );
-    let _added_tnx = add_signed_txn(&mut mempool, updated_txn);
+    assert!(add_signed_txn(&mut mempool, updated_txn.clone()).is_ok());
 
-    // Since both gas price and mas gas amount were updated, the ordering should not have changed.
-    // The second transaction with gas price 2 should come first.
+    // The replacement with the higher gas price comes first, the replaced transaction is gone.
+    assert_eq!(consensus.get_block(&mut mempool, 1), vec![updated_txn]);
     assert_eq!(consensus.get_block(&mut mempool, 1), vec![txns[1].clone()]);
-    let next_tnx = consensus.get_block(&mut mempool, 1);
-    assert_eq!(next_tnx, vec![txns[0].clone()]);
-    assert_eq!(next_tnx[0].gas_unit_price(), 1);
+    assert!(consensus.get_block(&mut mempool, 1).is_empty());
 }
 
 #[test]
@@ -466,6 +469,43 @@ fn test_timeline() {
     assert_eq!(0, pool.get_parking_lot_size());
 }
 
+#[test]
+fn test_get_account_transactions() {
+    let mut pool = setup_mempool().0;
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(1, 0, 1),
+            TestTransaction::new(1, 1, 1),
+            TestTransaction::new(1, 3, 1),
+            TestTransaction::new(2, 0, 1),
+        ],
+    );
+    let txns: Vec<_> = pool
+        .get_account_transactions(&TestTransaction::get_address(1))
+        .into_iter()
+        .map(|(txn, status)| (txn.sequence_number(), status))
+        .collect();
+    assert_eq!(
+        txns,
+        vec![
+            (0, MempoolTransactionStatus::Ready),
+            (1, MempoolTransactionStatus::Ready),
+            (3, MempoolTransactionStatus::Parked),
+        ]
+    );
+    assert!(pool
+        .get_account_transactions(&TestTransaction::get_address(3))
+        .is_empty());
+
+    let stats = pool.get_stats();
+    assert_eq!(stats.system_ttl_index, 4);
+    assert_eq!(stats.transaction_hash_index, 4);
+    assert_eq!(stats.priority_index, 3);
+    assert_eq!(stats.parking_lot_index, 1);
+    assert_eq!(stats.timeline_index, 3);
+}
+
 #[test]
 fn test_capacity() {
     let mut config = NodeConfig::random();
@@ -677,3 +717,373 @@ fn test_get_transaction_by_hash_after_the_txn_is_updated() {
     let txn_by_new_hash = pool.get_by_hash(new_txn_hash);
     assert_eq!(txn_by_new_hash, Some(new_txn));
 }
+
+fn journaled_config(journal: &TempPath) -> NodeConfig {
+    let mut config = NodeConfig::random();
+    config.mempool.journal_path = Some(journal.path().to_path_buf());
+    config
+}
+
+fn journaled_transactions(pool: &mut CoreMempool) -> Vec<SignedTransaction> {
+    let mut txns: Vec<_> = pool
+        .take_journaled_transactions()
+        .into_iter()
+        .map(|journaled_txn| journaled_txn.txn)
+        .collect();
+    txns.sort_by_key(|txn| (txn.sender(), txn.sequence_number()));
+    txns
+}
+
+#[test]
+fn test_journal_restores_transactions() {
+    let journal = TempPath::new();
+    let config = journaled_config(&journal);
+
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(1, 0, 1),
+            TestTransaction::new(2, 0, 1),
+            TestTransaction::new(2, 1, 1),
+        ],
+    );
+    pool.remove_transaction(&TestTransaction::get_address(0), 0, false);
+    pool.remove_transaction(&TestTransaction::get_address(2), 0, true);
+    drop(pool);
+
+    // Only the transactions neither committed nor rejected are restored.
+    let mut expected = vec![txns[1].clone(), txns[2].clone()];
+    expected.sort_by_key(|txn| (txn.sender(), txn.sequence_number()));
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(journaled_transactions(&mut pool), expected);
+
+    // Compaction keeps the transactions still in mempool, and the restored ones until they are
+    // dropped.
+    add_txns_to_mempool(&mut pool, vec![TestTransaction::new(3, 0, 1)]);
+    pool.journal_dropped_txns(vec![(txns[1].sender(), txns[1].sequence_number())]);
+    pool.compact_journal();
+    drop(pool);
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(journaled_transactions(&mut pool).len(), 2);
+}
+
+#[test]
+fn test_journal_ignores_incomplete_record() {
+    let journal = TempPath::new();
+    let config = journaled_config(&journal);
+
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 1)]);
+    drop(pool);
+
+    // Simulate a crash in the middle of appending a record.
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(journal.path())
+        .unwrap();
+    file.write_all(&100u32.to_le_bytes()).unwrap();
+    file.write_all(&[0u8; 10]).unwrap();
+    drop(file);
+
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(journaled_transactions(&mut pool), txns);
+
+    // The incomplete record was truncated, so the records appended since are read back.
+    let mut txns = txns;
+    txns.extend(add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(1, 0, 1)],
+    ));
+    drop(pool);
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(journaled_transactions(&mut pool), txns);
+}
+
+#[test]
+fn test_journal_ignores_oversized_record() {
+    let journal = TempPath::new();
+    let config = journaled_config(&journal);
+
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 1)]);
+    drop(pool);
+
+    // A corrupted length mustn't be allocated.
+    let mut file = OpenOptions::new()
+        .append(true)
+        .open(journal.path())
+        .unwrap();
+    file.write_all(&u32::MAX.to_le_bytes()).unwrap();
+    drop(file);
+
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(journaled_transactions(&mut pool), txns);
+}
+
+#[test]
+fn test_journal_records_expired_transactions() {
+    let journal = TempPath::new();
+    let config = journaled_config(&journal);
+
+    let mut pool = CoreMempool::new(&config);
+    let mut txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(1, 0, 1)]);
+    let txn = TestTransaction::new(1, 1, 1).make_signed_transaction_with_expiration_time(0);
+    pool.add_txn(
+        txn,
+        0,
+        1,
+        AccountSequenceInfo::Sequential(0),
+        TimelineState::NotReady,
+    );
+    txns.extend(add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(1, 2, 1)],
+    ));
+    pool.gc_by_expiration_time(Duration::from_secs(1));
+    drop(pool);
+
+    // Only the expired transaction is dropped from the journal.
+    let mut pool = CoreMempool::new(&config);
+    assert_eq!(journaled_transactions(&mut pool), txns);
+}
+
+#[test]
+fn test_replacement_requires_gas_price_bump() {
+    let (mut pool, _) = setup_mempool();
+    add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 100)]);
+
+    // The default bump is 10%.
+    let txn = TestTransaction::new(0, 0, 109).make_signed_transaction_with_max_gas_amount(200);
+    assert!(add_signed_txn(&mut pool, txn).is_err());
+    let txn = TestTransaction::new(0, 0, 110).make_signed_transaction_with_max_gas_amount(200);
+    assert!(add_signed_txn(&mut pool, txn.clone()).is_ok());
+    assert_eq!(
+        pool.get_by_hash(txn.clone().committed_hash()),
+        Some(txn.clone())
+    );
+
+    // Even without a bump, the gas price has to increase.
+    let mut config = NodeConfig::random();
+    config.mempool.replacement_gas_price_bump_percent = 0;
+    let mut pool = CoreMempool::new(&config);
+    add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 0)]);
+    let txn = TestTransaction::new(0, 0, 0).make_signed_transaction_with_max_gas_amount(200);
+    assert!(add_signed_txn(&mut pool, txn).is_err());
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction_with_max_gas_amount(200);
+    assert!(add_signed_txn(&mut pool, txn).is_ok());
+}
+
+#[test]
+fn test_replaced_transaction_removed_from_timeline() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 2;
+    let mut pool = CoreMempool::new(&config);
+    add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(0, 0, 1), TestTransaction::new(1, 0, 1)],
+    );
+
+    // Replacing a transaction is allowed even when mempool is full.
+    let txn = TestTransaction::new(0, 0, 10).make_signed_transaction_with_max_gas_amount(200);
+    assert!(add_signed_txn(&mut pool, txn.clone()).is_ok());
+
+    // The replacement gets a new position in the timeline, so that it's broadcast again.
+    let (timeline, _) = pool.read_timeline(0, 10);
+    assert_eq!(timeline.len(), 2);
+    assert_eq!(timeline[1], txn);
+    let (timeline, _) = pool.read_timeline(2, 10);
+    assert_eq!(timeline, vec![txn]);
+}
+
+#[test]
+fn test_evict_lower_ranked_txns() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    config.mempool.evict_lower_ranked_txns = true;
+    let mut pool = CoreMempool::new(&config);
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(1, 0, 5),
+        ],
+    );
+
+    // A higher ranked txn evicts the lowest ranked one.
+    add_txn(&mut pool, TestTransaction::new(2, 0, 10)).unwrap();
+    let mut txns: Vec<_> = pool
+        .get_batch(10, HashSet::new())
+        .iter()
+        .map(|txn| (txn.sender(), txn.sequence_number()))
+        .collect();
+    txns.sort_unstable();
+    let mut expected = vec![
+        (TestTransaction::get_address(0), 0),
+        (TestTransaction::get_address(1), 0),
+        (TestTransaction::get_address(2), 0),
+    ];
+    expected.sort_unstable();
+    assert_eq!(txns, expected);
+
+    // A txn ranked as low as the ones in mempool doesn't evict them.
+    assert!(add_txn(&mut pool, TestTransaction::new(3, 0, 1)).is_err());
+}
+
+#[test]
+fn test_evict_lower_ranked_txn_evicts_highest_sequence_number() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 3;
+    config.mempool.evict_lower_ranked_txns = true;
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 1),
+            TestTransaction::new(0, 2, 1),
+        ],
+    );
+
+    // Only the last txn of the account is evicted, the others stay executable.
+    let new_txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(1, 0, 10)]);
+    let mut expected = vec![txns[0].clone(), txns[1].clone(), new_txns[0].clone()];
+    expected.sort_by_key(|txn| (txn.sender(), txn.sequence_number()));
+    let mut batch = pool.get_batch(10, HashSet::new());
+    batch.sort_by_key(|txn| (txn.sender(), txn.sequence_number()));
+    assert_eq!(batch, expected);
+    assert_eq!(pool.get_stats().system_ttl_index, 3);
+}
+
+#[test]
+fn test_evict_lower_ranked_txn_keeps_higher_ranked_highest_sequence_number() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 2;
+    config.mempool.evict_lower_ranked_txns = true;
+    let mut pool = CoreMempool::new(&config);
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 1),
+            TestTransaction::new(0, 1, 100),
+        ],
+    );
+
+    // The last txn of the lowest ranked account outranks the new txn, so nothing is evicted.
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 0, 10)).is_err());
+    let mut batch = pool.get_batch(10, HashSet::new());
+    batch.sort_by_key(|txn| (txn.sender(), txn.sequence_number()));
+    assert_eq!(batch, txns);
+}
+
+#[test]
+fn test_evict_lower_ranked_txns_disabled_by_default() {
+    let mut config = NodeConfig::random();
+    config.mempool.capacity = 1;
+    let mut pool = CoreMempool::new(&config);
+    add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 1)]);
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 0, 10)).is_err());
+}
+
+#[test]
+fn test_get_batch_sender_share() {
+    let mut config = NodeConfig::random();
+    config.mempool.max_sender_batch_share_percent = Some(50);
+    let mut pool = CoreMempool::new(&config);
+    add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 10),
+            TestTransaction::new(0, 1, 10),
+            TestTransaction::new(0, 2, 10),
+            TestTransaction::new(0, 3, 10),
+            TestTransaction::new(1, 0, 1),
+            TestTransaction::new(1, 1, 1),
+        ],
+    );
+
+    // The higher paying sender can only fill half of the batch.
+    let batch = pool.get_batch(4, HashSet::new());
+    let num_txns_of_sender = |address| {
+        batch
+            .iter()
+            .filter(|txn| txn.sender() == TestTransaction::get_address(address))
+            .count()
+    };
+    assert_eq!(num_txns_of_sender(0), 2);
+    assert_eq!(num_txns_of_sender(1), 2);
+}
+
+#[test]
+fn test_transaction_trace() {
+    let mut config = NodeConfig::random();
+    config.mempool.trace_transactions = true;
+    let mut pool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
+    let txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 1)]);
+    let hash = txns[0].clone().committed_hash();
+    let peer = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+
+    pool.trace_received(
+        &txns[0],
+        ReceiveEvent {
+            timestamp_usecs: 1,
+            peer: None,
+        },
+        2,
+    );
+    // Only the first reception is kept.
+    pool.trace_received(
+        &txns[0],
+        ReceiveEvent {
+            timestamp_usecs: 3,
+            peer: Some(peer),
+        },
+        4,
+    );
+    pool.trace_broadcast(0, 1, peer, true);
+    pool.trace_broadcast(0, 1, peer, false);
+    pool.trace_ack(0, 1, peer, false, false);
+    let block = consensus.get_block(&mut pool, 1);
+    pool.trace_pulled_by_consensus(&block);
+
+    let trace = pool.get_trace(hash).unwrap();
+    assert_eq!(
+        trace.received,
+        Some(ReceiveEvent {
+            timestamp_usecs: 1,
+            peer: None,
+        })
+    );
+    assert_eq!(trace.validated_usecs, Some(2));
+    // Repeated events only keep their count and first and last occurrences.
+    let broadcasts = trace.broadcasts.as_ref().unwrap();
+    assert_eq!(broadcasts.count, 2);
+    assert_eq!(broadcasts.first.peer, peer);
+    assert!(broadcasts.first.backoff);
+    assert!(!broadcasts.last.backoff);
+    assert_eq!(trace.acks.as_ref().unwrap().count, 1);
+    assert_eq!(trace.pulled_by_consensus_usecs.as_ref().unwrap().count, 1);
+    assert_eq!(trace.committed_usecs, None);
+
+    // The trace is kept after the transaction leaves mempool.
+    pool.remove_transaction(&txns[0].sender(), 0, false);
+    assert!(pool.get_by_hash(hash).is_none());
+    let committed_trace = pool.get_trace(hash).unwrap();
+    assert!(committed_trace.committed_usecs.is_some());
+    assert_eq!(committed_trace.broadcasts, trace.broadcasts);
+
+    assert!(pool.get_trace(HashValue::random()).is_none());
+}
+
+#[test]
+fn test_transaction_trace_disabled_by_default() {
+    let (mut pool, _) = setup_mempool();
+    let txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(0, 0, 1)]);
+    let peer = PeerNetworkId::new(NetworkId::Validator, PeerId::random());
+    pool.trace_broadcast(0, 1, peer, false);
+    assert!(pool.get_trace(txns[0].clone().committed_hash()).is_none());
+}
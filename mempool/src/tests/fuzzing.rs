@@ -47,7 +47,7 @@ pub fn test_mempool_process_incoming_transactions_impl(
         PeerMetadataStorage::new(&[NetworkId::Validator]),
     );
 
-    let _ = tasks::process_incoming_transactions(&smp, txns, timeline_state);
+    let _ = tasks::process_incoming_transactions(&smp, txns, timeline_state, None);
 }
 
 proptest! {
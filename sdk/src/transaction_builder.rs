@@ -148,6 +148,14 @@ impl TransactionFactory {
         ))
     }
 
+    /// Replaces the authentication key of the sender, so that only the new key can sign its
+    /// transactions.  The address of the sender doesn't change.
+    pub fn rotate_authentication_key(&self, new_auth_key: AuthenticationKey) -> TransactionBuilder {
+        self.payload(aptos_stdlib::encode_account_rotate_authentication_key(
+            new_auth_key.to_vec(),
+        ))
+    }
+
     pub fn transfer(&self, to: AccountAddress, amount: u64) -> TransactionBuilder {
         self.payload(aptos_stdlib::encode_test_coin_transfer(to, amount))
     }
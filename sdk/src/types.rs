@@ -3,8 +3,9 @@
 
 use crate::{
     crypto::{
-        ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
-        traits::Uniform,
+        ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+        multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
+        traits::{SigningKey, Uniform},
     },
     transaction_builder::TransactionBuilder,
     types::{
@@ -12,6 +13,8 @@ use crate::{
         transaction::{authenticator::AuthenticationKey, RawTransaction, SignedTransaction},
     },
 };
+use anyhow::{bail, format_err, Result};
+use serde::{Deserialize, Serialize};
 
 pub use aptos_types::*;
 
@@ -164,3 +167,110 @@ impl From<Ed25519PrivateKey> for AccountKey {
         Self::from_private_key(private_key)
     }
 }
+
+/// An account controlled by a K-of-N `MultiEd25519` key.
+///
+/// The private keys are held by different parties, so its transactions are signed by
+/// collecting a [`PartialSignature`] from at least K of them and aggregating those.
+#[derive(Debug)]
+pub struct MultiSigAccount {
+    /// Address of the account.
+    address: AccountAddress,
+    /// Public keys and threshold of the account.
+    public_key: MultiEd25519PublicKey,
+    /// Latest known sequence number of the account, it can be different from validator.
+    sequence_number: u64,
+}
+
+impl MultiSigAccount {
+    pub fn new(
+        address: AccountAddress,
+        public_key: MultiEd25519PublicKey,
+        sequence_number: u64,
+    ) -> Self {
+        Self {
+            address,
+            public_key,
+            sequence_number,
+        }
+    }
+
+    /// Creates the account at the address derived from the `public_key`.
+    pub fn from_public_key(public_key: MultiEd25519PublicKey, sequence_number: u64) -> Self {
+        let address = AuthenticationKey::multi_ed25519(&public_key).derived_address();
+        Self::new(address, public_key, sequence_number)
+    }
+
+    /// Signs the transaction with one of the keys of the account.
+    pub fn partial_sign(
+        &self,
+        txn: &RawTransaction,
+        private_key: &Ed25519PrivateKey,
+    ) -> Result<PartialSignature> {
+        let public_key = Ed25519PublicKey::from(private_key);
+        let index = self
+            .public_key
+            .public_keys()
+            .iter()
+            .position(|key| key == &public_key)
+            .ok_or_else(|| format_err!("{} is not a key of the account", public_key))?;
+
+        Ok(PartialSignature {
+            index: index as u8,
+            signature: private_key.sign(txn),
+        })
+    }
+
+    /// Aggregates the partial signatures into a submittable transaction, failing if any
+    /// signature is invalid or if there are fewer signatures than the threshold.
+    pub fn aggregate_signatures(
+        &self,
+        txn: RawTransaction,
+        signatures: Vec<PartialSignature>,
+    ) -> Result<SignedTransaction> {
+        let threshold = *self.public_key.threshold() as usize;
+        if signatures.len() < threshold {
+            bail!(
+                "{} signatures are needed, only {} were given",
+                threshold,
+                signatures.len()
+            );
+        }
+        let signature = MultiEd25519Signature::new(
+            signatures
+                .into_iter()
+                .map(|partial| (partial.signature, partial.index))
+                .collect(),
+        )?;
+
+        let signed_txn = SignedTransaction::new_multisig(txn, self.public_key.clone(), signature);
+        Ok(signed_txn.check_signature()?.into_inner())
+    }
+
+    pub fn address(&self) -> AccountAddress {
+        self.address
+    }
+
+    pub fn public_key(&self) -> &MultiEd25519PublicKey {
+        &self.public_key
+    }
+
+    pub fn authentication_key(&self) -> AuthenticationKey {
+        AuthenticationKey::multi_ed25519(&self.public_key)
+    }
+
+    pub fn sequence_number(&self) -> u64 {
+        self.sequence_number
+    }
+
+    pub fn sequence_number_mut(&mut self) -> &mut u64 {
+        &mut self.sequence_number
+    }
+}
+
+/// A signature of a transaction by the key at `index` of a [`MultiSigAccount`].
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PartialSignature {
+    pub index: u8,
+    pub signature: Ed25519Signature,
+}
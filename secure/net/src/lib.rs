@@ -14,7 +14,17 @@
 //!
 //! Internally both the client and server leverage a NetworkStream that communications in blocks
 //! where a block is a length prefixed array of bytes.
-
+//!
+//! Optionally, a connection can be authenticated and encrypted with a Noise IK handshake. The
+//! client pins the public key of the server and the server only accepts clients presenting the
+//! pinned public key. Every block exchanged after the handshake is encrypted with the resulting
+//! Noise session. The client sends a timestamp in the handshake payload, which must strictly
+//! increase across handshakes, so that a recorded handshake can't be replayed to the server.
+
+use aptos_crypto::{
+    noise::{self, NoiseConfig, NoiseError, NoiseSession},
+    x25519,
+};
 use aptos_logger::{info, trace, warn, Schema};
 use aptos_secure_push_metrics::{register_int_counter_vec, IntCounterVec};
 use once_cell::sync::Lazy;
@@ -55,6 +65,7 @@ enum LogEvent {
     ConnectionAttempt,
     ConnectionSuccessful,
     ConnectionFailed,
+    HandshakeFailed,
     DisconnectedPeerOnRead,
     DisconnectedPeerOnWrite,
     Shutdown,
@@ -89,6 +100,7 @@ static EVENT_COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
 #[serde(rename_all = "snake_case")]
 enum Method {
     Connect,
+    Handshake,
     Read,
     Write,
 }
@@ -97,6 +109,7 @@ impl Method {
     fn as_str(&self) -> &'static str {
         match self {
             Method::Connect => "connect",
+            Method::Handshake => "handshake",
             Method::Read => "read",
             Method::Write => "write",
         }
@@ -138,20 +151,116 @@ pub enum Error {
     AlreadyShutdown,
     #[error("Found data that is too large to decode: {0}")]
     DataTooLarge(usize),
+    #[error("Missing anti-replay timestamp in the handshake payload")]
+    MissingAntiReplayTimestamp,
     #[error("Internal network error:")]
     NetworkError(#[from] std::io::Error),
     #[error("No active stream")]
     NoActiveStream,
+    #[error("Noise error: {0}")]
+    NoiseError(#[from] NoiseError),
     #[error("Overflow error: {0}")]
     OverflowError(String),
     #[error("Remote stream cleanly closed")]
     RemoteStreamClosed,
+    #[error("Replayed handshake, timestamp {0} isn't after the last one")]
+    ReplayedHandshake(u64),
+    #[error("Unexpected remote public key: {0}")]
+    UnexpectedRemoteKey(x25519::PublicKey),
+}
+
+/// The prologue mixed into every handshake, binding it to this protocol.
+const NOISE_PROLOGUE: &[u8] = b"aptos-secure-net";
+
+/// The largest plaintext that fits in a single Noise message.
+const MAX_NOISE_PLAINTEXT: usize = noise::decrypted_len(noise::MAX_SIZE_NOISE_MSG);
+
+/// Size of the handshake payload, a little endian timestamp in milliseconds since the Unix epoch.
+const TIMESTAMP_SIZE: usize = 8;
+
+/// Current time in milliseconds since the Unix epoch, sent in the client handshake.
+fn timestamp_millis() -> u64 {
+    time::SystemTime::now()
+        .duration_since(time::UNIX_EPOCH)
+        .expect("System time is before the Unix epoch")
+        .as_millis() as u64
+}
+
+/// The local key and the pinned remote public key used to establish a Noise IK session.
+struct NoiseKeys {
+    config: NoiseConfig,
+    remote_public_key: x25519::PublicKey,
+    /// Timestamp of the last handshake accepted from the client on the server side, starting
+    /// just before the time the keys were created
+    last_client_timestamp: u64,
+}
+
+impl NoiseKeys {
+    fn new(private_key: x25519::PrivateKey, remote_public_key: x25519::PublicKey) -> Self {
+        Self {
+            config: NoiseConfig::new(private_key),
+            remote_public_key,
+            // Accepts handshakes from the same millisecond the keys were created in
+            last_client_timestamp: timestamp_millis() - 1,
+        }
+    }
+
+    /// Initiates a handshake with the pinned server. The IK pattern only completes if the server
+    /// holds the private key of the pinned public key.
+    fn initiate(&self, stream: &mut NetworkStream, timestamp: u64) -> Result<NoiseSession, Error> {
+        let mut init_message = vec![0; noise::handshake_init_msg_len(TIMESTAMP_SIZE)];
+        let state = self.config.initiate_connection(
+            &mut rand::rngs::OsRng,
+            NOISE_PROLOGUE,
+            self.remote_public_key,
+            Some(&timestamp.to_le_bytes()),
+            &mut init_message,
+        )?;
+        stream.write(&init_message)?;
+        let response = stream.read()?;
+        let (_, session) = self.config.finalize_connection(state, &response)?;
+        Ok(session)
+    }
+
+    /// Responds to a client handshake, rejecting any client other than the pinned one and any
+    /// handshake whose timestamp isn't after the last accepted one.
+    ///
+    /// The watermark starts at the time the keys were created rather than being persisted, so a
+    /// handshake recorded before the server restarted can't be replayed as long as the server's
+    /// clock doesn't go backwards. In turn, a client whose clock is behind the server's is
+    /// rejected until it catches up with the time the server started.
+    fn respond(&mut self, stream: &mut NetworkStream) -> Result<NoiseSession, Error> {
+        let init_message = stream.read()?;
+        let (remote_public_key, state, payload) = self
+            .config
+            .parse_client_init_message(NOISE_PROLOGUE, &init_message)?;
+        if remote_public_key != self.remote_public_key {
+            return Err(Error::UnexpectedRemoteKey(remote_public_key));
+        }
+        if payload.len() != TIMESTAMP_SIZE {
+            return Err(Error::MissingAntiReplayTimestamp);
+        }
+        let mut timestamp = [0u8; TIMESTAMP_SIZE];
+        timestamp.copy_from_slice(&payload);
+        let timestamp = u64::from_le_bytes(timestamp);
+        if timestamp <= self.last_client_timestamp {
+            return Err(Error::ReplayedHandshake(timestamp));
+        }
+        self.last_client_timestamp = timestamp;
+        let mut response = vec![0; noise::handshake_resp_msg_len(0)];
+        let session =
+            self.config
+                .respond_to_client(&mut rand::rngs::OsRng, state, None, &mut response)?;
+        stream.write(&response)?;
+        Ok(session)
+    }
 }
 
 pub struct NetworkClient {
     service: &'static str,
     server: SocketAddr,
     stream: Option<NetworkStream>,
+    noise: Option<NoiseKeys>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
 }
@@ -162,10 +271,22 @@ impl NetworkClient {
             service,
             server,
             stream: None,
+            noise: None,
             timeout_ms,
         }
     }
 
+    /// Authenticate and encrypt the connection with a Noise IK handshake, only accepting a server
+    /// holding the private key of `server_public_key`.
+    pub fn with_noise(
+        mut self,
+        private_key: x25519::PrivateKey,
+        server_public_key: x25519::PublicKey,
+    ) -> Self {
+        self.noise = Some(NoiseKeys::new(private_key, server_public_key));
+        self
+    }
+
     fn increment_counter(&self, method: Method, result: MethodResult) {
         increment_counter(self.service, NetworkMode::Client, method, result)
     }
@@ -257,7 +378,28 @@ impl NetworkClient {
 
             let stream = stream?;
             stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, self.server, self.timeout_ms));
+            let mut stream = NetworkStream::new(stream, self.server, self.timeout_ms);
+            if let Some(noise) = &self.noise {
+                self.increment_counter(Method::Handshake, MethodResult::Query);
+                match noise.initiate(&mut stream, timestamp_millis()) {
+                    Ok(session) => {
+                        self.increment_counter(Method::Handshake, MethodResult::Success);
+                        stream.session = Some(session);
+                    }
+                    Err(err) => {
+                        self.increment_counter(Method::Handshake, MethodResult::Failure);
+                        warn!(SecureNetLogSchema::new(
+                            self.service,
+                            NetworkMode::Client,
+                            LogEvent::HandshakeFailed,
+                        )
+                        .error(&err)
+                        .remote_peer(&self.server));
+                        return Err(err);
+                    }
+                }
+            }
+            self.stream = Some(stream);
             self.increment_counter(Method::Connect, MethodResult::Success);
             info!(SecureNetLogSchema::new(
                 self.service,
@@ -275,6 +417,7 @@ pub struct NetworkServer {
     service: &'static str,
     listener: Option<TcpListener>,
     stream: Option<NetworkStream>,
+    noise: Option<NoiseKeys>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
 }
@@ -286,10 +429,22 @@ impl NetworkServer {
             service,
             listener: Some(listener.unwrap()),
             stream: None,
+            noise: None,
             timeout_ms,
         }
     }
 
+    /// Authenticate and encrypt connections with a Noise IK handshake, only accepting a client
+    /// holding the private key of `client_public_key`.
+    pub fn with_noise(
+        mut self,
+        private_key: x25519::PrivateKey,
+        client_public_key: x25519::PublicKey,
+    ) -> Self {
+        self.noise = Some(NoiseKeys::new(private_key, client_public_key));
+        self
+    }
+
     fn increment_counter(&self, method: Method, result: MethodResult) {
         increment_counter(self.service, NetworkMode::Server, method, result)
     }
@@ -399,7 +554,30 @@ impl NetworkServer {
             .remote_peer(&stream_addr));
 
             stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, stream_addr, self.timeout_ms));
+            let mut stream = NetworkStream::new(stream, stream_addr, self.timeout_ms);
+            if let Some(noise) = self.noise.as_mut() {
+                let result = noise.respond(&mut stream);
+                self.increment_counter(Method::Handshake, MethodResult::Query);
+                match result {
+                    Ok(session) => {
+                        self.increment_counter(Method::Handshake, MethodResult::Success);
+                        stream.session = Some(session);
+                    }
+                    Err(err) => {
+                        self.increment_counter(Method::Handshake, MethodResult::Failure);
+                        warn!(SecureNetLogSchema::new(
+                            self.service,
+                            NetworkMode::Server,
+                            LogEvent::HandshakeFailed,
+                        )
+                        .error(&err)
+                        .remote_peer(&stream_addr));
+                        // Dropping the stream closes the connection to the rejected client
+                        return Err(err);
+                    }
+                }
+            }
+            self.stream = Some(stream);
         }
 
         self.stream.as_mut().ok_or(Error::NoActiveStream)
@@ -411,6 +589,7 @@ struct NetworkStream {
     remote: SocketAddr,
     buffer: Vec<u8>,
     temp_buffer: [u8; 1024],
+    session: Option<NoiseSession>,
 }
 
 impl NetworkStream {
@@ -425,11 +604,21 @@ impl NetworkStream {
             remote,
             buffer: Vec::new(),
             temp_buffer: [0; 1024],
+            session: None,
         }
     }
 
-    /// Blocking read until able to successfully read an entire message
+    /// Blocking read until able to successfully read an entire message, decrypting it if a Noise
+    /// session has been established
     pub fn read(&mut self) -> Result<Vec<u8>, Error> {
+        let message = self.read_message()?;
+        match self.session.as_mut() {
+            Some(session) => decrypt(session, message),
+            None => Ok(message),
+        }
+    }
+
+    fn read_message(&mut self) -> Result<Vec<u8>, Error> {
         let result = self.read_buffer();
         if !result.is_empty() {
             return Ok(result);
@@ -457,8 +646,19 @@ impl NetworkStream {
         Ok(self.stream.shutdown(Shutdown::Both)?)
     }
 
-    /// Blocking write until able to successfully send an entire message
+    /// Blocking write until able to successfully send an entire message, encrypting it if a Noise
+    /// session has been established
     pub fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        match self.session.as_mut() {
+            Some(session) => {
+                let ciphertext = encrypt(session, data)?;
+                self.write_message(&ciphertext)
+            }
+            None => self.write_message(data),
+        }
+    }
+
+    fn write_message(&mut self, data: &[u8]) -> Result<(), Error> {
         let u32_max = u32::max_value() as usize;
         if u32_max <= data.len() {
             return Err(Error::DataTooLarge(data.len()));
@@ -514,10 +714,39 @@ impl NetworkStream {
     }
 }
 
+/// Noise messages are limited in size, so larger payloads are encrypted as a sequence of maximum
+/// sized Noise messages. Even an empty payload produces a message carrying its authentication tag.
+fn encrypt(session: &mut NoiseSession, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut ciphertext = Vec::with_capacity(noise::encrypted_len(data.len()));
+    let mut chunks = data.chunks(MAX_NOISE_PLAINTEXT).peekable();
+    if chunks.peek().is_none() {
+        let tag = session.write_message_in_place(&mut [])?;
+        ciphertext.extend(tag);
+    }
+    for chunk in chunks {
+        let mut message = chunk.to_vec();
+        let tag = session.write_message_in_place(&mut message)?;
+        ciphertext.extend(message);
+        ciphertext.extend(tag);
+    }
+    Ok(ciphertext)
+}
+
+/// Reverses `encrypt` by decrypting each maximum sized Noise message in order.
+fn decrypt(session: &mut NoiseSession, mut ciphertext: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let mut plaintext = Vec::with_capacity(ciphertext.len());
+    for message in ciphertext.chunks_mut(noise::MAX_SIZE_NOISE_MSG) {
+        plaintext.extend_from_slice(session.read_message_in_place(message)?);
+    }
+    Ok(plaintext)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use aptos_config::utils;
+    use aptos_crypto::Uniform;
+    use rand::{rngs::StdRng, SeedableRng};
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
     /// Read, Write, Connect timeout in milliseconds.
@@ -656,4 +885,118 @@ mod test {
         let result2 = server2.read().unwrap();
         assert_eq!(data2, result2);
     }
+
+    #[test]
+    fn test_noise_ping() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let server_public_key = server_key.public_key();
+        let client_public_key = client_key.public_key();
+
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut server = NetworkServer::new("test", server_addr, TIMEOUT)
+            .with_noise(server_key, client_public_key);
+        let mut client = NetworkClient::new("test", server_addr, TIMEOUT)
+            .with_noise(client_key, server_public_key);
+
+        // The client blocks on the handshake until the server accepts it
+        let server_thread = thread::spawn(move || {
+            let result = server.read().unwrap();
+            server.write(&result).unwrap();
+            server
+        });
+
+        // Spans several Noise messages
+        let data: Vec<u8> = (0..3 * noise::MAX_SIZE_NOISE_MSG)
+            .map(|i| i as u8)
+            .collect();
+        client.write(&data).unwrap();
+        let result = client.read().unwrap();
+        assert_eq!(data, result);
+
+        let mut server = server_thread.join().unwrap();
+        let data = vec![];
+        client.write(&data).unwrap();
+        let result = server.read().unwrap();
+        assert_eq!(data, result);
+    }
+
+    #[test]
+    fn test_noise_unexpected_client_key() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let other_key = x25519::PrivateKey::generate(&mut rng);
+        let server_public_key = server_key.public_key();
+        let other_public_key = other_key.public_key();
+
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let mut server = NetworkServer::new("test", server_addr, TIMEOUT)
+            .with_noise(server_key, other_public_key);
+        let mut client = NetworkClient::new("test", server_addr, TIMEOUT)
+            .with_noise(client_key, server_public_key);
+
+        let server_thread = thread::spawn(move || server.read());
+
+        client.write(&[0, 1, 2, 3]).unwrap_err();
+        let result = server_thread.join().unwrap();
+        assert!(matches!(result, Err(Error::UnexpectedRemoteKey(_))));
+    }
+
+    /// Runs a handshake between `client` and `server` over a new connection, returning the
+    /// server keys and the outcome of the handshake on the server side.
+    fn noise_handshake(
+        client: &NoiseKeys,
+        server: NoiseKeys,
+        timestamp: u64,
+    ) -> (NoiseKeys, Result<NoiseSession, Error>) {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let listener = TcpListener::bind(server_addr).unwrap();
+        let server_thread = thread::spawn(move || {
+            let mut server = server;
+            let (stream, remote) = listener.accept().unwrap();
+            let mut stream = NetworkStream::new(stream, remote, TIMEOUT);
+            let result = server.respond(&mut stream);
+            (server, result)
+        });
+
+        let stream = TcpStream::connect(server_addr).unwrap();
+        let mut stream = NetworkStream::new(stream, server_addr, TIMEOUT);
+        // Fails when the server rejects the handshake
+        let _ = client.initiate(&mut stream, timestamp);
+        server_thread.join().unwrap()
+    }
+
+    #[test]
+    fn test_noise_replayed_handshake() {
+        let mut rng = StdRng::from_seed([0u8; 32]);
+        let server_key = x25519::PrivateKey::generate(&mut rng);
+        let client_key = x25519::PrivateKey::generate(&mut rng);
+        let server_public_key = server_key.public_key();
+        let client_public_key = client_key.public_key();
+        let start = timestamp_millis();
+        let client = NoiseKeys::new(client_key, server_public_key);
+        let server = NoiseKeys::new(server_key, client_public_key);
+
+        // Handshakes from before the server started, e.g. recorded before a restart, are rejected
+        let (server, result) = noise_handshake(&client, server, start - 1);
+        assert!(matches!(result, Err(Error::ReplayedHandshake(t)) if t == start - 1));
+
+        let timestamp = timestamp_millis() + 1000;
+        let (server, result) = noise_handshake(&client, server, timestamp);
+        result.unwrap();
+
+        // The timestamp must strictly increase
+        let (server, result) = noise_handshake(&client, server, timestamp);
+        assert!(matches!(result, Err(Error::ReplayedHandshake(t)) if t == timestamp));
+        let (server, result) = noise_handshake(&client, server, timestamp - 1);
+        assert!(matches!(result, Err(Error::ReplayedHandshake(t)) if t == timestamp - 1));
+
+        let (_, result) = noise_handshake(&client, server, timestamp + 1);
+        result.unwrap();
+    }
 }
@@ -13,6 +13,11 @@ pub struct DbStateView {
 }
 
 impl DbStateView {
+    /// The version the state is read at, `None` before genesis is committed.
+    pub fn version(&self) -> Option<Version> {
+        self.version
+    }
+
     fn get(&self, key: &StateKey) -> Result<Option<Vec<u8>>> {
         if let Some(version) = self.version {
             self.db
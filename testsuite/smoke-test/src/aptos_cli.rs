@@ -2,11 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::smoke_test_environment::new_local_swarm_with_aptos;
-use aptos::{account::create::DEFAULT_FUNDED_COINS, test::CliTestFramework};
+use aptos::{
+    account::create::DEFAULT_FUNDED_COINS, common::types::CliError, test::CliTestFramework,
+};
 use aptos_config::keys::ConfigKey;
-use aptos_crypto::ed25519::Ed25519PrivateKey;
+use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, Uniform};
 use aptos_faucet::FaucetArgs;
-use aptos_types::{account_config::aptos_root_address, chain_id::ChainId};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    account_config::aptos_root_address, chain_id::ChainId,
+    transaction::authenticator::AuthenticationKey,
+};
 use forge::{LocalSwarm, Node};
 use tokio::task::JoinHandle;
 
@@ -84,3 +90,205 @@ async fn test_account_flow() {
             .unwrap()
     );
 }
+
+#[tokio::test]
+async fn test_offline_transfer_flow() {
+    let (_swarm, cli) = setup_test(2).await;
+    let temp_dir = TempPath::new();
+    temp_dir.create_as_dir().unwrap();
+    let raw_txn_file = temp_dir.path().join("transfer.raw");
+    let signed_txn_file = temp_dir.path().join("transfer.signed");
+
+    // Build, sign and submit a transfer in separate steps
+    let transfer_amount = 100;
+    let raw_txn = cli
+        .build_transfer(
+            CliTestFramework::account_id(0),
+            1,
+            transfer_amount,
+            &raw_txn_file,
+        )
+        .await
+        .unwrap();
+    assert_eq!(CliTestFramework::account_id(0), raw_txn.sender);
+    let signed_txn = cli
+        .sign_transaction(0, &raw_txn_file, &signed_txn_file)
+        .await
+        .unwrap();
+    assert_eq!(raw_txn.sequence_number, signed_txn.sequence_number);
+    let response = cli.submit_transaction(&signed_txn_file).await.unwrap();
+    assert!(response.success);
+
+    let expected_sender_amount =
+        DEFAULT_FUNDED_COINS - response.gas_used.unwrap() - transfer_amount;
+    assert_eq!(
+        expected_sender_amount,
+        cli.wait_for_balance(0, expected_sender_amount)
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        DEFAULT_FUNDED_COINS + transfer_amount,
+        cli.wait_for_balance(1, DEFAULT_FUNDED_COINS + transfer_amount)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_multisig_transfer_flow() {
+    let (_swarm, cli) = setup_test(3).await;
+    let temp_dir = TempPath::new();
+    temp_dir.create_as_dir().unwrap();
+    let multisig_file = temp_dir.path().join("multisig.pub");
+    let raw_txn_file = temp_dir.path().join("transfer.raw");
+    let signed_txn_file = temp_dir.path().join("transfer.signed");
+
+    // Create a 2-of-3 multisig account
+    let multisig = cli
+        .create_multisig(&[0, 1, 2], 2, &multisig_file)
+        .await
+        .unwrap();
+    cli.fund_address(multisig.account_address).await.unwrap();
+
+    // Transfer coins from the multisig account, signed by the keys of two of the profiles
+    let transfer_amount = 100;
+    cli.build_transfer(multisig.account_address, 0, transfer_amount, &raw_txn_file)
+        .await
+        .unwrap();
+    let mut signature_files = Vec::new();
+    for index in [0, 2] {
+        let signature_file = temp_dir.path().join(format!("transfer.{}.sig", index));
+        cli.partial_sign_transaction(index, &multisig_file, &raw_txn_file, &signature_file)
+            .await
+            .unwrap();
+        signature_files.push(signature_file);
+    }
+
+    // A transaction of another account isn't signed
+    let other_raw_txn_file = temp_dir.path().join("other_transfer.raw");
+    let other_signature_file = temp_dir.path().join("other_transfer.sig");
+    cli.build_transfer(
+        CliTestFramework::account_id(1),
+        0,
+        transfer_amount,
+        &other_raw_txn_file,
+    )
+    .await
+    .unwrap();
+    assert!(matches!(
+        cli.partial_sign_transaction(
+            0,
+            &multisig_file,
+            &other_raw_txn_file,
+            &other_signature_file
+        )
+        .await,
+        Err(CliError::CommandArgumentError(_))
+    ));
+
+    // A single signature isn't enough
+    assert!(cli
+        .aggregate_signatures(
+            &multisig_file,
+            &raw_txn_file,
+            &[signature_files[0].as_path()],
+            &signed_txn_file,
+        )
+        .await
+        .is_err());
+
+    let signed_txn = cli
+        .aggregate_signatures(
+            &multisig_file,
+            &raw_txn_file,
+            &[signature_files[0].as_path(), signature_files[1].as_path()],
+            &signed_txn_file,
+        )
+        .await
+        .unwrap();
+    assert_eq!(multisig.account_address, signed_txn.sender);
+    let response = cli.submit_transaction(&signed_txn_file).await.unwrap();
+    assert!(response.success);
+
+    assert_eq!(
+        DEFAULT_FUNDED_COINS + transfer_amount,
+        cli.wait_for_balance(0, DEFAULT_FUNDED_COINS + transfer_amount)
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_rotate_key_flow() {
+    let (_swarm, cli) = setup_test(2).await;
+    let account = CliTestFramework::account_id(0);
+    let new_private_key = Ed25519PrivateKey::generate_for_testing();
+
+    let summary = cli.rotate_key(0, &new_private_key).await.unwrap();
+    assert_eq!(account, summary.account);
+    assert_eq!(
+        AuthenticationKey::ed25519(&new_private_key.public_key()),
+        summary.authentication_key
+    );
+    assert!(summary.profile_updated);
+
+    // The profile keeps its address, and signs with the new key
+    assert_eq!(account, CliTestFramework::account_id(0));
+    let gas_used = summary.transaction.gas_used.unwrap();
+    let transfer_amount = 100;
+    let response = cli.transfer_coins(0, 1, transfer_amount).await.unwrap();
+    let expected_sender_amount =
+        DEFAULT_FUNDED_COINS - gas_used - response.gas_used.unwrap() - transfer_amount;
+    assert_eq!(
+        expected_sender_amount,
+        cli.wait_for_balance(0, expected_sender_amount)
+            .await
+            .unwrap()
+    );
+
+    // Rotating to the key already in use is refused
+    assert!(cli.rotate_key(0, &new_private_key).await.is_err());
+}
+
+#[tokio::test]
+async fn test_inspect_transactions_and_events() {
+    let (_swarm, cli) = setup_test(2).await;
+    let initial_deposits = cli
+        .list_coin_events(1, "deposit_events", None)
+        .await
+        .unwrap()
+        .0
+        .len();
+
+    let transfer_amount = 100;
+    let num_transfers = 3;
+    let mut versions = Vec::new();
+    for _ in 0..num_transfers {
+        let response = cli.transfer_coins(0, 1, transfer_amount).await.unwrap();
+        versions.push(response.version.unwrap());
+    }
+
+    let shown = cli.show_transaction(versions[0]).await.unwrap();
+    assert_eq!(Some(versions[0]), shown.0.version());
+    assert!(shown.0.success());
+
+    // Transactions of the sender are listed from the oldest, or the newest when reversed
+    let listed = cli.list_transactions(0, None, false).await.unwrap();
+    assert_eq!(num_transfers, listed.0.len());
+    let newest = cli.list_transactions(0, Some(1), true).await.unwrap();
+    assert_eq!(1, newest.0.len());
+    assert_eq!(versions.last().copied(), newest.0[0].version());
+
+    // The receiver has one more deposit per transfer
+    let deposits = cli
+        .list_coin_events(1, "deposit_events", None)
+        .await
+        .unwrap();
+    assert_eq!(initial_deposits + num_transfers, deposits.0.len());
+    let first_deposits = cli
+        .list_coin_events(1, "deposit_events", Some(2))
+        .await
+        .unwrap();
+    assert_eq!(2, first_deposits.0.len());
+}
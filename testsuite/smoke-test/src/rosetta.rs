@@ -3,7 +3,7 @@
 
 use crate::{aptos_cli::launch_faucet, smoke_test_environment::new_local_swarm_with_aptos};
 use aptos::{account::create::DEFAULT_FUNDED_COINS, test::CliTestFramework};
-use aptos_config::config::ApiConfig;
+use aptos_config::config::{ApiConfig, DEFAULT_MAX_CONCURRENT_STREAMS};
 use aptos_rosetta::{client::RosettaClient, types::AccountBalanceResponse, CURRENCY, NUM_DECIMALS};
 use aptos_types::{account_address::AccountAddress, chain_id::ChainId};
 use forge::{LocalSwarm, Node};
@@ -35,6 +35,8 @@ pub async fn setup_test(num_nodes: usize) -> (LocalSwarm, CliTestFramework, Rose
         tls_cert_path: None,
         tls_key_path: None,
         content_length_limit: None,
+        rate_limit: None,
+        max_concurrent_streams: DEFAULT_MAX_CONCURRENT_STREAMS,
     };
 
     // Start the server